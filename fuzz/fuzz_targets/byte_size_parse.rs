@@ -0,0 +1,18 @@
+#![no_main]
+
+use fetchbox::config::ByteSize;
+use libfuzzer_sys::fuzz_target;
+use std::str::FromStr;
+
+// `ByteSize::from_str`/`from_str_si` must never panic on arbitrary input,
+// and any size that parses successfully must render back to something that
+// re-parses (see `ByteSize::to_human_readable`'s one-decimal-digit rounding).
+fuzz_target!(|input: &str| {
+    if let Ok(size) = ByteSize::from_str(input) {
+        let rendered = size.to_human_readable();
+        ByteSize::from_str(&rendered)
+            .unwrap_or_else(|e| panic!("{rendered:?} (from {input:?}) failed to re-parse: {e}"));
+    }
+
+    let _ = ByteSize::from_str_si(input);
+});
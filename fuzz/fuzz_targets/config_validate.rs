@@ -0,0 +1,14 @@
+#![no_main]
+
+use fetchbox::config::Config;
+use libfuzzer_sys::fuzz_target;
+
+// Any TOML document - malformed, adversarial, or merely unusual - must
+// either fail to deserialize or pass through `validate_all` without
+// panicking; neither path should ever reach a `panic!`/`unwrap` inside
+// `config::validation`.
+fuzz_target!(|input: &str| {
+    if let Ok(config) = toml::from_str::<Config>(input) {
+        let _ = config.validate_all();
+    }
+});
@@ -14,6 +14,13 @@ pub enum ApiError {
     UnsupportedJobType(String),
     #[error("resource not found: {0}")]
     NotFound(String),
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+    /// A `Range` request header couldn't be satisfied against an artifact of
+    /// `.0` bytes - malformed, multi-range, or out of bounds (see
+    /// [`crate::api::services::get_resource_artifact`])
+    #[error("range not satisfiable for {0} byte artifact")]
+    RangeNotSatisfiable(u64),
     #[error("internal error: {0}")]
     Internal(String),
 }
@@ -25,6 +32,8 @@ impl ApiError {
             ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             ApiError::UnsupportedJobType(_) => StatusCode::FORBIDDEN,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
             ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -35,6 +44,8 @@ impl ApiError {
             ApiError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
             ApiError::UnsupportedJobType(_) => "UNSUPPORTED_JOB_TYPE",
             ApiError::NotFound(_) => "NOT_FOUND",
+            ApiError::Unauthorized(_) => "UNAUTHORIZED",
+            ApiError::RangeNotSatisfiable(_) => "RANGE_NOT_SATISFIABLE",
             ApiError::Internal(_) => "INTERNAL_ERROR",
         }
     }
@@ -57,7 +57,7 @@ use std::collections::{BTreeMap, HashMap};
 
 pub type HeadersMap = BTreeMap<String, String>;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Manifest {
     pub manifest_version: String,
     pub storage: StorageConfig,
@@ -67,13 +67,13 @@ pub struct Manifest {
     pub attributes: Option<Value>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StorageConfig {
     pub manifest_file: String,
     pub resource_key_prefix: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Resource {
     pub name: String,
     pub url: String,
@@ -87,12 +87,22 @@ pub struct Resource {
 pub struct JobAcceptedResponse {
     pub job_id: String,
     pub manifest_key: String,
+    /// `sha256-<hex>` integrity string of the uploaded manifest (see
+    /// [`crate::storage::cas`]) - workers can re-verify this hash after
+    /// download and reject corrupted content
+    pub manifest_integrity: String,
     pub resource_count: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JobSnapshot {
     pub job_id: String,
+    /// Handler that produced this job's tasks, e.g. `"default"`/`"gallery"`
+    /// (see [`crate::handlers::HandlerRegistry`]). Also used to key
+    /// per-handler retention overrides (see
+    /// [`crate::config::RetentionConfig::overrides`]).
+    #[serde(default)]
+    pub job_type: String,
     pub status: JobStatus,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -102,12 +112,17 @@ pub struct JobSnapshot {
     pub resource_completed: usize,
     pub resource_failed: usize,
     pub manifest_key: String,
+    /// `sha256-<hex>` integrity string of the manifest at `manifest_key`
+    /// (see [`crate::storage::cas`]); empty for jobs ingested before this
+    /// field existed
+    #[serde(default)]
+    pub manifest_integrity: String,
     pub errors: Vec<JobError>,
     pub tenant: String,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum JobStatus {
     Queued,
@@ -125,15 +140,124 @@ pub struct JobError {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Query parameters for `GET /operators/jobs`
+///
+/// `cursor` is an opaque keyset pagination token: the `job_id` of the last
+/// entry the caller has already seen. Since job ids are time-ordered
+/// UUIDv7s, the next page is simply every job with a greater id.
+#[derive(Debug, Deserialize, Default)]
+pub struct JobListQuery {
+    pub tenant: Option<String>,
+    pub status: Option<JobStatus>,
+    #[serde(default, with = "chrono::serde::ts_seconds::option")]
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, with = "chrono::serde::ts_seconds::option")]
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+/// Response envelope for `GET /operators/jobs`
+#[derive(Debug, Serialize)]
+pub struct JobListResponse {
+    pub jobs: Vec<JobSnapshot>,
+    pub next_cursor: Option<String>,
+}
+
+/// A single entry in a job's dead-letter queue, returned by
+/// `GET /operators/jobs/{job_id}/deadletters`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeadLetterEntry {
+    pub seq: u64,
+    pub job_id: String,
+    pub resource_id: String,
+    pub url: String,
+    pub failure_code: String,
+    pub failure_message: String,
+    pub attempts: u32,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DeadLetterEntry {
+    pub fn from_proto(seq: u64, dlq: crate::proto::DeadLetterTask) -> Self {
+        let task = dlq.task.unwrap_or_default();
+        Self {
+            seq,
+            job_id: task.job_id,
+            resource_id: task.resource_id,
+            url: task.url,
+            failure_code: dlq.failure_code,
+            failure_message: dlq.failure_message,
+            attempts: dlq.attempts,
+            failed_at: chrono::DateTime::from_timestamp_millis(dlq.failed_at_ms as i64)
+                .unwrap_or_else(chrono::Utc::now),
+        }
+    }
+}
+
+/// Response body for `POST /operators/jobs/{job_id}/deadletters/{resource_id}/replay`
+#[derive(Debug, Serialize)]
+pub struct ReplayDeadLetterResponse {
+    pub seq: u64,
+}
+
+/// Response body for `POST /operators/deadletters/{seq}/replay`
+#[derive(Debug, Serialize)]
+pub struct ReplayDlqResponse {
+    pub seq: u64,
+}
+
+/// Query params for `POST /operators/deadletters/replay`
+#[derive(Debug, Deserialize)]
+pub struct ReplayDlqAllQuery {
+    pub failure_code: String,
+}
+
+/// Response body for `POST /operators/deadletters/replay`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplayDlqAllResponse {
+    pub seqs: Vec<u64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub code: &'static str,
     pub message: String,
 }
 
+/// Request body for `POST /operators/schedules` - see
+/// [`crate::queue::scheduler::Scheduler::add`]
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduleRequest {
+    pub id: String,
+    pub job_template: crate::proto::DownloadTask,
+    pub interval_secs: u64,
+}
+
+/// Response body for `DELETE /operators/schedules/{id}`
+#[derive(Debug, Serialize)]
+pub struct DeleteScheduleResponse {
+    pub id: String,
+}
+
+/// Response body for `GET /operators/schedules`
+#[derive(Debug, Serialize)]
+pub struct ScheduleListResponse {
+    pub schedules: Vec<crate::ledger::ScheduleEntry>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: String,
-    pub components: HashMap<String, String>,
+    pub components: HashMap<String, ComponentHealth>,
     pub version: String,
 }
+
+/// Per-component result of a `GET /health` probe (see
+/// [`crate::api::services::health`])
+#[derive(Debug, Serialize)]
+pub struct ComponentHealth {
+    pub status: String,
+    pub latency_ms: u64,
+}
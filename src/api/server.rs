@@ -1,20 +1,27 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::{Router, routing::get, routing::post};
+use axum::{Router, middleware, routing::delete, routing::get, routing::post};
+use axum_server::tls_rustls::RustlsConfig;
 use tokio::net::TcpListener;
 use tower_http::decompression::RequestDecompressionLayer;
 use tracing::info;
 
 use super::{
-    services::{get_job, health, ingest_job},
+    auth::hmac_auth,
+    services::{
+        create_schedule, delete_schedule, get_job, get_job_logs, get_manifest_resources,
+        get_resource_artifact, health, ingest_job, job_events, list_deadletters, list_jobs,
+        list_schedules, metrics, replay_deadletter, replay_dlq, replay_dlq_all, task_events,
+    },
     state::AppState,
 };
 use crate::config::Config;
 use crate::handlers::HandlerRegistry;
 use crate::ledger::FjallStore;
-use crate::queue::{FjallQueue, TaskBroker};
+use crate::queue::{FjallQueue, Scheduler, TaskBroker};
 use crate::storage::StorageClient;
+use crate::worker::status_stream::StatusBroadcaster;
 use tokio::sync::RwLock;
 
 type AnyError = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -33,8 +40,15 @@ pub async fn run(
     let store = FjallStore::open(config.server.fjall_path.to_str().unwrap())
         .map_err(|e| format!("Failed to open Fjall store: {}", e))?;
 
-    // Initialize storage (in-memory for now)
-    let storage = StorageClient::in_memory();
+    // Installs console logging plus the per-job structured log layer (see
+    // `crate::worker::job_log`); must happen after `store` opens and before
+    // anything starts logging inside a `with_job_scope` future.
+    crate::worker::job_log::init_tracing(store.clone());
+
+    // Initialize storage from config.storage.provider (defaults to `local`,
+    // i.e. in-memory, unless the deployment opts into `s3`)
+    let storage = StorageClient::from_config(&config.storage)
+        .map_err(|e| format!("Failed to initialize storage: {}", e))?;
 
     // Initialize queue
     let queue_path = config.server.fjall_path.parent().unwrap().join("queue");
@@ -45,29 +59,185 @@ pub async fn run(
     ));
 
     // Initialize TaskBroker with worker channels
-    // TODO: Make num_workers and channel_size configurable
-    let (broker, _worker_receivers) = TaskBroker::new(queue.clone(), 8, 100);
+    let worker_config = &config.server.worker;
+    let (broker, worker_receivers, ack_tx) = TaskBroker::new(
+        queue.clone(),
+        worker_config.num_workers,
+        worker_config.channel_size,
+        std::time::Duration::from_secs(worker_config.visibility_timeout_secs),
+        worker_config.max_task_attempts,
+    );
     let broker = Arc::new(broker);
+    let _reaper_handle = broker.clone().spawn_reaper();
+    let metrics = Arc::new(crate::observability::Metrics::new());
 
-    // TODO: Spawn workers here (will be done in Phase 5)
+    // Pools with a `discovery` source are re-resolved on a background
+    // timer, independent of the static fallback chains `ProxyRotator`
+    // otherwise resolves fresh per download (see `crate::config::resolver::ProxyDirectory`).
+    let proxy_directory = Arc::new(crate::config::ProxyDirectory::new(config.proxy.clone()));
+    tokio::spawn(proxy_directory.clone().spawn_refresh());
+    let proxy_rotator = Arc::new(
+        crate::worker::proxy::ProxyRotator::new(config.proxy.clone())
+            .with_directory(proxy_directory),
+    );
+    let validator = Arc::new(crate::worker::validate::ContentValidator::new(&config.handlers));
+    let notifier = Arc::new(crate::worker::notify::NotificationDispatcher::new(&config.handlers));
+    let host_limiter = Arc::new(crate::worker::host_limit::HostLimiter::new(
+        worker_config.max_downloads_per_host,
+    ));
+    let streaming_policy = crate::worker::runner::StreamingPolicy {
+        stream_threshold_bytes: worker_config.stream_threshold.as_u64(),
+        upload_part_size_bytes: worker_config.upload_part_size.as_u64() as usize,
+        max_content_length_bytes: worker_config.max_content_length.map(|s| s.as_u64()),
+    };
 
-    // Initialize handler registry
-    let registry = HandlerRegistry::with_defaults();
+    // Initialize handler registry, then layer in any job types configured
+    // with a `wasm_module` (see `crate::handlers::wasm::WasmJobHandler`) on
+    // top of the compiled-in defaults
+    let mut registry = HandlerRegistry::with_defaults();
+    registry.register_wasm_handlers(&config.handlers);
+    let registry_for_workers = Arc::new(registry.clone());
 
-    let state = AppState::new(config, registry, store, storage, broker);
+    // Spawn the download worker pool; each worker drains its own channel and
+    // folds results directly into the Fjall ledger (see `crate::worker`).
+    let store_for_workers = Arc::new(store.clone());
+    let storage_for_workers = Arc::new(storage.clone());
+    let status_broadcaster = Arc::new(StatusBroadcaster::new());
+    let _worker_handles = crate::worker::spawn_pool(
+        worker_receivers,
+        storage_for_workers,
+        store_for_workers,
+        queue.clone(),
+        worker_config.max_task_attempts,
+        Some(proxy_rotator),
+        validator,
+        notifier,
+        metrics.clone(),
+        host_limiter,
+        streaming_policy,
+        status_broadcaster.clone(),
+        registry_for_workers,
+        ack_tx,
+        std::time::Duration::from_secs(worker_config.lease_heartbeat_interval_secs),
+    );
 
-    let app = Router::new()
+    // Replay whatever was still outstanding before a restart - workers are
+    // already draining their channels above, so this can't deadlock on a
+    // full channel.
+    if let Err(e) = broker.recover().await {
+        tracing::warn!(error = %e, "Failed to replay outstanding tasks on startup");
+    }
+
+    // Watch the config file (and SIGHUP) for edits; a validated reload is
+    // published through `config_handle` for subsystems migrated to read it,
+    // while `config` below stays the fixed startup snapshot for the rest.
+    let config_watcher =
+        crate::config::ConfigWatcher::new(crate::config::resolved_config_path(), config.clone());
+    let config_handle = config_watcher.handle();
+    config_watcher
+        .spawn()
+        .map_err(|e| format!("Failed to start config watcher: {}", e))?;
+
+    // Periodically sweep the ledger for expired jobs/logs/idempotency keys;
+    // paced by `config.retention.tranquility` so a large ledger doesn't
+    // stall foreground request latency (see `crate::ledger::scheduler`).
+    tokio::spawn(crate::ledger::scheduler::run(
+        store.clone(),
+        config.retention.clone(),
+    ));
+
+    // Rehydrate recurring jobs registered through `Scheduler::add` on a
+    // prior run, then fire/re-enqueue them on their own schedule.
+    let scheduler = Arc::new(
+        Scheduler::load(store.clone(), broker.clone())
+            .map_err(|e| format!("Failed to load recurring job schedules: {}", e))?,
+    );
+    tokio::spawn(scheduler.clone().run());
+
+    let state_tls = config.server.tls.clone();
+    let metrics_addr = config.telemetry.metrics_addr;
+    let state = AppState::new(
+        config,
+        config_handle,
+        registry,
+        store,
+        storage,
+        broker,
+        metrics,
+        status_broadcaster,
+        scheduler,
+    );
+
+    // Serve /metrics on its own listener so scrapers don't need the
+    // per-tenant HMAC credentials the main API requires.
+    tokio::spawn(run_metrics_server(metrics_addr, state.clone()));
+
+    // Per-tenant HMAC signing guards job ingestion and operator endpoints;
+    // `/health` stays open for load balancer probes.
+    let authenticated = Router::new()
         .route("/jobs", post(ingest_job))
+        .route("/operators/jobs", get(list_jobs))
         .route("/operators/jobs/{job_id}", get(get_job))
+        .route("/operators/jobs/{job_id}/resources", get(get_manifest_resources))
+        .route(
+            "/operators/jobs/{job_id}/resources/{resource_name}/artifact",
+            get(get_resource_artifact),
+        )
+        .route("/operators/jobs/{job_id}/events", get(job_events))
+        .route("/operators/jobs/{job_id}/logs", get(get_job_logs))
+        .route("/operators/jobs/{job_id}/deadletters", get(list_deadletters))
+        .route(
+            "/operators/jobs/{job_id}/deadletters/{resource_id}/replay",
+            post(replay_deadletter),
+        )
+        .route("/operators/deadletters/replay", post(replay_dlq_all))
+        .route("/operators/deadletters/{seq}/replay", post(replay_dlq))
+        .route("/operators/schedules", post(create_schedule).get(list_schedules))
+        .route("/operators/schedules/{id}", delete(delete_schedule))
+        .route("/tasks/{seq}/events", get(task_events))
         .route("/operators/health", get(health))
+        .route_layer(middleware::from_fn_with_state(state.clone(), hmac_auth));
+
+    let app = Router::new()
+        .merge(authenticated)
         .route("/health", get(health))
         .with_state(state)
         // Automatically decompress gzip/deflate/brotli request bodies
         // Handles Content-Encoding header transparently at the middleware level
         .layer(RequestDecompressionLayer::new());
 
+    match &state_tls {
+        Some(tls) => run_tls(address, app, tls).await,
+        None => run_plaintext(address, app).await,
+    }
+}
+
+/// Serve `GET /metrics` in Prometheus text exposition format on
+/// `config.telemetry.metrics_addr`
+async fn run_metrics_server(address: SocketAddr, state: AppState) {
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state);
+
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!(%address, error = %e, "Failed to bind metrics listener");
+            return;
+        }
+    };
+
+    info!(%address, "FetchBox metrics listening");
+
+    if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+        tracing::error!(error = %e, "Metrics server exited");
+    }
+}
+
+/// Serve over plaintext HTTP (default when `[server.tls]` is absent)
+async fn run_plaintext(address: SocketAddr, app: Router) -> Result<(), AnyError> {
     let listener = TcpListener::bind(address).await?;
-    info!(%address, "FetchBox API listening");
+    info!(%address, "FetchBox API listening (plaintext)");
 
     axum::serve(listener, app.into_make_service())
         .with_graceful_shutdown(shutdown_signal())
@@ -76,6 +246,40 @@ pub async fn run(
     Ok(())
 }
 
+/// Serve over TLS using rustls, terminating HTTPS directly without a reverse proxy
+async fn run_tls(
+    address: SocketAddr,
+    app: Router,
+    tls: &crate::config::TlsConfig,
+) -> Result<(), AnyError> {
+    info!(
+        %address,
+        cert = %tls.cert_path.display(),
+        mtls = tls.client_ca_path.is_some(),
+        "FetchBox API listening (TLS)"
+    );
+
+    let rustls_config =
+        RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await?;
+
+    // TODO: wire client_ca_path into a rustls WebPkiClientVerifier once
+    // axum_server exposes builder access to the ServerConfig's client auth.
+
+    let handle = axum_server::Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        shutdown_handle.graceful_shutdown(Some(std::time::Duration::from_secs(30)));
+    });
+
+    axum_server::bind_rustls(address, rustls_config)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -1,9 +1,24 @@
-use axum::{Json, extract::State, http::HeaderMap, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::HeaderMap,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::Stream;
 use http_body_util::BodyExt;
+use std::convert::Infallible;
+use std::time::Duration;
 use uuid::Uuid;
 
 use super::{
-    models::{JobSnapshot, Manifest},
+    auth::AuthenticatedTenant,
+    models::{
+        CreateScheduleRequest, DeadLetterEntry, DeleteScheduleResponse, JobListQuery,
+        JobListResponse, JobSnapshot, Manifest, Resource, ScheduleListResponse,
+    },
     state::AppState,
     validation::ManifestValidationError,
 };
@@ -18,7 +33,10 @@ const MAX_PAYLOAD_SIZE: usize = 5 * 1024 * 1024; // 5MB
 /// - Content-Type and encoding validation (gzip support)
 /// - Job type resolution via handler registry
 /// - Idempotency via X-Fetchbox-Idempotency-Key header
-/// - Manifest validation and storage (S3)
+/// - Content-addressed manifest storage with dedup (see [`crate::storage::cas`])
+/// - Thin/fat manifest split: the `resources` vector is uploaded under its
+///   own storage key, kept out of the ledger-persisted [`JobSnapshot`] (see
+///   [`get_manifest_resources`])
 /// - Job state initialization in ledger
 /// - Task generation via handler.build_tasks()
 /// - Publishing tasks to the jobs.tasks stream for workers
@@ -28,11 +46,28 @@ const MAX_PAYLOAD_SIZE: usize = 5 * 1024 * 1024; // 5MB
 /// 2. Check idempotency - return existing job if key matches
 /// 3. Read and decompress body (supports gzip), enforce size limits
 /// 4. Deserialize and validate manifest against schema
-/// 5. Generate UUIDv7 job_id, upload manifest to S3
-/// 6. Create JobSnapshot (Queued status) and persist to ledger
-/// 7. Invoke handler to generate tasks from manifest
-/// 8. Publish all tasks to jobs.tasks stream for worker consumption
-/// 9. Return 202 Accepted with job_id and resource count
+/// 5. Generate UUIDv7 job_id, content-address the manifest body and upload
+///    it to S3 under its CAS key unless an identical blob is already there
+/// 6. Upload the manifest's `resources` vector (potentially thousands of
+///    entries) to its own storage key and record it in the ledger, so
+///    status/debugging paths never have to download and re-parse the whole
+///    manifest blob just to read resources
+/// 7. Invoke handler to generate tasks from manifest (the resources already
+///    parsed in memory above, not re-fetched from the key written in step 6)
+///    - before the job snapshot or idempotency key is persisted, so a
+///      handler failure leaves no job behind (see "Failure atomicity" below)
+/// 8. Create JobSnapshot (Queued status) and persist to ledger
+/// 9. Publish all tasks to jobs.tasks stream for worker consumption
+/// 10. Return 202 Accepted with job_id, manifest integrity, and resource count
+///
+/// ## Failure atomicity:
+/// `handler.build_tasks()` runs before the JobSnapshot or idempotency key are
+/// written. If it fails, the only durable side effects already committed are
+/// the CAS-uploaded manifest blob (content-addressed, shared by retries) and
+/// the resources split under this attempt's job_id (its blob and ledger
+/// entry stay behind, unreferenced), so a retried POST starts clean rather
+/// than finding a permanent "Queued, zero tasks" job under the same
+/// idempotency key.
 ///
 /// ## Idempotency:
 /// If X-Fetchbox-Idempotency-Key is provided and matches an existing job,
@@ -57,14 +92,18 @@ pub async fn ingest_job(
 
     // Extract and validate job type (required)
     // The job type determines which handler processes this manifest
-    // Use default job type (simplified - only one job type supported)
-    let job_type = "default";
+    let job_type = headers
+        .get("X-Fetchbox-Job-Type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .ok_or_else(|| ApiError::InvalidPayload("X-Fetchbox-Job-Type header is required".to_string()))?;
+    super::utils::validate_job_type(&job_type)?;
 
     // Verify handler exists for this job type before proceeding
     state
         .registry
-        .get(job_type)
-        .map_err(|_| ApiError::UnsupportedJobType(job_type.to_string()))?;
+        .get(&job_type)
+        .map_err(|_| ApiError::UnsupportedJobType(job_type.clone()))?;
 
     // Extract required tenant identifier
     let tenant = headers
@@ -84,11 +123,13 @@ pub async fn ingest_job(
     // Idempotency check: if we've seen this key before, return the existing job
     // This allows clients to safely retry POST requests without creating duplicates
     if let Some(ref key) = idempotency_key {
-        if let Ok(Some(existing_job_id)) = state.store.get_idempotent(key) {
+        let ttl_days = state.config.retention.idempotency_ttl_days;
+        if let Ok(Some(existing_job_id)) = state.store.get_idempotent(&tenant, key, ttl_days) {
             if let Ok(Some(existing_snapshot)) = state.store.get(&existing_job_id) {
                 let response = super::models::JobAcceptedResponse {
                     job_id: existing_snapshot.job_id,
                     manifest_key: existing_snapshot.manifest_key,
+                    manifest_integrity: existing_snapshot.manifest_integrity,
                     resource_count: existing_snapshot.resource_total,
                 };
 
@@ -107,22 +148,88 @@ pub async fn ingest_job(
     // Generate time-sortable UUIDv7 for this job
     let job_id = Uuid::now_v7().to_string();
 
-    // Upload manifest to S3 for persistence and worker access
-    // Path uses client-provided storage configuration (spec §1.3.3)
-    // Full path: {resource_key_prefix}{manifest_file}
-    let storage_key = format!(
-        "{}{}",
-        manifest.storage.resource_key_prefix,
-        manifest.storage.manifest_file
-    );
-    let upload_result = state
+    // Content-address the manifest body instead of trusting the client's
+    // storage path (spec §1.3.3 used to derive the key from
+    // `resource_key_prefix`/`manifest_file`): two identical submissions now
+    // converge on the same blob rather than paying for a duplicate upload.
+    // See `crate::storage::cas`.
+    let manifest_integrity = crate::storage::cas::compute_integrity(&body_bytes);
+    let cas_key = crate::storage::cas::storage_key(&manifest_integrity)
+        .map_err(|e| ApiError::Internal(format!("Failed to derive CAS key: {}", e)))?;
+
+    // Short-circuit the upload if the index already has this content and
+    // its blob is still there; a missing blob (e.g. deleted out-of-band)
+    // falls back to a fresh upload rather than failing the ingest.
+    let already_stored = match state.store.get_cas_entry(&manifest_integrity).map_err(|e| {
+        ApiError::Internal(format!("Failed to look up CAS entry: {}", e))
+    })? {
+        Some(existing_key) => state
+            .storage
+            .exists(&existing_key)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to check CAS blob: {}", e)))?,
+        None => false,
+    };
+
+    if !already_stored {
+        state
+            .storage
+            .upload(&cas_key, body_bytes.to_vec())
+            .await
+            .map_err(|e| ApiError::Internal(format!("Storage upload failed: {}", e)))?;
+        state
+            .store
+            .remember_cas_entry(&manifest_integrity, &cas_key)
+            .map_err(|e| ApiError::Internal(format!("Failed to store CAS entry: {}", e)))?;
+    }
+
+    let manifest_key = format!("s3://{}/{}", state.storage.bucket, cas_key);
+
+    // Split off the "fat" part of the manifest - the `resources` vector,
+    // which can run to thousands of entries - into its own storage key
+    // rather than letting it ride along inside JobSnapshot or requiring a
+    // re-download/re-parse of the whole manifest blob to read it back.
+    // See `crate::ledger::FjallStore::get_manifest_resources_key` and
+    // `get_manifest_resources` below.
+    let resources_key = format!("manifests/{}/resources.json", job_id);
+    let resources_bytes = serde_json::to_vec(&manifest.resources)?;
+    state
         .storage
-        .upload(&storage_key, body_bytes.to_vec())
+        .upload(&resources_key, resources_bytes)
         .await
-        .map_err(|e| ApiError::Internal(format!("Storage upload failed: {}", e)))?;
+        .map_err(|e| ApiError::Internal(format!("Failed to upload manifest resources: {}", e)))?;
+    state
+        .store
+        .remember_manifest_resources(&job_id, &resources_key)
+        .map_err(|e| {
+            ApiError::Internal(format!("Failed to store manifest resources key: {}", e))
+        })?;
+
+    // Invoke handler to generate tasks from the manifest before writing
+    // the snapshot/idempotency records to the ledger: if `build_tasks` fails,
+    // no job exists under this job_id (only the resources split above
+    // remains). Doing this after the
+    // snapshot/idempotency writes used to leave a permanent "Queued, zero
+    // tasks" zombie job on failure, with repeat POSTs under the same
+    // idempotency key returning that dead job_id forever instead of retrying.
+    let handler = state.registry.get(&job_type).unwrap(); // Safe: already validated above
 
-    let manifest_key =
-        format!("s3://{}/{}", state.storage.bucket, upload_result.key);
+    // Wrap manifest with context for handler
+    let prepared = crate::handlers::types::PreparedManifest {
+        context: crate::handlers::types::ManifestContext {
+            job_id: job_id.clone(),
+            job_type: job_type.clone(),
+            manifest: manifest.clone(),
+        },
+        handler_data: None, // Reserved for handler-specific state
+    };
+
+    // Generate tasks - this is where job-specific logic transforms
+    // the manifest into executable work units
+    let tasks = handler
+        .build_tasks(prepared)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Handler failed: {}", e)))?;
 
     // Generate timestamp for job creation
     let timestamp = chrono::Utc::now();
@@ -131,6 +238,7 @@ pub async fn ingest_job(
     // This is the primary state representation stored in the ledger
     let snapshot = JobSnapshot {
         job_id: job_id.clone(),
+        job_type: job_type.clone(),
         status: super::models::JobStatus::Queued,
         created_at: timestamp,
         updated_at: timestamp,
@@ -138,6 +246,7 @@ pub async fn ingest_job(
         resource_completed: 0,
         resource_failed: 0,
         manifest_key: manifest_key.clone(),
+        manifest_integrity: manifest_integrity.clone(),
         errors: Vec::new(),
         tenant: tenant.clone(),
     };
@@ -147,7 +256,7 @@ pub async fn ingest_job(
     if let Some(ref key) = idempotency_key {
         state
             .store
-            .remember_idempotency(key.clone(), job_id.clone())
+            .remember_idempotency(&tenant, key, job_id.clone())
             .map_err(|e| {
                 ApiError::Internal(format!(
                     "Failed to store idempotency key: {}",
@@ -162,31 +271,10 @@ pub async fn ingest_job(
         .upsert(snapshot)
         .map_err(|e| ApiError::Internal(format!("Failed to store job: {}", e)))?;
 
-    // Invoke handler to generate tasks from the manifest
-    // The handler is job-type-specific and knows how to break down work
-    let handler = state.registry.get(job_type).unwrap(); // Safe: already validated above
-
-    // Wrap manifest with context for handler
-    let prepared = crate::handlers::types::PreparedManifest {
-        context: crate::handlers::types::ManifestContext {
-            job_id: job_id.clone(),
-            job_type: job_type.to_string(),
-            manifest: manifest.clone(),
-        },
-        handler_data: None, // Reserved for handler-specific state
-    };
-
-    // Generate tasks - this is where job-specific logic transforms
-    // the manifest into executable work units
-    let tasks = handler
-        .build_tasks(prepared)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Handler failed: {}", e)))?;
-
     // Create task context for proto conversion (shared across all tasks in this job)
     let task_context = crate::handlers::TaskContext {
         job_id: job_id.clone(),
-        job_type: job_type.to_string(),
+        job_type: job_type.clone(),
         tenant: tenant.clone(),
         manifest_key: manifest_key.clone(),
     };
@@ -214,6 +302,7 @@ pub async fn ingest_job(
     let response = super::models::JobAcceptedResponse {
         job_id,
         manifest_key,
+        manifest_integrity,
         resource_count: manifest.resources.len(),
     };
 
@@ -249,6 +338,7 @@ async fn read_body(body: axum::body::Body) -> Result<Vec<u8>, ApiError> {
 /// Includes status, progress, timestamps, and error information.
 pub async fn get_job(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
     axum::extract::Path(job_id): axum::extract::Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
     let snapshot = state
@@ -257,41 +347,666 @@ pub async fn get_job(
         .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
         .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
 
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
     Ok((axum::http::StatusCode::OK, Json(snapshot)))
 }
 
-/// Health check endpoint (GET /health)
+/// Fetch a job's resources vector on demand (GET /operators/jobs/:job_id/resources)
+///
+/// The `resources` array is the "fat" part of the manifest split off at
+/// ingest time (see [`ingest_job`]) instead of living in [`JobSnapshot`];
+/// this is the only path that pays for downloading and parsing it. Returns
+/// 404 if the job doesn't exist, or if it predates this split and never had
+/// a resources key recorded.
+pub async fn get_manifest_resources(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
+
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let resources_key = state
+        .store
+        .get_manifest_resources_key(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to look up manifest resources: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("resources for job {job_id}")))?;
+
+    let bytes = state
+        .storage
+        .download(&resources_key)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to download manifest resources: {}", e)))?;
+
+    let resources: Vec<Resource> = serde_json::from_slice(&bytes)?;
+
+    Ok((axum::http::StatusCode::OK, Json(resources)))
+}
+
+/// Fetch a job's captured structured logs (GET /operators/jobs/:job_id/logs)
+///
+/// Served straight out of `FjallStore`'s `logs` partition - see
+/// [`crate::worker::job_log`] for how `info!`/`warn!` events emitted while
+/// processing the job's tasks end up there. Returns 404 if the job doesn't
+/// exist; an existing job with no captured logs yet returns an empty list
+/// rather than 404.
+pub async fn get_job_logs(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
+
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let logs = state
+        .store
+        .read_logs(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to read job logs: {}", e)))?;
+
+    Ok((axum::http::StatusCode::OK, Json(logs)))
+}
+
+/// Download one resource's fetched bytes (GET
+/// /operators/jobs/:job_id/resources/:resource_name/artifact)
 ///
-/// Returns health status of all FetchBox components:
-/// - api: Axum HTTP server
-/// - fjall: Ledger (Fjall KV store)
-/// - task_broker: Task queue broker
-/// - storage: S3-compatible storage
+/// Looks the resource up in the job's run manifest (see
+/// [`crate::worker::manifest::get_resource_record`]) to find where its bytes
+/// landed, then serves them straight out of [`crate::storage::StorageClient`].
+/// A `Range: bytes=...` request header is honored via
+/// [`crate::storage::StorageClient::download_opts`], answering 206 Partial
+/// Content with a `Content-Range`; without one, the whole artifact comes back
+/// as 200. Multi-range requests aren't supported - they're rare for the
+/// download-a-file use case this serves, and 416 covers that and any other
+/// unsatisfiable range.
 ///
-/// Returns 503 Service Unavailable if any component is unhealthy.
-/// Returns 200 OK otherwise.
-pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
-    use std::collections::HashMap;
+/// Returns 404 if the job doesn't exist, or if no resource named
+/// `resource_name` has completed yet (the run manifest only gains an entry
+/// once a resource finishes downloading).
+pub async fn get_resource_artifact(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path((job_id, resource_name)): axum::extract::Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
 
-    let mut components = HashMap::new();
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let record = crate::worker::manifest::get_resource_record(
+        &state.storage,
+        &snapshot.manifest_key,
+        &resource_name,
+    )
+    .await
+    .map_err(|e| ApiError::Internal(format!("Failed to read run manifest: {}", e)))?
+    .ok_or_else(|| ApiError::NotFound(format!("artifact {resource_name} for job {job_id}")))?;
+
+    let parsed_range = headers
+        .get(axum::http::header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| parse_byte_range(v, record.size_bytes))
+        .transpose()?;
 
-    // Check each component - in v0 we assume healthy if running
-    components.insert("api".to_string(), "healthy".to_string());
-    components.insert("fjall".to_string(), "healthy".to_string());
-    components.insert("task_broker".to_string(), "healthy".to_string());
-    components.insert("storage".to_string(), "healthy".to_string());
+    let opts = crate::storage::DownloadOptions {
+        range: parsed_range.map(|(start, end)| crate::storage::ByteRange::Bounded(start..end + 1)),
+        ..Default::default()
+    };
 
-    // TODO: Add actual health checks for each component
-    // For now, if we can respond, we're healthy
+    let object = state
+        .storage
+        .download_opts(&record.storage_key, opts)
+        .await
+        .map_err(|e| match e {
+            crate::storage::StorageError::NotFound(_) => {
+                ApiError::NotFound(format!("artifact {resource_name} for job {job_id}"))
+            }
+            other => ApiError::Internal(format!("Failed to download artifact: {other}")),
+        })?;
 
-    let all_healthy = components.values().all(|status| status == "healthy");
-    let overall_status = if all_healthy {
-        "healthy"
+    let content_type = record
+        .detected_mime_type
+        .or(record.content_type)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        content_type
+            .parse()
+            .unwrap_or_else(|_| axum::http::HeaderValue::from_static("application/octet-stream")),
+    );
+    response_headers.insert(
+        axum::http::header::ACCEPT_RANGES,
+        axum::http::HeaderValue::from_static("bytes"),
+    );
+
+    let status = match parsed_range {
+        Some((start, end)) => {
+            let value = format!("bytes {start}-{end}/{}", record.size_bytes);
+            response_headers.insert(
+                axum::http::header::CONTENT_RANGE,
+                value.parse().map_err(|_| ApiError::Internal("invalid Content-Range".to_string()))?,
+            );
+            axum::http::StatusCode::PARTIAL_CONTENT
+        }
+        None => axum::http::StatusCode::OK,
+    };
+
+    Ok((status, response_headers, object.bytes))
+}
+
+/// Parse a single-range `Range` request header (`bytes=start-end`,
+/// `bytes=start-`, or `bytes=-suffix`) into an inclusive `(start, end)` byte
+/// range against an artifact of `total` bytes. Rejects multi-range requests
+/// and anything malformed or out of bounds as [`ApiError::RangeNotSatisfiable`].
+fn parse_byte_range(header: &str, total: u64) -> Result<(u64, u64), ApiError> {
+    let unsatisfiable = || ApiError::RangeNotSatisfiable(total);
+
+    let spec = header.strip_prefix("bytes=").ok_or_else(unsatisfiable)?;
+    if spec.contains(',') {
+        return Err(unsatisfiable());
+    }
+    let (start_s, end_s) = spec.split_once('-').ok_or_else(unsatisfiable)?;
+
+    let (start, end) = if start_s.is_empty() {
+        let suffix: u64 = end_s.parse().map_err(|_| unsatisfiable())?;
+        if suffix == 0 || total == 0 {
+            return Err(unsatisfiable());
+        }
+        (total.saturating_sub(suffix), total - 1)
     } else {
-        "unhealthy"
+        let start: u64 = start_s.parse().map_err(|_| unsatisfiable())?;
+        let end = if end_s.is_empty() {
+            total.saturating_sub(1)
+        } else {
+            end_s.parse().map_err(|_| unsatisfiable())?
+        };
+        (start, end.min(total.saturating_sub(1)))
+    };
+
+    if total == 0 || start > end || start >= total {
+        return Err(unsatisfiable());
+    }
+
+    Ok((start, end))
+}
+
+/// Live job progress via Server-Sent Events (GET /operators/jobs/:job_id/events)
+///
+/// Streams a `snapshot` event each time [`crate::ledger::FjallStore::record_resource_outcome`]
+/// folds a result into the job (pushed through `FjallStore`'s broadcast
+/// channel) plus a periodic `FjallStore` re-read every 15s as a heartbeat,
+/// so a client never waits longer than that for a keepalive. The stream
+/// ends after the event where `status` reaches `Completed` or `Failed`.
+pub async fn job_events(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
+
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let store = state.store.clone();
+    let mut receiver = store.subscribe_events();
+
+    let stream = async_stream::stream! {
+        let mut heartbeat = tokio::time::interval(Duration::from_secs(15));
+        heartbeat.tick().await; // first tick fires immediately; consume it
+
+        loop {
+            tokio::select! {
+                recv = receiver.recv() => {
+                    let snapshot = match recv {
+                        Ok(snapshot) if snapshot.job_id == job_id => snapshot,
+                        Ok(_) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    };
+                    let terminal = is_terminal_status(&snapshot.status);
+                    yield Ok(Event::default().event("snapshot").json_data(snapshot).unwrap());
+                    if terminal {
+                        break;
+                    }
+                }
+                _ = heartbeat.tick() => {
+                    let Ok(Some(snapshot)) = store.get(&job_id) else { break };
+                    let terminal = is_terminal_status(&snapshot.status);
+                    yield Ok(Event::default().event("snapshot").json_data(snapshot).unwrap());
+                    if terminal {
+                        break;
+                    }
+                }
+            }
+        }
     };
 
-    let status_code = if all_healthy {
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Live per-task status/log/progress via Server-Sent Events
+/// (GET /tasks/:seq/events)
+///
+/// Backed by [`crate::worker::status_stream::StatusBroadcaster`] rather than
+/// `FjallStore`'s job-level snapshot broadcast (see [`job_events`]): each
+/// event is one [`crate::worker::status_stream::TaskEventPayload`] - a
+/// status transition, a log line, or a progress update - with `id` set to
+/// the event's monotonic id so a reconnecting client's `Last-Event-ID`
+/// header resumes from wherever it left off, replaying the broadcaster's
+/// ring buffer before switching to the live channel. The stream ends after
+/// a `Status` event whose `to` is `"Succeeded"`, `"Failed"`, or
+/// `"DeadLettered"`.
+pub async fn task_events(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(seq): axum::extract::Path<u64>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let task_tenant = state
+        .broker
+        .task_tenant(seq)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to look up task: {}", e)))?;
+
+    caller.authorize(task_tenant.as_deref(), || ApiError::NotFound(format!("task {seq}")))?;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let (backlog, mut receiver) = state.status_broadcaster.subscribe_from(seq, last_event_id);
+
+    let stream = async_stream::stream! {
+        for event in backlog {
+            let terminal = is_terminal_task_event(&event);
+            yield Ok(task_event_to_sse(&event));
+            if terminal {
+                return;
+            }
+        }
+
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let terminal = is_terminal_task_event(&event);
+                    yield Ok(task_event_to_sse(&event));
+                    if terminal {
+                        break;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn task_event_to_sse(event: &crate::worker::status_stream::TaskEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event("task")
+        .json_data(event)
+        .unwrap()
+}
+
+fn is_terminal_task_event(event: &crate::worker::status_stream::TaskEvent) -> bool {
+    matches!(
+        &event.payload,
+        crate::worker::status_stream::TaskEventPayload::Status { to, .. }
+            if matches!(to.as_str(), "Succeeded" | "Failed" | "DeadLettered")
+    )
+}
+
+fn is_terminal_status(status: &super::models::JobStatus) -> bool {
+    matches!(
+        status,
+        super::models::JobStatus::Completed | super::models::JobStatus::Failed
+    )
+}
+
+/// List jobs for operator dashboards (GET /operators/jobs)
+///
+/// Supports keyset pagination via `cursor` (the last `job_id` seen) plus
+/// `tenant`/`status`/`created_after`/`created_before` filters. See
+/// [`crate::ledger::FjallStore::list_jobs`] for pagination semantics.
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Query(mut query): axum::extract::Query<JobListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    // In multi-tenant mode a caller can only ever list their own jobs - scope
+    // (or reject) the `tenant` filter to the authenticated identity rather
+    // than trusting whatever the caller passed, or `?tenant=<other>` would
+    // dump every other tenant's jobs.
+    if let AuthenticatedTenant(Some(tenant)) = &caller {
+        if query.tenant.as_deref().is_some_and(|t| t != tenant) {
+            return Err(ApiError::Unauthorized(
+                "tenant query param does not match authenticated tenant".to_string(),
+            ));
+        }
+        query.tenant = Some(tenant.clone());
+    }
+
+    let (jobs, next_cursor) = state
+        .store
+        .list_jobs(&query)
+        .map_err(|e| ApiError::Internal(format!("Failed to list jobs: {}", e)))?;
+
+    Ok((
+        axum::http::StatusCode::OK,
+        Json(JobListResponse { jobs, next_cursor }),
+    ))
+}
+
+/// List dead-lettered resources for a job (GET /operators/jobs/:job_id/deadletters)
+///
+/// Surfaces the resources that exhausted `worker.max_task_attempts` retries
+/// and were moved to the queue's dead-letter partition, so operators can
+/// inspect the failure and decide whether to replay it.
+pub async fn list_deadletters(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
+
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let entries = state
+        .broker
+        .list_deadletters(&job_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to list dead letters: {}", e)))?
+        .into_iter()
+        .map(|(seq, dlq)| DeadLetterEntry::from_proto(seq, dlq))
+        .collect::<Vec<_>>();
+
+    Ok((axum::http::StatusCode::OK, Json(entries)))
+}
+
+/// Replay a dead-lettered resource (POST /operators/jobs/:job_id/deadletters/:resource_id/replay)
+///
+/// Re-enqueues the task under a fresh sequence number and removes its
+/// dead-letter entry. Returns 404 if no matching entry exists.
+pub async fn replay_deadletter(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path((job_id, resource_id)): axum::extract::Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let snapshot = state
+        .store
+        .get(&job_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get job: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("job {job_id}")))?;
+
+    caller.authorize(Some(&snapshot.tenant), || ApiError::NotFound(format!("job {job_id}")))?;
+
+    let new_seq = state
+        .broker
+        .replay_deadletter(&job_id, &resource_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to replay dead letter: {}", e)))?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "dead letter entry for job {job_id} resource {resource_id}"
+            ))
+        })?;
+
+    let response = super::models::ReplayDeadLetterResponse { seq: new_seq };
+
+    Ok((axum::http::StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Replay a single DLQ entry by its Fjall sequence number
+/// (POST /operators/deadletters/:seq/replay)
+///
+/// Unlike [`replay_deadletter`], which is scoped to one job's resource,
+/// this addresses the DLQ directly by `seq` - useful once an operator has
+/// the sequence number from [`crate::observability::Metrics::set_dlq_size`]-
+/// adjacent tooling or a prior scrape, without needing the owning job_id.
+pub async fn replay_dlq(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedTenant>,
+    axum::extract::Path(seq): axum::extract::Path<u64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let entry = state
+        .broker
+        .get_dlq_entry(seq)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to look up dead letter: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound(format!("dead letter entry {seq}")))?;
+    let entry_tenant = entry.task.as_ref().map(|task| task.tenant.as_str());
+
+    caller.authorize(entry_tenant, || ApiError::NotFound(format!("dead letter entry {seq}")))?;
+
+    let new_seq = state.broker.replay_dlq(seq).await.map_err(|e| match e {
+        crate::queue::QueueError::TaskNotFound(_) => {
+            ApiError::NotFound(format!("dead letter entry {seq}"))
+        }
+        other => ApiError::Internal(format!("Failed to replay dead letter: {other}")),
+    })?;
+
+    let response = super::models::ReplayDlqResponse { seq: new_seq };
+
+    Ok((axum::http::StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Bulk-replay every DLQ entry matching `failure_code`
+/// (POST /operators/deadletters/replay?failure_code=NETWORK_ERROR)
+///
+/// E.g. once a dead proxy pool is fixed, an operator can retry every task
+/// it failed in one call instead of replaying each `seq` individually.
+pub async fn replay_dlq_all(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<super::models::ReplayDlqAllQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let seqs = state
+        .broker
+        .replay_dlq_all(&query.failure_code)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to replay dead letters: {}", e)))?;
+
+    let response = super::models::ReplayDlqAllResponse { seqs };
+
+    Ok((axum::http::StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Register a recurring job (POST /operators/schedules)
+///
+/// See [`crate::queue::scheduler::Scheduler::add`] - overwrites any existing
+/// entry with the same `id`.
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    Json(request): Json<CreateScheduleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if request.id.is_empty() {
+        return Err(ApiError::InvalidPayload("schedule id must not be empty".to_string()));
+    }
+    if request.interval_secs == 0 {
+        return Err(ApiError::InvalidPayload("interval_secs must be greater than zero".to_string()));
+    }
+
+    state
+        .scheduler
+        .add(
+            request.id,
+            request.job_template,
+            Duration::from_secs(request.interval_secs),
+        )
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to register schedule: {}", e)))?;
+
+    Ok(axum::http::StatusCode::ACCEPTED)
+}
+
+/// Unregister a recurring job (DELETE /operators/schedules/:id)
+///
+/// Returns 404 if no entry with `id` exists.
+pub async fn delete_schedule(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let removed = state
+        .scheduler
+        .remove(&id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to remove schedule: {}", e)))?;
+
+    if !removed {
+        return Err(ApiError::NotFound(format!("schedule {id}")));
+    }
+
+    Ok((axum::http::StatusCode::OK, Json(DeleteScheduleResponse { id })))
+}
+
+/// List every registered recurring job (GET /operators/schedules)
+pub async fn list_schedules(State(state): State<AppState>) -> impl IntoResponse {
+    let schedules = state.scheduler.list().await;
+    (axum::http::StatusCode::OK, Json(ScheduleListResponse { schedules }))
+}
+
+/// Prometheus scrape endpoint (GET /metrics on `telemetry.metrics_addr`)
+///
+/// Served on its own listener (see [`super::server::run_metrics_server`])
+/// rather than mixed into the authenticated API router, matching the
+/// separate `metrics_addr` operators already bind their scrapers to.
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    // Refresh the queue/DLQ gauges from Fjall right before rendering, rather
+    // than keeping them live-updated on every enqueue/dead-letter - a scrape
+    // is infrequent enough that a point-in-time read is simpler and just as
+    // accurate.
+    match state.broker.queue_depth().await {
+        Ok(depth) => state.metrics.set_queue_depth(depth),
+        Err(e) => tracing::warn!(error = %e, "Failed to read queue depth for /metrics"),
+    }
+    match state.broker.dlq_size().await {
+        Ok(size) => state.metrics.set_dlq_size(size),
+        Err(e) => tracing::warn!(error = %e, "Failed to read DLQ size for /metrics"),
+    }
+    state.metrics.set_worker_loads(state.broker.worker_loads());
+    let compression_stats = state.storage.compression_stats();
+    state
+        .metrics
+        .set_storage_compression_bytes(compression_stats.original_bytes, compression_stats.stored_bytes);
+
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render_prometheus(),
+    )
+}
+
+/// Health check endpoint (GET /health)
+///
+/// Actually probes each component instead of reporting a hardcoded
+/// `"healthy"`, each bounded by `health.probe_timeout_secs` so a hung
+/// dependency can't hang the endpoint itself:
+/// - api: always healthy - if this handler is running, the server is up
+/// - fjall: round-trips a fixed key through the ledger's `metadata`
+///   partition (see [`crate::ledger::FjallStore::health_check`])
+/// - task_broker: reads the queue depth
+/// - storage: a `HEAD` against the configured bucket (see
+///   [`crate::storage::StorageClient::exists`]); a missing key still proves
+///   the bucket is reachable, so only a transport/auth error fails this
+///
+/// `health.critical_components` (default `fjall`, `task_broker`, `storage`)
+/// controls which failures flip the response to 503 so orchestrators can
+/// gate traffic on it; a non-critical component still reports `"unhealthy"`
+/// in the body, it just doesn't affect the status code.
+pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    use std::collections::HashMap;
+    use std::time::Instant;
+
+    let timeout = Duration::from_secs(state.config.health.probe_timeout_secs);
+
+    async fn probe<F>(timeout: Duration, check: F) -> super::models::ComponentHealth
+    where
+        F: std::future::Future<Output = Result<(), String>>,
+    {
+        let start = Instant::now();
+        let status = match tokio::time::timeout(timeout, check).await {
+            Ok(Ok(())) => "healthy",
+            Ok(Err(error)) => {
+                tracing::warn!(error = %error, "health probe failed");
+                "unhealthy"
+            }
+            Err(_) => {
+                tracing::warn!("health probe timed out");
+                "unhealthy"
+            }
+        };
+        super::models::ComponentHealth {
+            status: status.to_string(),
+            latency_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+
+    let mut components = HashMap::new();
+    components.insert(
+        "api".to_string(),
+        super::models::ComponentHealth {
+            status: "healthy".to_string(),
+            latency_ms: 0,
+        },
+    );
+    components.insert(
+        "fjall".to_string(),
+        probe(timeout, async { state.store.health_check().map_err(|e| e.to_string()) }).await,
+    );
+    components.insert(
+        "task_broker".to_string(),
+        probe(timeout, async {
+            state.broker.queue_depth().await.map(|_| ()).map_err(|e| e.to_string())
+        })
+        .await,
+    );
+    components.insert(
+        "storage".to_string(),
+        probe(timeout, async {
+            state.storage.exists("healthcheck").await.map(|_| ()).map_err(|e| e.to_string())
+        })
+        .await,
+    );
+
+    let all_healthy = components.values().all(|c| c.status == "healthy");
+    let critical_healthy = state
+        .config
+        .health
+        .critical_components
+        .iter()
+        .all(|name| match components.get(name) {
+            Some(c) => c.status == "healthy",
+            None => true,
+        });
+
+    let overall_status = if all_healthy { "healthy" } else { "unhealthy" };
+    let status_code = if critical_healthy {
         axum::http::StatusCode::OK
     } else {
         axum::http::StatusCode::SERVICE_UNAVAILABLE
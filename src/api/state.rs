@@ -1,37 +1,55 @@
 use std::sync::Arc;
 
-use crate::config::Config;
+use crate::config::{Config, ConfigHandle};
 use crate::handlers::HandlerRegistry;
 use crate::ledger::FjallStore;
 use crate::observability::Metrics;
-use crate::queue::TaskBroker;
+use crate::queue::{Scheduler, TaskBroker};
 use crate::storage::StorageClient;
+use crate::worker::status_stream::StatusBroadcaster;
 
 #[derive(Clone)]
 pub struct AppState {
+    /// Snapshot of the config this process started with. Prefer
+    /// `config_handle` for anything that should pick up a hot reload.
     pub config: Arc<Config>,
+    /// Live config handle kept in sync by `crate::config::ConfigWatcher`.
+    pub config_handle: ConfigHandle,
     pub registry: Arc<HandlerRegistry>,
     pub store: Arc<FjallStore>,
     pub storage: Arc<StorageClient>,
     pub broker: Arc<TaskBroker>,
     pub metrics: Arc<Metrics>,
+    /// Fed by the worker pool as it processes tasks; backs
+    /// `GET /tasks/{seq}/events` (see [`crate::worker::status_stream`]).
+    pub status_broadcaster: Arc<StatusBroadcaster>,
+    /// Recurring jobs registered through [`crate::queue::scheduler`]
+    pub scheduler: Arc<Scheduler>,
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Config,
+        config_handle: ConfigHandle,
         registry: HandlerRegistry,
         store: FjallStore,
         storage: StorageClient,
         broker: Arc<TaskBroker>,
+        metrics: Arc<Metrics>,
+        status_broadcaster: Arc<StatusBroadcaster>,
+        scheduler: Arc<Scheduler>,
     ) -> Self {
         Self {
             config: Arc::new(config),
+            config_handle,
             registry: Arc::new(registry),
             store: Arc::new(store),
             storage: Arc::new(storage),
             broker,
-            metrics: Arc::new(Metrics::new()),
+            metrics,
+            status_broadcaster,
+            scheduler,
         }
     }
 }
@@ -41,6 +41,29 @@ pub fn validate_body_size(data: &[u8], max_size: usize) -> Result<(), ApiError>
     Ok(())
 }
 
+/// Validates the `X-Fetchbox-Job-Type` header value
+///
+/// Restricted to lowercase ASCII alphanumerics, `-`, and `_` - the same
+/// character set job types are registered under in
+/// [`crate::handlers::HandlerRegistry`] - so a typo'd or hostile header
+/// can't smuggle anything unexpected into log lines, metric labels, or
+/// storage key prefixes derived from it.
+pub fn validate_job_type(job_type: &str) -> Result<(), ApiError> {
+    let valid = !job_type.is_empty()
+        && job_type
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_');
+
+    if !valid {
+        return Err(ApiError::InvalidPayload(format!(
+            "invalid X-Fetchbox-Job-Type header: {}",
+            job_type
+        )));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +103,20 @@ mod tests {
             _ => panic!("Expected PayloadTooLarge error"),
         }
     }
+
+    #[test]
+    fn test_validate_job_type_valid() {
+        assert!(validate_job_type("default").is_ok());
+        assert!(validate_job_type("gallery").is_ok());
+        assert!(validate_job_type("my-job_type-1").is_ok());
+    }
+
+    #[test]
+    fn test_validate_job_type_invalid() {
+        assert!(validate_job_type("").is_err());
+        assert!(validate_job_type("Default").is_err());
+        assert!(validate_job_type("job type").is_err());
+        assert!(validate_job_type("job/type").is_err());
+        assert!(validate_job_type("job.type").is_err());
+    }
 }
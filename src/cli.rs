@@ -14,8 +14,14 @@ pub enum Commands {
     /// Run the FetchBox API service
     Api(ApiArgs),
 
-    /// Run the FetchBox download worker
-    Worker,
+    /// Run a standalone FetchBox download worker pool
+    Worker(WorkerArgs),
+
+    /// Inspect and replay dead-lettered tasks
+    Dlq(DlqArgs),
+
+    /// Inspect and validate the TOML configuration file
+    Config(ConfigArgs),
 }
 
 #[derive(clap::Args, Debug)]
@@ -28,3 +34,63 @@ pub struct ApiArgs {
     #[arg(long, default_value = "data/ledger")]
     pub ledger_path: String,
 }
+
+#[derive(clap::Args, Debug)]
+pub struct WorkerArgs {
+    /// Path to Fjall ledger storage (must match the `fetchbox api` instance
+    /// this worker drains tasks for; only one process may hold it at a time)
+    #[arg(long, default_value = "data/ledger")]
+    pub ledger_path: String,
+
+    /// Number of tasks processed concurrently; overrides `server.worker.num_workers`
+    #[arg(long)]
+    pub concurrency: Option<usize>,
+
+    /// Number of pending tasks pulled from the queue per poll; overrides
+    /// `server.worker.batch_size`
+    #[arg(long)]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DlqArgs {
+    /// Path to Fjall ledger storage (must match the `fetchbox api`/
+    /// `fetchbox worker` instance owning this queue; must not run
+    /// alongside one, since Fjall's per-path lock is exclusive)
+    #[arg(long, default_value = "data/ledger")]
+    pub ledger_path: String,
+
+    #[command(subcommand)]
+    pub command: DlqCommand,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Validate the configuration file (same checks as on startup / reload)
+    Check {
+        /// Collect and print every violation instead of stopping at the first
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DlqCommand {
+    /// Replay a single dead-lettered task by its Fjall sequence number
+    Replay {
+        /// Sequence number of the DLQ entry to replay
+        seq: u64,
+    },
+
+    /// Replay every dead-lettered task matching a failure code
+    ReplayAll {
+        /// Failure code to match, e.g. NETWORK_ERROR
+        failure_code: String,
+    },
+}
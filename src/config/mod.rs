@@ -29,19 +29,27 @@
 //! By default, the configuration is loaded from `config/fetchbox.toml`.
 //! This can be overridden using the `FETCHBOX_CONFIG` environment variable.
 
+mod discovery;
 mod models;
+mod reload;
 mod resolver;
 mod sources;
 mod validation;
 
 // Re-export public types
 pub use crate::humanize::ByteSize;
+pub use discovery::DiscoveryError;
 pub use models::{
-    ApiLimits, Config, HandlerConfig, ProxyConfig, ProxyEndpoint, ProxyPoolConfig,
-    ResolvedProxyPool, RetentionConfig, ServerConfig, StorageConfig, StorageProvider,
-    TelemetryConfig,
+    ApiLimits, AuthConfig, Config, EmailNotifyConfig, HandlerConfig, HealthConfig, IggyConfig,
+    NotifyConfig, ProxyConfig, ProxyDiscovery, ProxyEndpoint, ProxyEndpointKind,
+    ProxyPoolConfig, ResolvedProxyPool, ResolvedTier, RetentionConfig, RetrySchedule,
+    ServerConfig, StorageCompression, StorageConfig, StorageProvider, StorageRetryConfig,
+    TelemetryConfig, TenantAuth, TlsConfig, WasmModuleConfig, WasmModuleSource,
+    WebhookNotifyConfig, WorkerPoolConfig,
 };
-pub use resolver::{ProxyGraph, ResolverError};
+pub use reload::{ConfigHandle, ConfigWatcher, ReloadError};
+pub use resolver::{ProxyDirectory, ProxyGraph, ProxyResolver, ResolverError};
+pub use sources::resolved_config_path;
 pub use validation::ValidationError;
 
 use thiserror::Error;
@@ -77,6 +85,15 @@ impl Config {
         Ok(config)
     }
 
+    /// Load configuration without validating it.
+    ///
+    /// Only for callers that run their own validation pass afterwards, such
+    /// as `fetchbox config check` reporting violations instead of failing
+    /// to start.
+    pub fn load_unvalidated() -> Result<Self, ConfigError> {
+        Ok(sources::load()?)
+    }
+
     /// Load configuration from a specific path
     ///
     /// Useful for testing with custom configuration files.
@@ -90,6 +107,17 @@ impl Config {
     pub fn proxy_resolver(&self) -> ProxyGraph<'_> {
         ProxyGraph::new(&self.proxy)
     }
+
+    /// Validate this configuration, stopping at the first violation
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validation::validate(self)
+    }
+
+    /// Validate this configuration, collecting every violation instead of
+    /// stopping at the first one found (used by `fetchbox config check --all`)
+    pub fn validate_all(&self) -> Result<(), Vec<ValidationError>> {
+        validation::validate_all(self)
+    }
 }
 
 #[cfg(test)]
@@ -166,8 +194,8 @@ fallbacks = []
         let resolved = resolver.resolve("primary").unwrap();
 
         assert_eq!(resolved.tiers.len(), 2);
-        assert_eq!(resolved.tiers[0][0].uri, "http://primary:8080");
-        assert_eq!(resolved.tiers[1][0].uri, "http://backup:8080");
+        assert_eq!(resolved.tiers[0].endpoints[0].uri, "http://primary:8080");
+        assert_eq!(resolved.tiers[1].endpoints[0].uri, "http://backup:8080");
     }
 
     #[test]
@@ -217,6 +245,9 @@ job_ttl_days = 30
 ledger_max_bytes = "50GB"
 logs_ttl_days = 30
 
+[retention.overrides]
+gallery = 90
+
 [telemetry]
 metrics_addr = "0.0.0.0:9090"
 otlp_endpoint = "http://otel-collector:4317"
@@ -231,6 +262,7 @@ otlp_endpoint = "http://otel-collector:4317"
         assert_eq!(config.handlers.len(), 2);
         assert_eq!(config.proxy.pools.len(), 3);
         assert_eq!(config.retention.job_ttl_days, 30);
+        assert_eq!(config.retention.ttl_days_for("gallery", config.retention.job_ttl_days), 90);
         assert!(config.telemetry.otlp_endpoint.is_some());
 
         // Test proxy resolution
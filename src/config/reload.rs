@@ -0,0 +1,199 @@
+//! Hot-reload of the TOML config file without a process restart.
+//!
+//! A [`ConfigWatcher`] watches the config path for file-change notifications
+//! (plus SIGHUP on Unix as a manual trigger), debounces rapid events, and on
+//! each trigger re-parses and re-[`validate`](super::validation::validate)s
+//! the file. Only a config that passes validation is published - through a
+//! [`ConfigHandle`] backed by [`arc_swap::ArcSwap`] - to the rest of the
+//! process; a failing reload logs the error and leaves the previous config
+//! live. Fields that can't safely change without a restart (see
+//! [`RESTART_REQUIRED_FIELDS`]) are pinned to their old value across a swap
+//! and reported via a warning rather than silently applied.
+
+use super::models::Config;
+use super::ConfigError;
+use arc_swap::ArcSwap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+/// How long to wait after the first file-change event before reloading, so
+/// that editors which write-then-rename (or write in several small flushes)
+/// collapse into a single reload instead of several.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Capacity of the reload-notification broadcast channel (see
+/// [`ConfigWatcher::subscribe`]).
+const RELOAD_CHANNEL_CAPACITY: usize = 16;
+
+/// Dotted paths of fields that require a process restart to take effect.
+/// A reload that changes one of these keeps the old value live for that
+/// field and surfaces a warning naming it, rather than applying it
+/// half-way or rejecting the whole reload.
+const RESTART_REQUIRED_FIELDS: &[&str] =
+    &["server.bind_addr", "server.fjall_path", "iggy.endpoint", "storage.provider"];
+
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    #[error("failed to watch config file: {0}")]
+    Watch(#[from] notify::Error),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Cheaply-cloneable handle to the live [`Config`].
+///
+/// Subsystems that need to observe reloads should hold a `ConfigHandle`
+/// (an `Arc` bump to clone) rather than a `Config` snapshot taken once at
+/// startup.
+#[derive(Clone)]
+pub struct ConfigHandle {
+    current: Arc<ArcSwap<Config>>,
+}
+
+impl ConfigHandle {
+    pub fn new(config: Config) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(config)),
+        }
+    }
+
+    /// Current config snapshot. Cheap; safe to call per-request.
+    pub fn load(&self) -> Arc<Config> {
+        self.current.load_full()
+    }
+
+    fn store(&self, config: Config) {
+        self.current.store(Arc::new(config));
+    }
+}
+
+/// Watches a TOML config file (plus SIGHUP on Unix) and hot-swaps a
+/// [`ConfigHandle`] on validated changes.
+pub struct ConfigWatcher {
+    handle: ConfigHandle,
+    path: PathBuf,
+    reload_tx: broadcast::Sender<Arc<Config>>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf, initial: Config) -> Self {
+        let (reload_tx, _) = broadcast::channel(RELOAD_CHANNEL_CAPACITY);
+        Self {
+            handle: ConfigHandle::new(initial),
+            path,
+            reload_tx,
+        }
+    }
+
+    /// Handle for reading the live config; clone freely.
+    pub fn handle(&self) -> ConfigHandle {
+        self.handle.clone()
+    }
+
+    /// Subscribe to be notified with the new config every time a reload
+    /// swaps it in. Lagging subscribers miss intermediate reloads but can
+    /// always catch up via [`ConfigHandle::load`].
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Config>> {
+        self.reload_tx.subscribe()
+    }
+
+    /// Spawn the watch loop as a background task. Runs until the process
+    /// exits; the returned `notify` watcher is kept alive inside the task.
+    pub fn spawn(self) -> Result<(), ReloadError> {
+        let (fs_tx, mut fs_rx) = tokio::sync::mpsc::channel(RELOAD_CHANNEL_CAPACITY);
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if matches!(res, Ok(event) if event.kind.is_modify() || event.kind.is_create()) {
+                let _ = fs_tx.blocking_send(());
+            }
+        })?;
+        watcher.watch(&self.path, notify::RecursiveMode::NonRecursive)?;
+
+        tokio::spawn(async move {
+            let _watcher = watcher; // keep alive for the task's lifetime
+
+            #[cfg(unix)]
+            let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+                .expect("failed to install SIGHUP handler");
+
+            loop {
+                #[cfg(unix)]
+                let triggered = tokio::select! {
+                    event = fs_rx.recv() => event.is_some(),
+                    _ = sighup.recv() => true,
+                };
+                #[cfg(not(unix))]
+                let triggered = fs_rx.recv().await.is_some();
+
+                if !triggered {
+                    break;
+                }
+
+                tokio::time::sleep(DEBOUNCE).await;
+                while fs_rx.try_recv().is_ok() {}
+
+                self.reload();
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Re-parse and re-validate the config file, publishing it through
+    /// [`ConfigHandle`] only on success.
+    fn reload(&self) {
+        let old = self.handle.load();
+
+        match Config::load_from_path(self.path.clone()) {
+            Ok(mut new_config) => {
+                let restart_fields = pin_restart_required_fields(&old, &mut new_config);
+                let new_config = Arc::new(new_config);
+                self.handle.store((*new_config).clone());
+                let _ = self.reload_tx.send(new_config);
+
+                if restart_fields.is_empty() {
+                    info!(path = %self.path.display(), "config reloaded");
+                } else {
+                    warn!(
+                        path = %self.path.display(),
+                        fields = ?restart_fields,
+                        "config reloaded; these fields changed but require a restart to take effect",
+                    );
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "config reload failed, keeping previous configuration live");
+            }
+        }
+    }
+}
+
+/// Overwrite every field in `new` listed in [`RESTART_REQUIRED_FIELDS`] that
+/// differs from `old` back to `old`'s value, and return the dotted names of
+/// the fields that were pinned this way.
+fn pin_restart_required_fields(old: &Config, new: &mut Config) -> Vec<&'static str> {
+    let mut pinned = Vec::new();
+
+    if old.server.bind_addr != new.server.bind_addr {
+        new.server.bind_addr = old.server.bind_addr;
+        pinned.push("server.bind_addr");
+    }
+    if old.server.fjall_path != new.server.fjall_path {
+        new.server.fjall_path = old.server.fjall_path.clone();
+        pinned.push("server.fjall_path");
+    }
+    if old.iggy.endpoint != new.iggy.endpoint {
+        new.iggy.endpoint = old.iggy.endpoint.clone();
+        pinned.push("iggy.endpoint");
+    }
+    if old.storage.provider != new.storage.provider {
+        new.storage.provider = old.storage.provider.clone();
+        pinned.push("storage.provider");
+    }
+
+    pinned
+}
@@ -17,11 +17,7 @@ pub fn load() -> Result<Config, ConfigError> {
     // Load .env file if it exists (ignore errors if file doesn't exist)
     let _ = dotenvy::dotenv();
 
-    let config_path = env::var(CONFIG_ENV_VAR)
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from(DEFAULT_CONFIG_PATH));
-
-    let mut config = load_from_sources(config_path)?;
+    let mut config = load_from_sources(resolved_config_path())?;
 
     // Load secrets from environment variables
     load_secrets(&mut config);
@@ -29,28 +25,52 @@ pub fn load() -> Result<Config, ConfigError> {
     Ok(config)
 }
 
+/// The config file path [`load`] reads from: `FETCHBOX_CONFIG` if set,
+/// otherwise [`DEFAULT_CONFIG_PATH`]. Exposed so callers that need to watch
+/// the same file (e.g. [`super::reload::ConfigWatcher`]) don't re-derive
+/// the env var lookup themselves.
+pub fn resolved_config_path() -> PathBuf {
+    env::var(CONFIG_ENV_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_CONFIG_PATH))
+}
+
 /// Load secrets from environment variables into config
 /// Secrets are never stored in TOML files, only in environment
 fn load_secrets(config: &mut Config) {
-    // Load S3 credentials
-    if let Ok(access_key) = env::var("S3_ACCESS_KEY") {
-        config.storage.access_key = Some(access_key);
-    }
-    if let Ok(secret_key) = env::var("S3_SECRET_KEY") {
-        config.storage.secret_key = Some(secret_key);
-    }
+    config.storage.access_key = resolve_secret(&["S3_ACCESS_KEY", "AWS_ACCESS_KEY_ID"]);
+    config.storage.secret_key = resolve_secret(&["S3_SECRET_KEY", "AWS_SECRET_ACCESS_KEY"]);
+}
 
-    // Alternative: AWS-style environment variable names
-    if config.storage.access_key.is_none() {
-        if let Ok(access_key) = env::var("AWS_ACCESS_KEY_ID") {
-            config.storage.access_key = Some(access_key);
+/// Resolve a secret, trying each name in `names` in order and preferring
+/// `{name}_FILE` (read and trimmed of trailing whitespace) over the plain
+/// `{name}` value - Docker/Kubernetes commonly mount secrets as files and
+/// expose their paths via a `_FILE`-suffixed variable, so operators aren't
+/// forced to inline credentials into the process environment. Returns the
+/// first name that resolves either way; future secrets (proxy credentials,
+/// telemetry tokens) should go through this same helper rather than reading
+/// `env::var` directly.
+fn resolve_secret(names: &[&str]) -> Option<String> {
+    for name in names {
+        let file_var = format!("{name}_FILE");
+        if let Ok(path) = env::var(&file_var) {
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => {
+                    tracing::debug!(var = %file_var, "Resolved secret from file");
+                    return Some(contents.trim_end().to_string());
+                }
+                Err(e) => {
+                    tracing::warn!(var = %file_var, path, error = %e, "Failed to read secret file");
+                }
+            }
         }
-    }
-    if config.storage.secret_key.is_none() {
-        if let Ok(secret_key) = env::var("AWS_SECRET_ACCESS_KEY") {
-            config.storage.secret_key = Some(secret_key);
+
+        if let Ok(value) = env::var(name) {
+            tracing::debug!(var = %name, "Resolved secret from environment variable");
+            return Some(value);
         }
     }
+    None
 }
 
 /// Load configuration from a specific path and environment
@@ -166,6 +186,9 @@ job_ttl_days = 30
 ledger_max_bytes = "50GB"
 logs_ttl_days = 30
 
+[retention.overrides]
+gallery = 90
+
 [telemetry]
 metrics_addr = "0.0.0.0:9090"
         "#;
@@ -198,5 +221,6 @@ metrics_addr = "0.0.0.0:9090"
             config.retention.ledger_max_bytes.as_u64(),
             50 * 1024 * 1024 * 1024
         );
+        assert_eq!(config.retention.overrides.get("gallery"), Some(&90));
     }
 }
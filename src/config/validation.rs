@@ -1,4 +1,4 @@
-use super::models::{Config, StorageProvider};
+use super::models::{Config, StorageCompression, StorageProvider};
 use crate::humanize::ByteSize;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use thiserror::Error;
@@ -31,25 +31,84 @@ pub enum ValidationError {
 
     #[error("Ledger max bytes must be positive")]
     InvalidLedgerMaxBytes,
+
+    #[error("retention.low_watermark_fraction must be in (0.0, 1.0], got {0}")]
+    InvalidLowWatermarkFraction(f64),
+
+    #[error("retention.tranquility must be >= 0.0, got {0}")]
+    InvalidTranquility(f64),
+
+    #[error("storage.compression zstd level must be in 1..=22, got {0}")]
+    InvalidZstdLevel(i32),
+
+    #[error("Storage provider is {provider:?} but missing credentials (access_key or secret_key)")]
+    MissingStorageCredentials { provider: StorageProvider },
+
+    #[error("Storage provider is File but missing storage.path")]
+    MissingStoragePath,
+
+    #[error(
+        "worker.lease_heartbeat_interval_secs ({heartbeat}) must be less than worker.visibility_timeout_secs ({visibility}), or a still-in-progress download's lease will expire before it can renew"
+    )]
+    HeartbeatNotBelowVisibilityTimeout { heartbeat: u64, visibility: u64 },
+
+    #[error(
+        "handlers.{job_type}.wasm_module is configured, but this build has no WASM execution engine wired in - every job submitted against '{job_type}' would fail at runtime. See crate::handlers::wasm for the host ABI this is a skeleton for"
+    )]
+    WasmExecutionEngineUnavailable { job_type: String },
 }
 
-/// Validate the entire configuration
+/// Validate the entire configuration, stopping at the first violation.
+///
+/// A thin wrapper over [`validate_all`] for call sites that only care
+/// whether the config is valid (config loading, hot reload); use
+/// `validate_all` when every violation should be reported at once (`fetchbox
+/// config check --all`).
 pub fn validate(config: &Config) -> Result<(), ValidationError> {
-    validate_handlers(config)?;
-    validate_proxy_pools(config)?;
-    validate_manifest_size(config)?;
-    validate_storage(config)?;
-    validate_retention(config)?;
-    Ok(())
+    match validate_all(config) {
+        Ok(()) => Ok(()),
+        Err(mut errors) => Err(errors.remove(0)),
+    }
+}
+
+/// Validate the entire configuration, collecting every violation instead of
+/// stopping at the first one found.
+pub fn validate_all(config: &Config) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    errors.extend(validate_handlers(config));
+    errors.extend(validate_proxy_pools(config));
+    errors.extend(validate_manifest_size(config));
+    errors.extend(validate_storage(config));
+    errors.extend(validate_retention(config));
+    errors.extend(validate_iggy(config));
+    errors.extend(validate_worker_pool(config));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
 
 /// Ensure at least one handler exists and all handlers reference valid proxy pools
-fn validate_handlers(config: &Config) -> Result<(), ValidationError> {
+fn validate_handlers(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
     if config.handlers.is_empty() {
-        return Err(ValidationError::NoHandlersConfigured);
+        errors.push(ValidationError::NoHandlersConfigured);
     }
 
     for (handler_name, handler_config) in &config.handlers {
+        // wasm_module names a real host ABI (see crate::handlers::wasm), but
+        // this build has no wasmtime engine to actually execute it against -
+        // fail config validation up front rather than silently accepting the
+        // config and failing every job against this handler at runtime.
+        if handler_config.wasm_module.is_some() {
+            errors.push(ValidationError::WasmExecutionEngineUnavailable {
+                job_type: handler_name.clone(),
+            });
+        }
+
         // Skip proxy validation if no proxy_pool configured (v0 allows optional proxies)
         if let Some(ref proxy_pool) = handler_config.proxy_pool {
             // Extract pool name from proxy_pool (may be "pools/default" or just "default")
@@ -58,7 +117,7 @@ fn validate_handlers(config: &Config) -> Result<(), ValidationError> {
                 .unwrap_or(proxy_pool);
 
             if !config.proxy.pools.contains_key(pool_name) {
-                return Err(ValidationError::InvalidProxyPoolReference {
+                errors.push(ValidationError::InvalidProxyPoolReference {
                     handler: handler_name.clone(),
                     pool: proxy_pool.clone(),
                 });
@@ -66,18 +125,20 @@ fn validate_handlers(config: &Config) -> Result<(), ValidationError> {
         }
     }
 
-    Ok(())
+    errors
 }
 
 /// Validate proxy pool fallback chains for cycles and invalid references
-fn validate_proxy_pools(config: &Config) -> Result<(), ValidationError> {
+fn validate_proxy_pools(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
     // Check all fallback references exist
     for (pool_name, pool_config) in &config.proxy.pools {
         for fallback in &pool_config.fallbacks {
             let fallback_name = fallback.strip_prefix("pools/").unwrap_or(fallback);
 
             if !config.proxy.pools.contains_key(fallback_name) {
-                return Err(ValidationError::InvalidFallbackReference {
+                errors.push(ValidationError::InvalidFallbackReference {
                     pool: pool_name.clone(),
                     fallback: fallback.clone(),
                 });
@@ -85,93 +146,183 @@ fn validate_proxy_pools(config: &Config) -> Result<(), ValidationError> {
         }
     }
 
-    // Detect cycles using DFS
+    // Detect cycles using DFS from every pool, deduplicating cycles found
+    // more than once (e.g. from two different starting nodes on the same loop)
+    let mut seen_cycles = HashSet::new();
     for pool_name in config.proxy.pools.keys() {
-        detect_cycles(pool_name, &config.proxy.pools, &mut HashSet::new(), &mut Vec::new())?;
+        let mut path = Vec::new();
+        if let Some(cycle) = find_cycle(pool_name, &config.proxy.pools, &mut path) {
+            if seen_cycles.insert(canonical_cycle(&cycle)) {
+                errors.push(ValidationError::ProxyFallbackCycle {
+                    path: cycle.join(" -> "),
+                });
+            }
+        }
     }
 
-    Ok(())
+    errors
 }
 
-/// DFS-based cycle detection in proxy fallback chains
-fn detect_cycles(
+/// DFS-based cycle detection in proxy fallback chains; returns the first
+/// cycle found starting from `current`, if any.
+fn find_cycle(
     current: &str,
     pools: &HashMap<String, super::models::ProxyPoolConfig>,
-    visited: &mut HashSet<String>,
     path: &mut Vec<String>,
-) -> Result<(), ValidationError> {
-    if path.contains(&current.to_string()) {
-        // Cycle detected
-        path.push(current.to_string());
-        return Err(ValidationError::ProxyFallbackCycle {
-            path: path.join(" -> "),
-        });
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|p| p == current) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(current.to_string());
+        return Some(cycle);
     }
 
-    if visited.contains(current) {
-        return Ok(()); // Already explored this path
-    }
-
-    visited.insert(current.to_string());
     path.push(current.to_string());
 
     if let Some(pool) = pools.get(current) {
         for fallback in &pool.fallbacks {
             let fallback_name = fallback.strip_prefix("pools/").unwrap_or(fallback);
-            detect_cycles(fallback_name, pools, visited, path)?;
+            if let Some(cycle) = find_cycle(fallback_name, pools, path) {
+                path.pop();
+                return Some(cycle);
+            }
         }
     }
 
     path.pop();
-    Ok(())
+    None
+}
+
+/// Normalize a cycle (a closed walk `a -> b -> a`) so the same loop found
+/// from different starting nodes compares equal, by rotating to start at
+/// its lexicographically smallest node.
+fn canonical_cycle(cycle: &[String]) -> Vec<String> {
+    let nodes = &cycle[..cycle.len() - 1]; // drop the repeated closing node
+    let Some(min_idx) = (0..nodes.len()).min_by_key(|&i| &nodes[i]) else {
+        return Vec::new();
+    };
+    nodes[min_idx..].iter().chain(nodes[..min_idx].iter()).cloned().collect()
 }
 
 /// Ensure max_payload_bytes doesn't exceed 5MB (unless explicitly overridden)
-fn validate_manifest_size(config: &Config) -> Result<(), ValidationError> {
+fn validate_manifest_size(config: &Config) -> Vec<ValidationError> {
     const MAX_PAYLOAD_BYTES: u64 = 5 * 1024 * 1024; // 5 MB
 
     if config.server.api.max_payload_bytes.as_u64() > MAX_PAYLOAD_BYTES {
-        return Err(ValidationError::ManifestSizeExceedsLimit {
+        vec![ValidationError::ManifestSizeExceedsLimit {
             actual: config.server.api.max_payload_bytes.as_u64(),
             limit: MAX_PAYLOAD_BYTES,
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Validate storage credentials when provider is S3/Azure, that `path` is
+/// set when provider is File, and the compression codec level
+fn validate_storage(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if config.storage.provider == StorageProvider::S3
+        && (config.storage.access_key.is_none() || config.storage.secret_key.is_none())
+    {
+        errors.push(ValidationError::MissingS3Credentials);
+    }
+
+    if config.storage.provider == StorageProvider::Azure
+        && (config.storage.access_key.is_none() || config.storage.secret_key.is_none())
+    {
+        errors.push(ValidationError::MissingStorageCredentials {
+            provider: StorageProvider::Azure,
         });
     }
 
-    Ok(())
-}
+    if config.storage.provider == StorageProvider::File && config.storage.path.is_none() {
+        errors.push(ValidationError::MissingStoragePath);
+    }
 
-/// Validate storage credentials when provider is S3
-fn validate_storage(config: &Config) -> Result<(), ValidationError> {
-    if config.storage.provider == StorageProvider::S3 {
-        if config.storage.access_key.is_none() || config.storage.secret_key.is_none() {
-            return Err(ValidationError::MissingS3Credentials);
+    if let StorageCompression::Zstd { level } = config.storage.compression {
+        if !(1..=22).contains(&level) {
+            errors.push(ValidationError::InvalidZstdLevel(level));
         }
     }
 
-    Ok(())
+    errors
+}
+
+/// Validate the Iggy endpoint uses a supported scheme
+fn validate_iggy(config: &Config) -> Vec<ValidationError> {
+    let endpoint = &config.iggy.endpoint;
+    if endpoint.starts_with("iggy://") || endpoint.starts_with("tcp://") {
+        Vec::new()
+    } else {
+        let scheme = endpoint.split("://").next().unwrap_or(endpoint).to_string();
+        vec![ValidationError::InvalidIggyScheme { scheme }]
+    }
 }
 
 /// Validate retention configuration
-fn validate_retention(config: &Config) -> Result<(), ValidationError> {
+fn validate_retention(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
     if config.retention.job_ttl_days == 0 {
-        return Err(ValidationError::InvalidRetentionTTL {
+        errors.push(ValidationError::InvalidRetentionTTL {
             field: "job_ttl_days".to_string(),
             value: 0,
         });
     }
 
     if config.retention.logs_ttl_days == 0 {
-        return Err(ValidationError::InvalidRetentionTTL {
+        errors.push(ValidationError::InvalidRetentionTTL {
             field: "logs_ttl_days".to_string(),
             value: 0,
         });
     }
 
     if config.retention.ledger_max_bytes.as_u64() == 0 {
-        return Err(ValidationError::InvalidLedgerMaxBytes);
+        errors.push(ValidationError::InvalidLedgerMaxBytes);
+    }
+
+    for (key, ttl_days) in &config.retention.overrides {
+        if *ttl_days == 0 {
+            errors.push(ValidationError::InvalidRetentionTTL {
+                field: format!("overrides.{key}"),
+                value: 0,
+            });
+        }
     }
 
-    Ok(())
+    let low_watermark = config.retention.low_watermark_fraction;
+    if !(low_watermark > 0.0 && low_watermark <= 1.0) {
+        errors.push(ValidationError::InvalidLowWatermarkFraction(low_watermark));
+    }
+
+    if config.retention.prune_interval_secs == 0 {
+        errors.push(ValidationError::InvalidRetentionTTL {
+            field: "prune_interval_secs".to_string(),
+            value: 0,
+        });
+    }
+
+    if config.retention.tranquility < 0.0 {
+        errors.push(ValidationError::InvalidTranquility(config.retention.tranquility));
+    }
+
+    errors
+}
+
+/// Ensure a worker can actually renew its dispatch lease before it expires
+fn validate_worker_pool(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let worker = &config.server.worker;
+    if worker.lease_heartbeat_interval_secs >= worker.visibility_timeout_secs {
+        errors.push(ValidationError::HeartbeatNotBelowVisibilityTimeout {
+            heartbeat: worker.lease_heartbeat_interval_secs,
+            visibility: worker.visibility_timeout_secs,
+        });
+    }
+
+    errors
 }
 
 #[cfg(test)]
@@ -201,6 +352,8 @@ mod tests {
                 fallbacks: vec![],
                 retry_backoff_ms: 500,
                 max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
             },
         );
 
@@ -209,9 +362,11 @@ mod tests {
             iggy: IggyConfig::default(),
             storage: StorageConfig::default(),
             handlers,
-            proxy: ProxyConfig { pools },
+            proxy: ProxyConfig { pools, ..Default::default() },
             retention: RetentionConfig::default(),
             telemetry: TelemetryConfig::default(),
+            auth: AuthConfig::default(),
+            health: HealthConfig::default(),
         }
     }
 
@@ -242,6 +397,22 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_wasm_module_rejected_without_execution_engine() {
+        let mut config = create_test_config();
+        config.handlers.get_mut("default").unwrap().wasm_module = Some(WasmModuleConfig {
+            source: WasmModuleSource::Directory { directory: "/opt/fetchbox/modules".into() },
+            fuel_limit: 10_000_000,
+            memory_limit_bytes: 64 * 1024 * 1024,
+        });
+
+        let result = validate(&config);
+        assert!(matches!(
+            result,
+            Err(ValidationError::WasmExecutionEngineUnavailable { .. })
+        ));
+    }
+
     #[test]
     fn test_cycle_detection() {
         let mut config = create_test_config();
@@ -254,6 +425,8 @@ mod tests {
                 fallbacks: vec!["pool_b".to_string()],
                 retry_backoff_ms: 500,
                 max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
             },
         );
 
@@ -264,6 +437,8 @@ mod tests {
                 fallbacks: vec!["pool_a".to_string()],
                 retry_backoff_ms: 500,
                 max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
             },
         );
 
@@ -312,6 +487,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_azure_credentials_missing() {
+        let mut config = create_test_config();
+        config.storage.provider = StorageProvider::Azure;
+        config.storage.access_key = None;
+        config.storage.secret_key = None;
+
+        let result = validate(&config);
+        assert!(matches!(
+            result,
+            Err(ValidationError::MissingStorageCredentials { provider: StorageProvider::Azure })
+        ));
+    }
+
+    #[test]
+    fn test_file_path_missing() {
+        let mut config = create_test_config();
+        config.storage.provider = StorageProvider::File;
+        config.storage.path = None;
+
+        let result = validate(&config);
+        assert!(matches!(result, Err(ValidationError::MissingStoragePath)));
+    }
+
     #[test]
     fn test_invalid_iggy_scheme() {
         let mut config = create_test_config();
@@ -335,4 +534,141 @@ mod tests {
             Err(ValidationError::InvalidRetentionTTL { .. })
         ));
     }
+
+    #[test]
+    fn test_zero_retention_override_ttl() {
+        let mut config = create_test_config();
+        config.retention.overrides.insert("gallery".to_string(), 0);
+
+        let result = validate(&config);
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvalidRetentionTTL { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_low_watermark_fraction() {
+        let mut config = create_test_config();
+        config.retention.low_watermark_fraction = 1.5;
+
+        let result = validate(&config);
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvalidLowWatermarkFraction(_))
+        ));
+    }
+
+    #[test]
+    fn test_zero_prune_interval() {
+        let mut config = create_test_config();
+        config.retention.prune_interval_secs = 0;
+
+        let result = validate(&config);
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvalidRetentionTTL { .. })
+        ));
+    }
+
+    #[test]
+    fn test_negative_tranquility() {
+        let mut config = create_test_config();
+        config.retention.tranquility = -1.0;
+
+        let result = validate(&config);
+        assert!(matches!(result, Err(ValidationError::InvalidTranquility(_))));
+    }
+
+    #[test]
+    fn test_invalid_zstd_level() {
+        let mut config = create_test_config();
+        config.storage.compression = StorageCompression::Zstd { level: 0 };
+
+        let result = validate(&config);
+        assert!(matches!(result, Err(ValidationError::InvalidZstdLevel(_))));
+    }
+
+    #[test]
+    fn test_valid_zstd_level() {
+        let mut config = create_test_config();
+        config.storage.compression = StorageCompression::Zstd { level: 19 };
+
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_iggy_scheme_via_validate_all() {
+        let mut config = create_test_config();
+        config.iggy.endpoint = "http://localhost:8090".to_string();
+
+        let result = validate_all(&config);
+        assert!(matches!(
+            result,
+            Err(errors) if errors.iter().any(|e| matches!(e, ValidationError::InvalidIggyScheme { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_validate_all_collects_every_violation() {
+        let mut config = create_test_config();
+        config.handlers.clear();
+        config.retention.job_ttl_days = 0;
+        config.retention.tranquility = -1.0;
+
+        let errors = validate_all(&config).unwrap_err();
+        assert!(errors.iter().any(|e| matches!(e, ValidationError::NoHandlersConfigured)));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidRetentionTTL { field, .. } if field == "job_ttl_days")));
+        assert!(errors.iter().any(|e| matches!(e, ValidationError::InvalidTranquility(_))));
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn test_validate_matches_first_error_from_validate_all() {
+        let mut config = create_test_config();
+        config.handlers.clear();
+        config.retention.job_ttl_days = 0;
+
+        let all_errors = validate_all(&config).unwrap_err();
+        let first_error = validate(&config).unwrap_err();
+        assert_eq!(first_error.to_string(), all_errors[0].to_string());
+    }
+
+    #[test]
+    fn test_cycle_detected_from_either_starting_node_reports_once() {
+        let mut config = create_test_config();
+
+        config.proxy.pools.insert(
+            "pool_a".to_string(),
+            ProxyPoolConfig {
+                primary: vec!["http://a:8080".to_string()],
+                fallbacks: vec!["pool_b".to_string()],
+                retry_backoff_ms: 500,
+                max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
+            },
+        );
+
+        config.proxy.pools.insert(
+            "pool_b".to_string(),
+            ProxyPoolConfig {
+                primary: vec!["http://b:8080".to_string()],
+                fallbacks: vec!["pool_a".to_string()],
+                retry_backoff_ms: 500,
+                max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
+            },
+        );
+
+        let errors = validate_all(&config).unwrap_err();
+        let cycle_count = errors
+            .iter()
+            .filter(|e| matches!(e, ValidationError::ProxyFallbackCycle { .. }))
+            .count();
+        assert_eq!(cycle_count, 1);
+    }
 }
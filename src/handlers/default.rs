@@ -64,6 +64,7 @@ impl JobHandler for DefaultHandler {
                     &self.config.default_headers,
                     storage_hint,
                     proxy_hint,
+                    ctx.manifest.attributes.clone(),
                 )
             })
             .collect();
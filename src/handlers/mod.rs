@@ -7,6 +7,8 @@
 //!
 //! - [`JobHandler`] - Main trait for implementing custom handlers
 //! - [`DefaultHandler`] - Built-in handler that echoes manifests
+//! - [`wasm::WasmJobHandler`] - Handler backed by a runtime-loaded `.wasm`
+//!   module instead of a compiled-in implementation
 //! - [`HandlerRegistry`] - Registry for managing handler instances
 //! - [`ManifestContext`] - Context passed to handlers
 //! - [`DownloadTask`] - Individual download task emitted by handlers
@@ -28,12 +30,14 @@ mod default;
 mod registry;
 mod traits;
 pub(crate) mod types;
+pub mod wasm;
 
 pub use default::DefaultHandler;
 pub use registry::{
     HandlerConfig, HandlerRegistry, ProxyConfig, RegistryError, StorageConfig,
 };
 pub use traits::{HandlerError, JobHandler};
+pub use wasm::WasmJobHandler;
 pub use types::{
     DownloadTask, HeadersMap, JobSummary, ManifestContext, PreparedManifest,
     ProxyHint, StorageHint, TaskContext,
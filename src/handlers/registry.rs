@@ -99,6 +99,36 @@ impl HandlerRegistry {
         self.handlers.contains_key(job_type)
     }
 
+    /// Register a WASM-backed handler (see [`super::wasm::WasmJobHandler`])
+    /// for every job type in `handlers` that names a `wasm_module`,
+    /// overriding any native handler already registered under that job
+    /// type. In practice this never registers anything against a validated
+    /// config: [`crate::config::validation`] rejects `wasm_module` entries
+    /// outright since [`super::wasm`] has no execution engine wired in yet.
+    pub fn register_wasm_handlers(
+        &mut self,
+        handlers: &std::collections::HashMap<String, crate::config::HandlerConfig>,
+    ) {
+        for (job_type, handler_config) in handlers {
+            let Some(wasm_config) = &handler_config.wasm_module else {
+                continue;
+            };
+
+            let wasm_handler = Arc::new(super::wasm::WasmJobHandler::new(
+                job_type.clone(),
+                wasm_config.clone(),
+            ));
+            let config = HandlerConfig {
+                handler: format!("wasm::{}", job_type),
+                default_headers: HeadersMap::new(),
+                proxy: None,
+                storage: None,
+                options: Value::Object(Map::new()),
+            };
+            self.register(job_type.clone(), wasm_handler, config);
+        }
+    }
+
     /// Create default registry with built-in handlers
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
@@ -76,6 +76,7 @@ impl DownloadTask {
         default_headers: &HeadersMap,
         storage_hint: Option<StorageHint>,
         proxy_hint: Option<ProxyHint>,
+        attributes: Option<Value>,
     ) -> Self {
         let mut headers = default_headers.clone();
         headers.extend(resource.headers.clone());
@@ -89,7 +90,7 @@ impl DownloadTask {
             proxy_hint,
             storage_hint,
             tags: resource.tags.clone(),
-            attributes: None,
+            attributes,
         }
     }
 
@@ -98,10 +99,25 @@ impl DownloadTask {
         use crate::proto::{DownloadTask, HttpHeader, TaskAttributes};
         use uuid::Uuid;
 
+        // `mime_hint` carries the first declared entry of `attributes`'s
+        // `allowed_mime_types`, if any - a forward-looking expectation, not
+        // an authoritative check. [`crate::worker::validate::ContentValidator`]
+        // re-parses the full list out of `extra` once bytes are in hand and
+        // compares it against the type it sniffs from the body itself.
+        let mime_hint = self
+            .attributes
+            .as_ref()
+            .and_then(|v| v.get("allowed_mime_types"))
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
         let attributes = TaskAttributes {
             tags: self.tags.clone().into_iter().collect(), // BTreeMap → HashMap
             checksum_hint: String::new(),
-            mime_hint: String::new(),
+            mime_hint,
             extra: self
                 .attributes
                 .as_ref()
@@ -269,7 +285,7 @@ mod tests {
             fallback_pools: vec![],
         });
 
-        let task = DownloadTask::from_resource(&resource, &default_headers, storage, proxy);
+        let task = DownloadTask::from_resource(&resource, &default_headers, storage, proxy, None);
 
         assert_eq!(task.resource_name, "resource-1.jpg");
         assert_eq!(task.url, "https://example.com/file.jpg");
@@ -278,4 +294,51 @@ mod tests {
         assert!(task.proxy_hint.is_some());
         assert_eq!(task.tags.len(), 1);
     }
+
+    #[test]
+    fn test_to_proto_derives_mime_hint_from_attributes() {
+        let resource = Resource {
+            name: "resource-1.jpg".to_string(),
+            url: "https://example.com/file.jpg".to_string(),
+            headers: HeadersMap::new(),
+            tags: HeadersMap::new(),
+        };
+        let attributes = serde_json::json!({"allowed_mime_types": ["image/jpeg", "image/png"]});
+        let task = DownloadTask::from_resource(
+            &resource,
+            &HeadersMap::new(),
+            None,
+            None,
+            Some(attributes),
+        );
+
+        let ctx = TaskContext {
+            job_id: "job-1".to_string(),
+            job_type: "default".to_string(),
+            tenant: "tenant-1".to_string(),
+            manifest_key: "manifest.json".to_string(),
+        };
+        let proto = task.to_proto(&ctx);
+        assert_eq!(proto.attributes.unwrap().mime_hint, "image/jpeg");
+    }
+
+    #[test]
+    fn test_to_proto_mime_hint_empty_without_attributes() {
+        let resource = Resource {
+            name: "resource-1.jpg".to_string(),
+            url: "https://example.com/file.jpg".to_string(),
+            headers: HeadersMap::new(),
+            tags: HeadersMap::new(),
+        };
+        let task = DownloadTask::from_resource(&resource, &HeadersMap::new(), None, None, None);
+
+        let ctx = TaskContext {
+            job_id: "job-1".to_string(),
+            job_type: "default".to_string(),
+            tenant: "tenant-1".to_string(),
+            manifest_key: "manifest.json".to_string(),
+        };
+        let proto = task.to_proto(&ctx);
+        assert_eq!(proto.attributes.unwrap().mime_hint, "");
+    }
 }
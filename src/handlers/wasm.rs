@@ -0,0 +1,256 @@
+//! WASM-pluggable job handlers - **skeleton, not yet functional**
+//!
+//! This module is a skeleton for letting an operator add a new job type
+//! without recompiling the server, not a working feature: [`execute_module`]
+//! unconditionally errors, so every job submitted against a
+//! [`WasmModuleConfig`]-backed job type fails. [`crate::config::validation`]
+//! rejects any config that sets `handlers.*.wasm_module` for exactly this
+//! reason - fail at config-load time with a clear message, rather than
+//! accepting the config and failing every job against it later.
+//!
+//! What's real: [`WasmJobHandler`] registers under a job type exactly like a
+//! native [`JobHandler`](super::traits::JobHandler) implementation (see
+//! [`HandlerRegistry::register_wasm_handlers`](super::registry::HandlerRegistry::register_wasm_handlers)),
+//! and the host ABI below is the intended contract for when an execution
+//! engine lands. What's missing: `wasmtime` isn't a dependency of this tree,
+//! and [`execute_module`] doesn't run anything.
+//!
+//! ## Host ABI (not yet implemented)
+//!
+//! A module would export:
+//! - `alloc(len: i32) -> i32` - reserve `len` bytes in the module's linear
+//!   memory and return the offset, so the host has somewhere to write the
+//!   input
+//! - `build_tasks(ptr: i32, len: i32) -> i64` - given the offset/length of a
+//!   JSON-encoded [`PreparedManifest`] (`job_id`, `job_type`, and the
+//!   manifest) written into memory at `ptr`, run the module's task-building
+//!   logic and return a packed `(out_ptr << 32) | out_len` pointing at a
+//!   JSON-encoded `Vec<DownloadTask>` written back into the same memory
+//!
+//! The host would never call the guest's `dealloc` - each invocation would
+//! get a fresh, fuel-and-memory-bounded `wasmtime::Store` torn down after
+//! the call, so there'd be nothing to free.
+//!
+//! ## Sandboxing (not yet implemented)
+//!
+//! Each call would run against a fresh store configured with
+//! [`WasmModuleConfig::fuel_limit`] fuel and a
+//! [`WasmModuleConfig::memory_limit_bytes`] memory ceiling, so a module that
+//! burns through either traps instead of spinning or ballooning the host
+//! process. A trap (or any other execution failure) would surface as
+//! [`HandlerError::Fatal`] tagged with the module's job type, which
+//! `ingest_job`'s existing `ApiError::Internal(format!("Handler failed:
+//! {}", e))` mapping (see [`crate::api::services::ingest_job`]) already
+//! carries through to the client unchanged - that plumbing works today even
+//! though nothing produces a real (non-stub) error through it yet.
+//!
+//! See [`crate::config::discovery::resolve_dns_srv`] for the same
+//! "interface real, engine missing" situation with DNS SRV discovery.
+
+use async_trait::async_trait;
+
+use super::traits::{HandlerError, JobHandler};
+use super::types::{DownloadTask, JobSummary, ManifestContext, PreparedManifest};
+use crate::config::{WasmModuleConfig, WasmModuleSource};
+
+/// A job type's handler, backed by a `.wasm` module instead of a compiled-in
+/// [`JobHandler`] implementation.
+#[derive(Debug, Clone)]
+pub struct WasmJobHandler {
+    job_type: String,
+    config: WasmModuleConfig,
+}
+
+impl WasmJobHandler {
+    pub fn new(job_type: impl Into<String>, config: WasmModuleConfig) -> Self {
+        Self {
+            job_type: job_type.into(),
+            config,
+        }
+    }
+
+    /// Human-readable module location, for error messages and logs
+    fn source_description(&self) -> String {
+        match &self.config.source {
+            WasmModuleSource::Directory { directory } => {
+                format!("{}/{}.wasm", directory.display(), self.job_type)
+            }
+            WasmModuleSource::ObjectStorage { prefix } => {
+                format!("{}{}.wasm", prefix, self.job_type)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for WasmJobHandler {
+    /// No module-specific validation hook in the host ABI (see the module
+    /// docs) - the manifest is handed to the module as-is, and any
+    /// rejection happens inside `build_tasks` instead.
+    async fn prepare_manifest(
+        &self,
+        ctx: ManifestContext,
+    ) -> Result<PreparedManifest, HandlerError> {
+        Ok(PreparedManifest {
+            context: ctx,
+            handler_data: None,
+        })
+    }
+
+    async fn build_tasks(
+        &self,
+        prepared: PreparedManifest,
+    ) -> Result<Vec<DownloadTask>, HandlerError> {
+        let input = serde_json::to_vec(&WasmPreparedManifest::from(&prepared))
+            .map_err(|e| HandlerError::TaskGeneration(e.to_string()))?;
+
+        let output = execute_module(&self.job_type, &self.source_description(), &self.config, input)
+            .await
+            .map_err(|e| {
+                HandlerError::Fatal(format!(
+                    "wasm module '{}' ({}) failed: {}",
+                    self.job_type,
+                    self.source_description(),
+                    e
+                ))
+            })?;
+
+        serde_json::from_slice(&output).map_err(|e| {
+            HandlerError::TaskGeneration(format!(
+                "wasm module '{}' returned malformed tasks: {}",
+                self.job_type, e
+            ))
+        })
+    }
+
+    async fn finalize_job(&self, _summary: JobSummary) -> Result<(), HandlerError> {
+        // The host ABI only exposes `build_tasks`; finalization stays a
+        // no-op for WASM-backed job types until a `finalize_job` export is
+        // added to the ABI.
+        Ok(())
+    }
+}
+
+/// Wire shape for the manifest handed across the host ABI - a trimmed,
+/// serializable view of [`PreparedManifest`] (which itself isn't
+/// `Serialize`, since `handler_data` is an arbitrary [`serde_json::Value`]
+/// that native handlers use for their own in-process bookkeeping)
+#[derive(Debug, serde::Serialize)]
+struct WasmPreparedManifest<'a> {
+    job_id: &'a str,
+    job_type: &'a str,
+    manifest: &'a crate::api::models::Manifest,
+}
+
+impl<'a> From<&'a PreparedManifest> for WasmPreparedManifest<'a> {
+    fn from(prepared: &'a PreparedManifest) -> Self {
+        Self {
+            job_id: &prepared.context.job_id,
+            job_type: &prepared.context.job_type,
+            manifest: &prepared.context.manifest,
+        }
+    }
+}
+
+/// Would run `input` (a JSON-encoded [`WasmPreparedManifest`]) through
+/// `job_type`'s module and return the JSON-encoded `Vec<DownloadTask>` it
+/// produces, enforcing `config`'s fuel and memory caps.
+///
+/// Always fails: `wasmtime` isn't a dependency of this tree - see the module
+/// docs. [`crate::config::validation`] refuses to start the server with a
+/// `wasm_module` configured at all, so in practice this is unreachable from
+/// a validated config; it stays callable (and tested) so the signature and
+/// caller-side plumbing in [`WasmJobHandler::build_tasks`] are exercised
+/// ahead of a real engine landing.
+async fn execute_module(
+    job_type: &str,
+    source: &str,
+    config: &WasmModuleConfig,
+    _input: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    let _ = job_type;
+    let _ = source;
+    let _ = config.fuel_limit;
+    let _ = config.memory_limit_bytes;
+    Err("wasmtime execution engine isn't wired into this tree yet".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::{Manifest, Resource};
+    use crate::handlers::types::HeadersMap;
+    use serde_json::{Map, Value};
+
+    fn config(source: WasmModuleSource) -> WasmModuleConfig {
+        WasmModuleConfig {
+            source,
+            fuel_limit: 10_000_000,
+            memory_limit_bytes: 64 * 1024 * 1024,
+        }
+    }
+
+    fn ctx() -> ManifestContext {
+        ManifestContext {
+            job_id: "job-1".to_string(),
+            job_type: "custom".to_string(),
+            manifest: Manifest {
+                manifest_version: "v1".to_string(),
+                storage: crate::api::models::StorageConfig {
+                    manifest_file: "metadata.json".to_string(),
+                    resource_key_prefix: "resources/".to_string(),
+                },
+                metadata: Value::Object(Map::new()),
+                resources: vec![Resource {
+                    name: "resource-1".to_string(),
+                    url: "https://example.com/file".to_string(),
+                    headers: HeadersMap::new(),
+                    tags: HeadersMap::new(),
+                }],
+                attributes: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_source_description_directory() {
+        let handler = WasmJobHandler::new(
+            "custom",
+            config(WasmModuleSource::Directory {
+                directory: "/opt/fetchbox/modules".into(),
+            }),
+        );
+        assert_eq!(
+            handler.source_description(),
+            "/opt/fetchbox/modules/custom.wasm"
+        );
+    }
+
+    #[test]
+    fn test_source_description_object_storage() {
+        let handler = WasmJobHandler::new(
+            "custom",
+            config(WasmModuleSource::ObjectStorage {
+                prefix: "handlers/".to_string(),
+            }),
+        );
+        assert_eq!(handler.source_description(), "handlers/custom.wasm");
+    }
+
+    #[tokio::test]
+    async fn test_build_tasks_surfaces_trap_with_module_name() {
+        let handler = WasmJobHandler::new(
+            "custom",
+            config(WasmModuleSource::Directory {
+                directory: "/opt/fetchbox/modules".into(),
+            }),
+        );
+
+        let prepared = handler.prepare_manifest(ctx()).await.unwrap();
+        let err = handler.build_tasks(prepared).await.unwrap_err();
+
+        assert!(matches!(err, HandlerError::Fatal(_)));
+        assert!(err.to_string().contains("custom"));
+        assert!(err.to_string().contains("/opt/fetchbox/modules/custom.wasm"));
+    }
+}
@@ -15,6 +15,9 @@ pub enum ParseError {
 
     #[error("Invalid unit: {0}")]
     InvalidUnit(String),
+
+    #[error("Size {0} overflows u64 bytes")]
+    Overflow(String),
 }
 
 /// Byte size wrapper with human-readable parsing
@@ -37,18 +40,28 @@ impl ByteSize {
 
         for (i, &(unit, divisor)) in UNITS.iter().enumerate().rev() {
             if self.0 >= divisor {
-                let value = self.0 / divisor;
+                let whole = self.0 / divisor;
                 let remainder = self.0 % divisor;
 
-                if remainder == 0 || i == 0 {
-                    return format!("{}{}", value, unit);
+                if i == 0 {
+                    return format!("{whole}{unit}");
+                }
+
+                // Round the remainder to the nearest tenth of `unit` rather
+                // than truncating, so e.g. 1.95GB doesn't print as "1.9GB".
+                let tenths = (remainder * 10 + divisor / 2) / divisor;
+                // Near u64::MAX, rounding up would render a size that no
+                // longer fits on re-parse, so it stays at `.9` instead.
+                let (whole, tenths) = if tenths >= 10 && (whole + 1).checked_mul(divisor).is_some() {
+                    (whole + 1, 0)
                 } else {
-                    let decimal = (remainder * 10 / divisor) as u64;
-                    if decimal > 0 {
-                        return format!("{}.{}{}", value, decimal, unit);
-                    }
-                    return format!("{}{}", value, unit);
+                    (whole, tenths.min(9))
+                };
+
+                if tenths == 0 {
+                    return format!("{whole}{unit}");
                 }
+                return format!("{whole}.{tenths}{unit}");
             }
         }
 
@@ -89,36 +102,99 @@ impl<'de> Deserialize<'de> for ByteSize {
     }
 }
 
-impl FromStr for ByteSize {
-    type Err = ParseError;
+impl ByteSize {
+    /// Parse a size string the same as [`FromStr`], but treat the ambiguous
+    /// `KB`/`MB`/`GB`/`TB` suffixes as SI (1000-based) rather than the
+    /// 1024-based default `FromStr` uses for backward compatibility.
+    /// `KiB`/`MiB`/`GiB`/`TiB` are always 1024-based either way.
+    pub fn from_str_si(s: &str) -> Result<Self, ParseError> {
+        parse(s, UnitSystem::Si)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim().to_uppercase();
+#[derive(Clone, Copy)]
+enum UnitSystem {
+    /// `KB`/`MB`/`GB`/`TB` mean 1024-based, matching this crate's original
+    /// (technically incorrect, but long-standing) behavior.
+    Binary,
+    /// `KB`/`MB`/`GB`/`TB` mean 1000-based, per the SI/JEDEC definitions.
+    Si,
+}
 
-        // Try to parse as plain number first
-        if let Ok(num) = s.parse::<u64>() {
-            return Ok(ByteSize(num));
-        }
+fn unit_multiplier(unit: &str, system: UnitSystem) -> Option<u128> {
+    let decimal = matches!(system, UnitSystem::Si);
+    Some(match unit {
+        "B" => 1,
+        "K" | "KB" => if decimal { 1_000 } else { 1024 },
+        "KIB" => 1024,
+        "M" | "MB" => if decimal { 1_000u128.pow(2) } else { 1024u128.pow(2) },
+        "MIB" => 1024u128.pow(2),
+        "G" | "GB" => if decimal { 1_000u128.pow(3) } else { 1024u128.pow(3) },
+        "GIB" => 1024u128.pow(3),
+        "T" | "TB" => if decimal { 1_000u128.pow(4) } else { 1024u128.pow(4) },
+        "TIB" => 1024u128.pow(4),
+        _ => return None,
+    })
+}
+
+/// Parse a (possibly fractional) mantissa times `multiplier`, rounding to
+/// the nearest byte using integer math rather than floats, and rejecting
+/// anything that overflows `u64`.
+fn scaled_by(mantissa: &str, multiplier: u128, original: &str) -> Result<u64, ParseError> {
+    let (int_part, frac_part) = match mantissa.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, frac_part),
+        None => (mantissa, ""),
+    };
+
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(ParseError::InvalidFormat(original.to_string()));
+    }
+
+    let int_value: u128 = if int_part.is_empty() { 0 } else { int_part.parse()? };
+    let frac_value: u128 = if frac_part.is_empty() { 0 } else { frac_part.parse()? };
+    let overflow = || ParseError::Overflow(original.to_string());
+    let scale = 10u128.checked_pow(frac_part.len() as u32).ok_or_else(overflow)?;
+
+    let numerator = int_value
+        .checked_mul(scale)
+        .and_then(|v| v.checked_add(frac_value))
+        .and_then(|v| v.checked_mul(multiplier))
+        .ok_or_else(overflow)?;
+    let rounded = numerator.checked_add(scale / 2).ok_or_else(overflow)? / scale;
+
+    u64::try_from(rounded).map_err(|_| overflow())
+}
+
+fn parse(s: &str, system: UnitSystem) -> Result<ByteSize, ParseError> {
+    let trimmed = s.trim();
+    let upper = trimmed.to_uppercase();
+
+    // Try to parse as a plain integer byte count first
+    if let Ok(num) = upper.parse::<u64>() {
+        return Ok(ByteSize(num));
+    }
+
+    // Split into a digits-and-dot mantissa and a unit suffix
+    let pos = upper
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| ParseError::InvalidFormat(trimmed.to_string()))?;
+    let (mantissa, unit) = (&upper[..pos], upper[pos..].trim());
 
-        // Parse with unit suffix
-        let (num_str, unit) = if let Some(pos) = s.find(|c: char| !c.is_ascii_digit()) {
-            (&s[..pos], &s[pos..])
-        } else {
-            return Err(ParseError::InvalidFormat(s.to_string()));
-        };
+    let multiplier = unit_multiplier(unit, system)
+        .ok_or_else(|| ParseError::InvalidUnit(unit.to_string()))?;
 
-        let num: u64 = num_str.parse()?;
+    let bytes = scaled_by(mantissa, multiplier, trimmed)?;
+    Ok(ByteSize(bytes))
+}
 
-        let multiplier = match unit.trim() {
-            "B" => 1,
-            "K" | "KB" | "KIB" => 1024,
-            "M" | "MB" | "MIB" => 1024 * 1024,
-            "G" | "GB" | "GIB" => 1024 * 1024 * 1024,
-            "T" | "TB" | "TIB" => 1024 * 1024 * 1024 * 1024,
-            _ => return Err(ParseError::InvalidUnit(unit.to_string())),
-        };
+impl FromStr for ByteSize {
+    type Err = ParseError;
 
-        Ok(ByteSize(num * multiplier))
+    /// `KB`/`MB`/`GB`/`TB` are 1024-based here for backward compatibility;
+    /// use [`ByteSize::from_str_si`] for the 1000-based reading of those
+    /// same suffixes. `KiB`/`MiB`/`GiB`/`TiB` are always 1024-based.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, UnitSystem::Binary)
     }
 }
 
@@ -191,4 +267,70 @@ mod tests {
         assert_eq!(format!("{}", ByteSize(1024)), "1KB");
         assert_eq!(format!("{}", ByteSize(5 * 1024 * 1024)), "5MB");
     }
+
+    #[test]
+    fn test_parse_fractional() {
+        assert_eq!("1.5GB".parse::<ByteSize>().unwrap().as_u64(), 1024 * 1024 * 1024 * 3 / 2);
+        assert_eq!("0.5MB".parse::<ByteSize>().unwrap().as_u64(), 1024 * 1024 / 2);
+        assert_eq!(
+            "2.25TB".parse::<ByteSize>().unwrap().as_u64(),
+            (1024u64 * 1024 * 1024 * 1024) * 9 / 4
+        );
+    }
+
+    #[test]
+    fn test_parse_fractional_rounds_to_nearest_byte() {
+        // 1.005KB = 1029.12 bytes, rounds to 1029
+        assert_eq!("1.005KB".parse::<ByteSize>().unwrap().as_u64(), 1029);
+    }
+
+    #[test]
+    fn test_binary_vs_si_units() {
+        assert_eq!("1KB".parse::<ByteSize>().unwrap().as_u64(), 1024);
+        assert_eq!(ByteSize::from_str_si("1KB").unwrap().as_u64(), 1000);
+
+        // KiB is always 1024-based regardless of unit system
+        assert_eq!(ByteSize::from_str_si("1KiB").unwrap().as_u64(), 1024);
+        assert_eq!("1GiB".parse::<ByteSize>().unwrap().as_u64(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_overflow_rejected() {
+        let result = "99999999999999999999999TB".parse::<ByteSize>();
+        assert!(matches!(result, Err(ParseError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_to_human_readable_one_fractional_digit() {
+        assert_eq!(ByteSize(1536).to_human_readable(), "1.5KB");
+        assert_eq!(ByteSize(1024 + 973).to_human_readable(), "2KB"); // 1997 bytes rounds up past 1.95KB
+    }
+
+    #[test]
+    fn test_round_trip_within_one_tenth_of_unit() {
+        for &bytes in &[1536u64, 1_500_000, 5_000_000_000, 2_684_354_560] {
+            let size = ByteSize(bytes);
+            let human = size.to_human_readable();
+            let reparsed = human.parse::<ByteSize>().unwrap();
+
+            let (unit, divisor) = [
+                ("TB", 1024u64.pow(4)),
+                ("GB", 1024u64.pow(3)),
+                ("MB", 1024u64.pow(2)),
+                ("KB", 1024),
+                ("B", 1),
+            ]
+            .into_iter()
+            .find(|&(u, _)| human.ends_with(u))
+            .unwrap();
+            let _ = unit;
+
+            let diff = bytes.abs_diff(reparsed.as_u64());
+            assert!(
+                diff <= divisor / 10 + 1,
+                "{human} reparsed to {} bytes, original was {bytes}",
+                reparsed.as_u64()
+            );
+        }
+    }
 }
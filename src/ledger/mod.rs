@@ -17,11 +17,17 @@
 ///
 /// ## Retention Policies
 ///
-/// - Jobs: 30 days (configurable via TOML in Task 05)
-/// - Logs: 30 days (aligned with Iggy `jobs.logs` stream)
-/// - Idempotency: 14 days (shorter window for dedup)
-///
-/// Pruning is triggered manually via `FjallStore::prune_expired()`.
+/// - Jobs: `retention.job_ttl_days` (default 30), overridable per `job_type`
+///   via `retention.overrides` (see [`crate::config::RetentionConfig`])
+/// - Logs: `retention.logs_ttl_days` (default 30), overridable via
+///   `retention.overrides.logs`
+/// - Idempotency: `retention.idempotency_ttl_days` (default 14), scoped per
+///   tenant so two tenants sharing a key don't collide
+///
+/// Pruning can be triggered manually via `FjallStore::prune_expired()`, or
+/// left to the background sweep spawned by [`scheduler::run`], which paces
+/// itself by `retention.tranquility` on a `retention.prune_interval_secs`
+/// timer so it doesn't compete with foreground request latency.
 ///
 /// ## Usage
 ///
@@ -36,10 +42,9 @@
 pub mod error;
 pub mod partitions;
 pub mod pruning;
+pub mod scheduler;
 pub mod store;
 
 pub use error::{LedgerError, Result};
-pub use pruning::{
-    PruneStats, RETENTION_IDEMPOTENCY_DAYS, RETENTION_JOBS_DAYS, RETENTION_LOGS_DAYS,
-};
-pub use store::{FjallStore, StoreStats};
+pub use pruning::PruneStats;
+pub use store::{FjallStore, LogRecord, ScheduleEntry, StoreStats};
@@ -3,7 +3,8 @@
 /// Partition structure:
 /// - `jobs`: job:{job_id} -> JobSnapshot (JSON)
 /// - `logs`: log:{job_id}:{offset:016} -> LogEntry (JSON)
-/// - `idempotency`: idem:{key} -> job_id (string)
+/// - `idempotency`: idem:{tenant.len()}:{tenant}:{key} -> IdempotencyRecord
+///   (JSON, see [`super::store::IdempotencyRecord`])
 /// - `metadata`: meta:{key} -> value (JSON/string)
 
 /// Encode a job key: job:{job_id}
@@ -39,9 +40,17 @@ pub fn decode_log_key(key: &[u8]) -> Option<(String, u64)> {
     Some((job_id, offset))
 }
 
-/// Encode an idempotency key: idem:{key}
-pub fn encode_idem_key(key: &str) -> Vec<u8> {
-    format!("idem:{}", key).into_bytes()
+/// Encode a tenant-scoped idempotency key: idem:{tenant.len()}:{tenant}:{key}
+///
+/// Scoping by tenant means two tenants that happen to pick the same
+/// `X-Fetchbox-Idempotency-Key` don't collide and return each other's jobs -
+/// but `key` is attacker-controlled (`X-Fetchbox-Idempotency-Key`, only
+/// checked for non-empty) and plain `tenant:key` colon-joining would let
+/// tenant `"foo"` submit key `"bar:realkey"` and collide with tenant
+/// `"foo:bar"`'s key `"realkey"`. The length prefix fixes the tenant/key
+/// boundary unambiguously regardless of colons in either part.
+pub fn encode_idem_key(tenant: &str, key: &str) -> Vec<u8> {
+    format!("idem:{}:{}:{}", tenant.len(), tenant, key).into_bytes()
 }
 
 /// Encode a metadata key: meta:{key}
@@ -49,6 +58,35 @@ pub fn encode_meta_key(key: &str) -> Vec<u8> {
     format!("meta:{}", key).into_bytes()
 }
 
+/// Encode a CAS index key: meta:cas:{integrity} -> storage key, so the
+/// [`crate::storage::cas`] content-addressed blob a given integrity string
+/// (e.g. `sha256-<hex>`) maps to can be looked up before re-uploading it -
+/// see [`crate::api::services::ingest_job`]
+pub fn encode_cas_key(integrity: &str) -> Vec<u8> {
+    encode_meta_key(&format!("cas:{}", integrity))
+}
+
+/// Encode a manifest-resources key: meta:resources:{job_id} -> storage key,
+/// so the `resources` vector a job's manifest was split into at ingest (the
+/// "fat" part - see [`crate::api::services::ingest_job`]) can be found
+/// without bloating [`crate::api::models::JobSnapshot`] with it
+pub fn encode_manifest_resources_key(job_id: &str) -> Vec<u8> {
+    encode_meta_key(&format!("resources:{}", job_id))
+}
+
+/// Encode a recurring-job schedule key: meta:schedule:{id}, so
+/// [`super::store::ScheduleEntry`] survives a restart (see
+/// [`super::store::FjallStore::upsert_schedule`])
+pub fn encode_schedule_key(id: &str) -> Vec<u8> {
+    encode_meta_key(&format!("schedule:{}", id))
+}
+
+/// Decode a schedule key: meta:schedule:{id} -> id
+pub fn decode_schedule_key(key: &[u8]) -> Option<String> {
+    let key_str = std::str::from_utf8(key).ok()?;
+    key_str.strip_prefix("meta:schedule:").map(String::from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,8 +122,17 @@ mod tests {
 
     #[test]
     fn test_idem_key_encoding() {
-        let key = encode_idem_key("test-key");
-        assert_eq!(key, b"idem:test-key");
+        let key = encode_idem_key("tenant-a", "test-key");
+        assert_eq!(key, b"idem:8:tenant-a:test-key");
+    }
+
+    #[test]
+    fn test_idem_key_no_cross_tenant_collision_via_colon() {
+        // Without the length prefix, tenant "foo" + key "bar:realkey" would
+        // encode identically to tenant "foo:bar" + key "realkey".
+        let a = encode_idem_key("foo", "bar:realkey");
+        let b = encode_idem_key("foo:bar", "realkey");
+        assert_ne!(a, b);
     }
 
     #[test]
@@ -93,4 +140,25 @@ mod tests {
         let key = encode_meta_key("last_prune");
         assert_eq!(key, b"meta:last_prune");
     }
+
+    #[test]
+    fn test_cas_key_encoding() {
+        let key = encode_cas_key("sha256-abcd1234");
+        assert_eq!(key, b"meta:cas:sha256-abcd1234");
+    }
+
+    #[test]
+    fn test_manifest_resources_key_encoding() {
+        let key = encode_manifest_resources_key("job_123");
+        assert_eq!(key, b"meta:resources:job_123");
+    }
+
+    #[test]
+    fn test_schedule_key_encoding() {
+        let key = encode_schedule_key("nightly-crawl");
+        assert_eq!(key, b"meta:schedule:nightly-crawl");
+
+        let decoded = decode_schedule_key(&key).unwrap();
+        assert_eq!(decoded, "nightly-crawl");
+    }
 }
@@ -0,0 +1,29 @@
+/// Background ledger retention sweep
+///
+/// Runs for the lifetime of the process (spawned once from
+/// `crate::api::server::run`), periodically invoking
+/// [`super::store::FjallStore::prune_expired_throttled`] on a timer driven
+/// by `retention.prune_interval_secs`. Unlike the on-demand
+/// `FjallStore::prune_expired`, the throttled sweep paces its deletes by
+/// `retention.tranquility` so it doesn't compete with foreground request
+/// latency on a large ledger.
+use tracing::{error, info};
+
+use super::store::FjallStore;
+use crate::config::RetentionConfig;
+
+/// Sweep the ledger for expired/oversized entries every
+/// `retention.prune_interval_secs`, for as long as the process runs
+pub async fn run(store: FjallStore, retention: RetentionConfig) {
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(retention.prune_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        match store.prune_expired_throttled(&retention).await {
+            Ok(stats) => info!(?stats, "Background ledger prune sweep complete"),
+            Err(e) => error!(error = %e, "Background ledger prune sweep failed"),
+        }
+    }
+}
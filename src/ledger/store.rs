@@ -1,15 +1,66 @@
 use std::path::Path;
 
 use fjall::{Config, Keyspace, PartitionCreateOptions, PartitionHandle};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use tracing::{debug, info};
 
-use crate::api::models::JobSnapshot;
+use crate::api::models::{JobError, JobListQuery, JobSnapshot, JobStatus};
 
 use super::error::Result;
 use super::partitions::{
-    encode_idem_key, encode_job_key, encode_log_key, encode_log_prefix, encode_meta_key,
+    decode_schedule_key, encode_cas_key, encode_idem_key, encode_job_key, encode_log_key,
+    encode_log_prefix, encode_manifest_resources_key, encode_meta_key, encode_schedule_key,
 };
-use super::pruning::{prune_expired, PruneStats};
+use super::pruning::{prune_expired, prune_expired_throttled, PruneStats};
+use crate::proto::DownloadTask;
+
+/// Capacity of the job snapshot change broadcast channel (see [`FjallStore::subscribe_events`])
+const EVENTS_CHANNEL_CAPACITY: usize = 256;
+
+/// Stored value for an idempotency record: the job it resolved to, plus the
+/// time it was recorded, so [`FjallStore::get_idempotent`] and the
+/// background prune sweep (see [`super::pruning`]) can tell a record older
+/// than `retention.idempotency_ttl_days` apart from a live one
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct IdempotencyRecord {
+    pub job_id: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A recurring download job registered with
+/// [`crate::queue::scheduler::Scheduler`], persisted in the `metadata`
+/// partition so it survives a restart
+///
+/// `job_template` is re-enqueued as-is on every fire except for `job_id`
+/// and `trace_id`, which [`crate::queue::scheduler::Scheduler`] stamps
+/// fresh each time so runs don't collide in the ledger or get confused for
+/// retries of one another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub id: String,
+    pub job_template: DownloadTask,
+    pub interval_secs: u64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub next_fire_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A single structured log line captured for a job, written by
+/// [`crate::worker::job_log::JobLogLayer`] and read back by
+/// [`FjallStore::read_logs`] for `GET /operators/jobs/{job_id}/logs`-style
+/// handlers. `timestamp` must stay named as-is and keep the
+/// `ts_seconds` encoding - [`super::pruning`]'s expiry scan decodes only
+/// that field, independent of whatever else this struct grows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: std::collections::BTreeMap<String, String>,
+}
 
 /// Fjall-backed persistent storage for job snapshots, logs, and metadata
 #[derive(Clone)]
@@ -19,6 +70,17 @@ pub struct FjallStore {
     logs: PartitionHandle,
     idempotency: PartitionHandle,
     metadata: PartitionHandle,
+    /// Broadcasts a clone of the updated [`JobSnapshot`] whenever
+    /// `record_resource_outcome` folds a result into it. A single global
+    /// channel (filtered by `job_id` at the subscriber) is simpler than a
+    /// per-job channel registry and fits the single-process architecture;
+    /// see `GET /operators/jobs/{job_id}/events`.
+    events: broadcast::Sender<JobSnapshot>,
+    /// Monotonic offset for [`FjallStore::append_log`]/`append_log_batch` -
+    /// global rather than per-job since it only needs to order entries
+    /// within a given job's `encode_log_prefix` range, not start at zero
+    /// for each one
+    log_seq: std::sync::Arc<std::sync::atomic::AtomicU64>,
 }
 
 impl FjallStore {
@@ -41,6 +103,8 @@ impl FjallStore {
         let idempotency = keyspace.open_partition("idempotency", PartitionCreateOptions::default())?;
         let metadata = keyspace.open_partition("metadata", PartitionCreateOptions::default())?;
 
+        let (events, _) = broadcast::channel(EVENTS_CHANNEL_CAPACITY);
+
         info!("Fjall store opened successfully");
         Ok(Self {
             keyspace,
@@ -48,9 +112,21 @@ impl FjallStore {
             logs,
             idempotency,
             metadata,
+            events,
+            log_seq: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
         })
     }
 
+    /// Subscribe to job snapshot change events, for SSE endpoints
+    ///
+    /// Events are broadcast globally; subscribers filter by `job_id`
+    /// themselves. Lagged subscribers simply miss intermediate updates -
+    /// callers should re-read via [`FjallStore::get`] after resubscribing
+    /// if they need the latest state.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<JobSnapshot> {
+        self.events.subscribe()
+    }
+
     /// Store or update a job snapshot
     pub fn upsert(&self, snapshot: JobSnapshot) -> Result<()> {
         let key = encode_job_key(&snapshot.job_id);
@@ -72,28 +148,257 @@ impl FjallStore {
         }
     }
 
-    /// Remember an idempotency key -> job_id mapping
-    pub fn remember_idempotency(&self, key: String, job_id: String) -> Result<()> {
-        let idem_key = encode_idem_key(&key);
-        self.idempotency.insert(idem_key, job_id.as_bytes())?;
-        debug!("Remembered idempotency: {} -> {}", key, job_id);
+    /// Remember a tenant-scoped idempotency key -> job_id mapping, stamped
+    /// with the current time so [`FjallStore::get_idempotent`] and the
+    /// background prune sweep (see [`super::pruning`]) can tell expired
+    /// records apart from live ones
+    pub fn remember_idempotency(&self, tenant: &str, key: &str, job_id: String) -> Result<()> {
+        let idem_key = encode_idem_key(tenant, key);
+        let record = IdempotencyRecord {
+            job_id: job_id.clone(),
+            created_at: chrono::Utc::now(),
+        };
+        self.idempotency.insert(idem_key, serde_json::to_vec(&record)?)?;
+        debug!(tenant, key, job_id, "Remembered idempotency");
         Ok(())
     }
 
-    /// Check if an idempotency key exists and return the associated job_id
-    pub fn get_idempotent(&self, key: &str) -> Result<Option<String>> {
-        let idem_key = encode_idem_key(key);
-        match self.idempotency.get(idem_key)? {
-            Some(value) => {
-                let job_id = String::from_utf8_lossy(&value).to_string();
-                Ok(Some(job_id))
-            }
+    /// Check if a tenant-scoped idempotency key exists and return the
+    /// associated job_id, unless the record is older than `ttl_days` - an
+    /// expired record is treated as absent so the caller reprocesses the
+    /// job (see [`crate::api::services::ingest_job`])
+    pub fn get_idempotent(&self, tenant: &str, key: &str, ttl_days: u32) -> Result<Option<String>> {
+        let idem_key = encode_idem_key(tenant, key);
+        let Some(value) = self.idempotency.get(idem_key)? else {
+            return Ok(None);
+        };
+        let record: IdempotencyRecord = serde_json::from_slice(&value)?;
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(ttl_days as i64);
+        if record.created_at < cutoff {
+            return Ok(None);
+        }
+        Ok(Some(record.job_id))
+    }
+
+    /// Remember an integrity string (e.g. `sha256-<hex>`, see
+    /// [`crate::storage::cas`]) -> storage key mapping, so a later ingest of
+    /// the same manifest bytes can short-circuit the upload
+    pub fn remember_cas_entry(&self, integrity: &str, storage_key: &str) -> Result<()> {
+        let cas_key = encode_cas_key(integrity);
+        self.metadata.insert(cas_key, storage_key.as_bytes())?;
+        debug!("Remembered CAS entry: {} -> {}", integrity, storage_key);
+        Ok(())
+    }
+
+    /// Look up the storage key a previously-seen integrity string maps to
+    pub fn get_cas_entry(&self, integrity: &str) -> Result<Option<String>> {
+        let cas_key = encode_cas_key(integrity);
+        match self.metadata.get(cas_key)? {
+            Some(value) => Ok(Some(String::from_utf8_lossy(&value).to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Remember the storage key holding a job's resources vector - the
+    /// "fat" part of its manifest, split off at ingest time (see
+    /// [`crate::api::services::ingest_job`]) - so it can be fetched on
+    /// demand without [`JobSnapshot`] having to carry it
+    pub fn remember_manifest_resources(&self, job_id: &str, storage_key: &str) -> Result<()> {
+        let key = encode_manifest_resources_key(job_id);
+        self.metadata.insert(key, storage_key.as_bytes())?;
+        debug!("Remembered manifest resources for {}: {}", job_id, storage_key);
+        Ok(())
+    }
+
+    /// Look up the storage key holding a job's resources vector, if one was
+    /// recorded at ingest time
+    pub fn get_manifest_resources_key(&self, job_id: &str) -> Result<Option<String>> {
+        let key = encode_manifest_resources_key(job_id);
+        match self.metadata.get(key)? {
+            Some(value) => Ok(Some(String::from_utf8_lossy(&value).to_string())),
             None => Ok(None),
         }
     }
 
-    /// Prune expired entries based on retention policies
-    pub fn prune_expired(&self) -> Result<PruneStats> {
+    /// Persist (or overwrite) a recurring job's [`ScheduleEntry`], so
+    /// [`crate::queue::scheduler::Scheduler`] survives a process restart
+    pub fn upsert_schedule(&self, entry: &ScheduleEntry) -> Result<()> {
+        let key = encode_schedule_key(&entry.id);
+        self.metadata.insert(key, serde_json::to_vec(entry)?)?;
+        debug!(id = %entry.id, "Upserted schedule entry");
+        Ok(())
+    }
+
+    /// Drop a recurring job's schedule entry; a no-op if `id` is unknown
+    pub fn remove_schedule(&self, id: &str) -> Result<()> {
+        self.metadata.remove(encode_schedule_key(id))?;
+        debug!(id, "Removed schedule entry");
+        Ok(())
+    }
+
+    /// List every persisted [`ScheduleEntry`], in no particular order -
+    /// called once at startup by [`crate::queue::scheduler::Scheduler::load`]
+    pub fn list_schedules(&self) -> Result<Vec<ScheduleEntry>> {
+        let mut entries = Vec::new();
+        for item in self.metadata.iter() {
+            let (key, value) = item?;
+            if decode_schedule_key(&key).is_none() {
+                continue;
+            }
+            entries.push(serde_json::from_slice(&value)?);
+        }
+        Ok(entries)
+    }
+
+    /// Append a single [`LogRecord`] to `job_id`'s log stream. Prefer
+    /// [`FjallStore::append_log_batch`] from a hot path - see
+    /// [`crate::worker::job_log`], which batches writes precisely so
+    /// high-volume logging doesn't call this one record at a time.
+    pub fn append_log(&self, job_id: &str, record: &LogRecord) -> Result<()> {
+        let offset = self.log_seq.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let key = encode_log_key(job_id, offset);
+        self.logs.insert(key, serde_json::to_vec(record)?)?;
+        Ok(())
+    }
+
+    /// Append a batch of `(job_id, LogRecord)` pairs, flushing once at the
+    /// end rather than after every insert - see
+    /// [`crate::worker::job_log::spawn_log_writer`]
+    pub fn append_log_batch(&self, batch: &[(String, LogRecord)]) -> Result<()> {
+        for (job_id, record) in batch {
+            self.append_log(job_id, record)?;
+        }
+        self.persist()?;
+        Ok(())
+    }
+
+    /// Read back every [`LogRecord`] for `job_id`, oldest first - the
+    /// `encode_log_key` offset suffix sorts lexicographically in write
+    /// order, so a prefix scan is already chronological.
+    pub fn read_logs(&self, job_id: &str) -> Result<Vec<LogRecord>> {
+        let prefix = encode_log_prefix(job_id);
+        let mut records = Vec::new();
+        for item in self.logs.prefix(&prefix) {
+            let (_key, value) = item?;
+            records.push(serde_json::from_slice(&value)?);
+        }
+        Ok(records)
+    }
+
+    /// Fold a single resource's download outcome into its job's snapshot
+    ///
+    /// Called by download workers once per `DownloadTask` as they finish.
+    /// Increments `resource_completed` or `resource_failed` (recording
+    /// `error` in the latter case) and transitions `status` to `Completed`
+    /// or `Failed` once every resource has been accounted for. Returns the
+    /// updated snapshot, plus whether this call is the one that drove
+    /// `status` into that terminal state - the caller uses the latter to
+    /// invoke a handler's `finalize_job` exactly once per job rather than
+    /// once per resource (see [`crate::worker::runner::process_task`]).
+    pub fn record_resource_outcome(
+        &self,
+        job_id: &str,
+        error: Option<JobError>,
+    ) -> Result<Option<(JobSnapshot, bool)>> {
+        let key = encode_job_key(job_id);
+        let Some(value) = self.jobs.get(&key)? else {
+            debug!(job_id, "record_resource_outcome: job not found, ignoring");
+            return Ok(None);
+        };
+
+        let mut snapshot: JobSnapshot = serde_json::from_slice(&value)?;
+        let was_terminal = is_terminal(&snapshot.status);
+
+        match error {
+            Some(err) => {
+                snapshot.resource_failed += 1;
+                snapshot.errors.push(err);
+            }
+            None => snapshot.resource_completed += 1,
+        }
+
+        if snapshot.resource_completed + snapshot.resource_failed >= snapshot.resource_total {
+            snapshot.status = if snapshot.resource_failed > 0 {
+                JobStatus::Failed
+            } else {
+                JobStatus::Completed
+            };
+        }
+        snapshot.updated_at = chrono::Utc::now();
+
+        let newly_finalized = !was_terminal && is_terminal(&snapshot.status);
+
+        let value = serde_json::to_vec(&snapshot)?;
+        self.jobs.insert(key, value)?;
+        debug!(job_id, status = ?snapshot.status, "Folded resource outcome into job snapshot");
+
+        // Ignore send errors: no SSE subscribers is the common case, not a failure.
+        let _ = self.events.send(snapshot.clone());
+        Ok(Some((snapshot, newly_finalized)))
+    }
+
+    /// List job snapshots for operator dashboards, newest-created-last
+    ///
+    /// Job ids are time-ordered UUIDv7s and keys are `job:{job_id}`, so a
+    /// plain partition scan already yields jobs in creation order; keyset
+    /// pagination is simply "skip everything up to and including `cursor`".
+    /// Filtering happens after decoding since Fjall has no secondary
+    /// indexes - fine at the scale a single operator dashboard scans.
+    pub fn list_jobs(&self, query: &JobListQuery) -> Result<(Vec<JobSnapshot>, Option<String>)> {
+        let limit = query.limit.unwrap_or(50).max(1);
+
+        let mut jobs = Vec::new();
+        let mut next_cursor = None;
+
+        for item in self.jobs.iter() {
+            let (key, value) = item?;
+            let Some(job_id) = decode_job_key(&key) else {
+                continue;
+            };
+
+            if let Some(cursor) = &query.cursor {
+                if job_id.as_str() <= cursor.as_str() {
+                    continue;
+                }
+            }
+
+            let snapshot: JobSnapshot = serde_json::from_slice(&value)?;
+
+            if let Some(tenant) = &query.tenant {
+                if &snapshot.tenant != tenant {
+                    continue;
+                }
+            }
+            if let Some(status) = &query.status {
+                if &snapshot.status != status {
+                    continue;
+                }
+            }
+            if let Some(after) = query.created_after {
+                if snapshot.created_at < after {
+                    continue;
+                }
+            }
+            if let Some(before) = query.created_before {
+                if snapshot.created_at > before {
+                    continue;
+                }
+            }
+
+            if jobs.len() == limit {
+                next_cursor = jobs.last().map(|last: &JobSnapshot| last.job_id.clone());
+                break;
+            }
+
+            jobs.push(snapshot);
+        }
+
+        Ok((jobs, next_cursor))
+    }
+
+    /// Prune expired entries based on `retention`'s TTLs and per-handler/
+    /// per-partition overrides
+    pub fn prune_expired(&self, retention: &crate::config::RetentionConfig) -> Result<PruneStats> {
         info!("Starting pruning process");
         let stats = prune_expired(
             &self.keyspace,
@@ -101,11 +406,45 @@ impl FjallStore {
             &self.logs,
             &self.idempotency,
             &self.metadata,
+            retention,
         )?;
         info!("Pruning completed: {:?}", stats);
         Ok(stats)
     }
 
+    /// Paced counterpart to [`FjallStore::prune_expired`] for
+    /// [`super::scheduler::run`]'s background timer: deletes are batched
+    /// and throttled by `retention.tranquility`, and progress is
+    /// checkpointed to the `metadata` partition after every batch so a
+    /// restart resumes an interrupted sweep instead of rescanning.
+    pub async fn prune_expired_throttled(
+        &self,
+        retention: &crate::config::RetentionConfig,
+    ) -> Result<PruneStats> {
+        info!("Starting throttled pruning process");
+        let stats = prune_expired_throttled(
+            &self.keyspace,
+            &self.jobs,
+            &self.logs,
+            &self.idempotency,
+            &self.metadata,
+            retention,
+        )
+        .await?;
+        info!("Throttled pruning completed: {:?}", stats);
+        Ok(stats)
+    }
+
+    /// Cheap liveness probe for `GET /health`: round-trips a fixed key
+    /// through the `metadata` partition rather than touching `jobs`, so a
+    /// large ledger's read path can't skew the probe's latency
+    pub fn health_check(&self) -> Result<()> {
+        let key = encode_meta_key("healthcheck");
+        self.metadata.insert(&key, b"ok".as_slice())?;
+        self.metadata.get(&key)?;
+        Ok(())
+    }
+
     /// Persist all pending writes to disk
     pub fn persist(&self) -> Result<()> {
         self.keyspace.persist(fjall::PersistMode::SyncAll)?;
@@ -148,6 +487,12 @@ pub struct StoreStats {
     pub idem_count: usize,
 }
 
+/// Whether `status` is a terminal job state - no further resource outcomes
+/// are expected once reached
+fn is_terminal(status: &JobStatus) -> bool {
+    matches!(status, JobStatus::Completed | JobStatus::Failed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,21 +506,20 @@ mod tests {
     }
 
     fn create_test_snapshot(job_id: &str) -> JobSnapshot {
-        let now = time::OffsetDateTime::now_utc()
-            .format(&time::format_description::well_known::Rfc3339)
-            .unwrap();
+        let now = chrono::Utc::now();
         JobSnapshot {
             job_id: job_id.to_string(),
             job_type: "test".to_string(),
             status: JobStatus::Queued,
-            created_at: now.clone(),
+            created_at: now,
             updated_at: now,
             resource_total: 10,
             resource_completed: 0,
             resource_failed: 0,
             manifest_key: "manifests/test.json".to_string(),
+            manifest_integrity: "sha256-test".to_string(),
             errors: Vec::new(),
-            tenant: Some("test-tenant".to_string()),
+            tenant: "test-tenant".to_string(),
         }
     }
 
@@ -213,16 +557,90 @@ mod tests {
         let (store, _temp) = create_test_store();
 
         store
-            .remember_idempotency("key_123".to_string(), "job_456".to_string())
+            .remember_idempotency("tenant-a", "key_123", "job_456".to_string())
             .unwrap();
 
-        let result = store.get_idempotent("key_123").unwrap();
+        let result = store.get_idempotent("tenant-a", "key_123", 14).unwrap();
         assert_eq!(result, Some("job_456".to_string()));
 
-        let missing = store.get_idempotent("missing_key").unwrap();
+        let missing = store.get_idempotent("tenant-a", "missing_key", 14).unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_idempotency_scoped_per_tenant() {
+        let (store, _temp) = create_test_store();
+
+        store
+            .remember_idempotency("tenant-a", "shared-key", "job_a".to_string())
+            .unwrap();
+        store
+            .remember_idempotency("tenant-b", "shared-key", "job_b".to_string())
+            .unwrap();
+
+        assert_eq!(
+            store.get_idempotent("tenant-a", "shared-key", 14).unwrap(),
+            Some("job_a".to_string())
+        );
+        assert_eq!(
+            store.get_idempotent("tenant-b", "shared-key", 14).unwrap(),
+            Some("job_b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_idempotency_expired_record_treated_as_absent() {
+        let (store, _temp) = create_test_store();
+
+        let idem_key = super::super::partitions::encode_idem_key("tenant-a", "key_123");
+        let stale_record = IdempotencyRecord {
+            job_id: "job_456".to_string(),
+            created_at: chrono::Utc::now() - chrono::Duration::days(20),
+        };
+        store
+            .idempotency
+            .insert(idem_key, serde_json::to_vec(&stale_record).unwrap())
+            .unwrap();
+
+        let result = store.get_idempotent("tenant-a", "key_123", 14).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_cas_entry() {
+        let (store, _temp) = create_test_store();
+
+        store
+            .remember_cas_entry("sha256-abcd1234", "cas/ab/cd/abcd1234")
+            .unwrap();
+
+        let result = store.get_cas_entry("sha256-abcd1234").unwrap();
+        assert_eq!(result, Some("cas/ab/cd/abcd1234".to_string()));
+
+        let missing = store.get_cas_entry("sha256-missing").unwrap();
         assert_eq!(missing, None);
     }
 
+    #[test]
+    fn test_manifest_resources_key() {
+        let (store, _temp) = create_test_store();
+
+        assert_eq!(store.get_manifest_resources_key("job_1").unwrap(), None);
+
+        store
+            .remember_manifest_resources("job_1", "manifests/job_1/resources.json")
+            .unwrap();
+
+        let result = store.get_manifest_resources_key("job_1").unwrap();
+        assert_eq!(result, Some("manifests/job_1/resources.json".to_string()));
+    }
+
+    #[test]
+    fn test_health_check() {
+        let (store, _temp) = create_test_store();
+        store.health_check().unwrap();
+    }
+
     #[test]
     fn test_stats() {
         let (store, _temp) = create_test_store();
@@ -230,7 +648,7 @@ mod tests {
         let snapshot = create_test_snapshot("job_1");
         store.upsert(snapshot).unwrap();
         store
-            .remember_idempotency("key_1".to_string(), "job_1".to_string())
+            .remember_idempotency("tenant-a", "key_1", "job_1".to_string())
             .unwrap();
 
         let stats = store.stats().unwrap();
@@ -247,4 +665,150 @@ mod tests {
         // Persist should not error
         store.persist().unwrap();
     }
+
+    #[test]
+    fn test_prune_expired_removes_stale_idempotency_records() {
+        let (store, _temp) = create_test_store();
+
+        let idem_key = super::super::partitions::encode_idem_key("tenant-a", "key_old");
+        let stale_record = IdempotencyRecord {
+            job_id: "job_old".to_string(),
+            created_at: chrono::Utc::now() - chrono::Duration::days(20),
+        };
+        store
+            .idempotency
+            .insert(idem_key, serde_json::to_vec(&stale_record).unwrap())
+            .unwrap();
+
+        store
+            .remember_idempotency("tenant-a", "key_fresh", "job_fresh".to_string())
+            .unwrap();
+
+        let stats = store.prune_expired(&crate::config::RetentionConfig::default()).unwrap();
+
+        assert_eq!(stats.idempotency_pruned, 1);
+        assert_eq!(store.get_idempotent("tenant-a", "key_old", 14).unwrap(), None);
+        assert_eq!(
+            store.get_idempotent("tenant-a", "key_fresh", 14).unwrap(),
+            Some("job_fresh".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prune_expired_removes_old_jobs_and_respects_override() {
+        let (store, _temp) = create_test_store();
+
+        let mut old_default = create_test_snapshot("job_default_old");
+        old_default.created_at = chrono::Utc::now() - chrono::Duration::days(40);
+        store.upsert(old_default).unwrap();
+
+        let mut old_gallery = create_test_snapshot("job_gallery_old");
+        old_gallery.job_type = "gallery".to_string();
+        old_gallery.created_at = chrono::Utc::now() - chrono::Duration::days(40);
+        store.upsert(old_gallery).unwrap();
+
+        store.upsert(create_test_snapshot("job_fresh")).unwrap();
+
+        let mut retention = crate::config::RetentionConfig::default();
+        retention.overrides.insert("gallery".to_string(), 90);
+
+        let stats = store.prune_expired(&retention).unwrap();
+
+        assert_eq!(stats.jobs_pruned, 1);
+        assert!(store.get("job_default_old").unwrap().is_none());
+        assert!(store.get("job_gallery_old").unwrap().is_some());
+        assert!(store.get("job_fresh").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_expired_evicts_oldest_jobs_when_over_size_limit() {
+        let (store, _temp) = create_test_store();
+
+        for i in 0..5i64 {
+            let mut snapshot = create_test_snapshot(&format!("job_{i}"));
+            snapshot.created_at = chrono::Utc::now() - chrono::Duration::seconds(5 - i);
+            store.upsert(snapshot).unwrap();
+        }
+
+        let one_entry_bytes = store
+            .jobs
+            .iter()
+            .next()
+            .unwrap()
+            .map(|(k, v)| (k.len() + v.len()) as u64)
+            .unwrap();
+
+        let retention = crate::config::RetentionConfig {
+            // Sized to fit only 2 entries at the high watermark
+            ledger_max_bytes: crate::config::ByteSize(one_entry_bytes * 2),
+            low_watermark_fraction: 0.5,
+            ..Default::default()
+        };
+
+        let stats = store.prune_expired(&retention).unwrap();
+
+        assert!(stats.jobs_evicted > 0);
+        assert!(stats.bytes_evicted > 0);
+        // Oldest snapshots (job_0, job_1, ...) are evicted first
+        assert!(store.get("job_0").unwrap().is_none());
+        assert!(store.get("job_4").unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_expired_throttled_matches_blocking_prune() {
+        let (store, _temp) = create_test_store();
+
+        let mut old_default = create_test_snapshot("job_default_old");
+        old_default.created_at = chrono::Utc::now() - chrono::Duration::days(40);
+        store.upsert(old_default).unwrap();
+
+        store.upsert(create_test_snapshot("job_fresh")).unwrap();
+
+        // Flat-out (no throttling sleeps) so the test stays fast
+        let retention = crate::config::RetentionConfig {
+            tranquility: 0.0,
+            ..Default::default()
+        };
+
+        let stats = store.prune_expired_throttled(&retention).await.unwrap();
+
+        assert_eq!(stats.jobs_pruned, 1);
+        assert!(store.get("job_default_old").unwrap().is_none());
+        assert!(store.get("job_fresh").unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_expired_throttled_resumes_from_pending_keys() {
+        let (store, _temp) = create_test_store();
+
+        let mut old_job = create_test_snapshot("job_resume_old");
+        old_job.created_at = chrono::Utc::now() - chrono::Duration::days(40);
+        store.upsert(old_job).unwrap();
+
+        // Simulate a prior sweep that scanned and persisted a pending
+        // delete list but was interrupted before deleting anything
+        store
+            .metadata
+            .insert(
+                crate::ledger::partitions::encode_meta_key("prune_pending_jobs"),
+                serde_json::to_vec(&vec!["job:job_resume_old"]).unwrap(),
+            )
+            .unwrap();
+
+        let retention = crate::config::RetentionConfig {
+            tranquility: 0.0,
+            ..Default::default()
+        };
+
+        let stats = store.prune_expired_throttled(&retention).await.unwrap();
+
+        assert_eq!(stats.jobs_pruned, 1);
+        assert!(store.get("job_resume_old").unwrap().is_none());
+        // The pending-keys checkpoint is cleared once the sweep finishes
+        assert!(store
+            .metadata
+            .get(crate::ledger::partitions::encode_meta_key("prune_pending_jobs"))
+            .unwrap()
+            .is_none());
+    }
 }
@@ -8,6 +8,4 @@ pub mod observability;
 pub mod proto;
 pub mod queue;
 pub mod storage;
-
-// Disable worker module for now during API isolation
-// pub mod worker;
+pub mod worker;
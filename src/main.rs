@@ -1,20 +1,73 @@
 mod cli;
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, ConfigCommand, DlqCommand};
 use fetchbox::api;
+use fetchbox::config::Config;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    tracing_subscriber::fmt::init();
-
     let cli = Cli::parse();
 
+    // `Api`/`Worker` open a Fjall store before they can install logging, so
+    // each installs its own subscriber (console output plus the per-job
+    // structured log layer - see `fetchbox::worker::job_log::init_tracing`)
+    // as soon as that store is open. The remaining subcommands never touch
+    // per-job logging, so they just get plain console output up front.
     match cli.command {
         Commands::Api(args) => api::run(args.address, args.ledger_path).await?,
-        Commands::Worker => {
-            eprintln!("Worker mode is temporarily disabled during architecture transition");
-            std::process::exit(1);
+        Commands::Worker(args) => {
+            fetchbox::worker::pool::run(args.ledger_path, args.concurrency, args.batch_size)
+                .await?
+        }
+        Commands::Dlq(args) => {
+            tracing_subscriber::fmt::init();
+            match args.command {
+                DlqCommand::Replay { seq } => {
+                    let new_seq = fetchbox::queue::replay_dlq(&args.ledger_path, seq)?;
+                    println!("Replayed DLQ entry {seq} as seq {new_seq}");
+                }
+                DlqCommand::ReplayAll { failure_code } => {
+                    let new_seqs =
+                        fetchbox::queue::replay_dlq_all(&args.ledger_path, &failure_code)?;
+                    println!(
+                        "Replayed {} DLQ entr{} matching {failure_code}: {:?}",
+                        new_seqs.len(),
+                        if new_seqs.len() == 1 { "y" } else { "ies" },
+                        new_seqs
+                    );
+                }
+            }
+        }
+        Commands::Config(args) => {
+            tracing_subscriber::fmt::init();
+            match args.command {
+                ConfigCommand::Check { all } => {
+                    let config = Config::load_unvalidated()
+                        .map_err(|e| format!("Failed to load config: {e}"))?;
+
+                    if all {
+                        match config.validate_all() {
+                            Ok(()) => println!("Configuration is valid"),
+                            Err(errors) => {
+                                println!("Configuration has {} violation(s):", errors.len());
+                                for error in &errors {
+                                    println!("  - {error}");
+                                }
+                                std::process::exit(1);
+                            }
+                        }
+                    } else {
+                        match config.validate() {
+                            Ok(()) => println!("Configuration is valid"),
+                            Err(error) => {
+                                println!("Configuration is invalid: {error}");
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -5,6 +5,7 @@
 
 use async_trait::async_trait;
 use thiserror::Error;
+use tokio::sync::broadcast;
 
 #[derive(Debug, Error)]
 pub enum MessagingError {
@@ -27,6 +28,20 @@ pub trait MessageProducer: Send + Sync {
     async fn health(&self) -> bool;
 }
 
+/// Message consumer for subscribing to a stream - symmetric to
+/// [`MessageProducer`]. `Message` is the consumer's own payload type rather
+/// than `MessageProducer::publish`'s raw `Vec<u8>`, since a subscriber (e.g.
+/// [`crate::worker::status_stream::StatusBroadcaster`]) typically wants its
+/// already-decoded event type back out, not bytes it has to re-parse itself.
+#[async_trait]
+pub trait MessageConsumer: Send + Sync {
+    type Message: Clone + Send + 'static;
+
+    /// Subscribe to `stream`, receiving a clone of every message
+    /// subsequently published to it
+    async fn subscribe(&self, stream: &str) -> Result<broadcast::Receiver<Self::Message>>;
+}
+
 /// Mock producer for development
 #[derive(Debug, Clone, Default)]
 pub struct MockProducer;
@@ -1,18 +1,78 @@
-//! Observability stubs (metrics, tracing)
+//! Observability: counters, gauges, and histograms rendered in Prometheus
+//! text exposition format at `GET /metrics` (see [`crate::api::server::run`]).
+//!
+//! There is no `prometheus` crate dependency here on purpose - the repo's
+//! metrics surface is small enough that hand-rolled bucketed counters behind
+//! a `Mutex<HashMap<..>>` are simpler than wiring up a registry, and it keeps
+//! `Metrics` dependency-free like the rest of this module.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
 
-/// Metrics handle for recording counters/gauges
+/// Bucket upper bounds (seconds) for `fetchbox_download_duration_seconds`
+const LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Bucket upper bounds (bytes) for `fetchbox_download_bytes`
+const BYTE_BUCKETS: &[f64] = &[
+    1024.0,
+    10.0 * 1024.0,
+    100.0 * 1024.0,
+    1024.0 * 1024.0,
+    10.0 * 1024.0 * 1024.0,
+    100.0 * 1024.0 * 1024.0,
+];
+
+/// Label key shared by the download histograms and the status counter:
+/// `(job_type, tenant)`, taken straight from [`crate::proto::DownloadTask`].
+type Labels = (String, String);
+
+/// Metrics handle for recording counters/gauges/histograms
 #[derive(Debug, Default)]
 pub struct Metrics {
     jobs_accepted: AtomicU64,
     jobs_failed: AtomicU64,
     tasks_published: AtomicU64,
+    downloads_in_flight: AtomicI64,
+    download_duration_seconds: Histogram,
+    download_bytes: Histogram,
+    download_status_total: Mutex<HashMap<(String, String, u16), u64>>,
+    /// Downloads served per `(job_type, proxy_pool)`, where `proxy_pool` is
+    /// `"direct"` when the task carried no [`crate::handlers::types::ProxyHint`]
+    download_proxy_pool_total: Mutex<HashMap<(String, String), u64>>,
+    download_attempts: AtomicU64,
+    download_successes: AtomicU64,
+    /// Failed download attempts by [`crate::worker::http::DownloadError::metric_label`]
+    /// (or [`crate::worker::proxy::ProxyRotationError::metric_label`] for a
+    /// proxied attempt)
+    download_failures_total: Mutex<HashMap<String, u64>>,
+    retries_total: AtomicU64,
+    /// `current_seq - consumer_cursor` of the lease-based
+    /// [`crate::queue::FjallQueue`] protocol, refreshed on each `/metrics`
+    /// scrape (see [`crate::api::services::metrics`])
+    queue_depth: AtomicI64,
+    /// Entry count of the DLQ partition, refreshed alongside `queue_depth`
+    dlq_size: AtomicI64,
+    /// Cumulative uncompressed bytes written to storage, refreshed from
+    /// [`crate::storage::StorageClient::compression_stats`] on each scrape
+    storage_original_bytes: AtomicI64,
+    /// Cumulative bytes actually written to storage (post-compression),
+    /// refreshed alongside `storage_original_bytes`
+    storage_stored_bytes: AtomicI64,
+    /// In-flight task count per worker, refreshed from
+    /// [`crate::queue::TaskBroker::worker_loads`] on each `/metrics` scrape
+    worker_loads: Mutex<Vec<usize>>,
 }
 
 impl Metrics {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            download_duration_seconds: Histogram::new(LATENCY_BUCKETS),
+            download_bytes: Histogram::new(BYTE_BUCKETS),
+            ..Self::default()
+        }
     }
 
     pub fn job_accepted(&self) {
@@ -30,6 +90,97 @@ impl Metrics {
         tracing::debug!(counter = "tasks_published", "Metric incremented");
     }
 
+    /// Mark a download as started; pair with [`Metrics::download_finished`]
+    /// around the fetch+upload span so the gauge never drifts under panics
+    /// (callers should record it in a `defer`-style guard or right after the
+    /// span completes either way).
+    pub fn download_started(&self) {
+        self.downloads_in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn download_finished(&self) {
+        self.downloads_in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Record one completed download: wall-clock latency, bytes transferred,
+    /// and the originating HTTP status code, labeled by `job_type`/`tenant`.
+    pub fn observe_download(&self, job_type: &str, tenant: &str, elapsed: Duration, bytes: u64, status: u16) {
+        self.histogram_observe(
+            &self.download_duration_seconds,
+            job_type,
+            tenant,
+            elapsed.as_secs_f64(),
+        );
+        self.histogram_observe(&self.download_bytes, job_type, tenant, bytes as f64);
+
+        let mut counts = self.download_status_total.lock().unwrap();
+        *counts
+            .entry((job_type.to_string(), tenant.to_string(), status))
+            .or_insert(0) += 1;
+    }
+
+    fn histogram_observe(&self, histogram: &Histogram, job_type: &str, tenant: &str, value: f64) {
+        histogram.observe((job_type.to_string(), tenant.to_string()), value);
+    }
+
+    /// Record which proxy pool ultimately served a download (see
+    /// [`crate::worker::proxy::ProxyRotator`]), so operators can see
+    /// fallback rates per `job_type`.
+    pub fn record_proxy_pool(&self, job_type: &str, pool: &str) {
+        let mut counts = self.download_proxy_pool_total.lock().unwrap();
+        *counts
+            .entry((job_type.to_string(), pool.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// Mark one download attempt starting, pair with
+    /// [`Metrics::record_download_success`] or
+    /// [`Metrics::record_download_failure`] once it resolves
+    pub fn record_download_attempt(&self) {
+        self.download_attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_download_success(&self) {
+        self.download_successes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a failed download attempt, labeled by a stable error variant
+    /// name (see [`crate::worker::http::DownloadError::metric_label`]).
+    pub fn record_download_failure(&self, error_label: &str) {
+        let mut counts = self.download_failures_total.lock().unwrap();
+        *counts.entry(error_label.to_string()).or_insert(0) += 1;
+    }
+
+    /// Record a task being parked for retry (see
+    /// [`crate::worker::retry::RetryScheduler`]) rather than completing or
+    /// dead-lettering outright.
+    pub fn record_retry(&self) {
+        self.retries_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Refresh the queue-depth gauge; see [`crate::queue::FjallQueue::queue_depth`].
+    pub fn set_queue_depth(&self, depth: u64) {
+        self.queue_depth.store(depth as i64, Ordering::Relaxed);
+    }
+
+    /// Refresh the DLQ-size gauge; see [`crate::queue::FjallQueue::dlq_size`].
+    pub fn set_dlq_size(&self, size: u64) {
+        self.dlq_size.store(size as i64, Ordering::Relaxed);
+    }
+
+    /// Refresh the storage compression gauges; see
+    /// [`crate::storage::StorageClient::compression_stats`].
+    pub fn set_storage_compression_bytes(&self, original_bytes: u64, stored_bytes: u64) {
+        self.storage_original_bytes.store(original_bytes as i64, Ordering::Relaxed);
+        self.storage_stored_bytes.store(stored_bytes as i64, Ordering::Relaxed);
+    }
+
+    /// Refresh the per-worker in-flight gauges; see
+    /// [`crate::queue::TaskBroker::worker_loads`].
+    pub fn set_worker_loads(&self, loads: Vec<usize>) {
+        *self.worker_loads.lock().unwrap() = loads;
+    }
+
     pub fn snapshot(&self) -> MetricsSnapshot {
         MetricsSnapshot {
             jobs_accepted: self.jobs_accepted.load(Ordering::Relaxed),
@@ -37,6 +188,94 @@ impl Metrics {
             tasks_published: self.tasks_published.load(Ordering::Relaxed),
         }
     }
+
+    /// Render all metrics in Prometheus text exposition format for `GET /metrics`.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        render_counter(&mut out, "fetchbox_jobs_accepted_total", "Total jobs accepted", self.jobs_accepted.load(Ordering::Relaxed));
+        render_counter(&mut out, "fetchbox_jobs_failed_total", "Total jobs failed", self.jobs_failed.load(Ordering::Relaxed));
+        render_counter(&mut out, "fetchbox_tasks_published_total", "Total tasks published to workers", self.tasks_published.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_downloads_in_flight Downloads currently being fetched or uploaded");
+        let _ = writeln!(out, "# TYPE fetchbox_downloads_in_flight gauge");
+        let _ = writeln!(out, "fetchbox_downloads_in_flight {}", self.downloads_in_flight.load(Ordering::Relaxed));
+
+        self.download_duration_seconds.render(
+            &mut out,
+            "fetchbox_download_duration_seconds",
+            "Per-download wall-clock latency in seconds",
+        );
+        self.download_bytes.render(
+            &mut out,
+            "fetchbox_download_bytes",
+            "Bytes transferred per download",
+        );
+
+        let _ = writeln!(out, "# HELP fetchbox_download_status_total Download attempts by originating HTTP status code");
+        let _ = writeln!(out, "# TYPE fetchbox_download_status_total counter");
+        let counts = self.download_status_total.lock().unwrap();
+        for ((job_type, tenant, status), count) in counts.iter() {
+            let _ = writeln!(
+                out,
+                "fetchbox_download_status_total{{job_type=\"{job_type}\",tenant=\"{tenant}\",status=\"{status}\"}} {count}"
+            );
+        }
+
+        let _ = writeln!(out, "# HELP fetchbox_download_proxy_pool_total Downloads served per proxy pool (\"direct\" when unproxied)");
+        let _ = writeln!(out, "# TYPE fetchbox_download_proxy_pool_total counter");
+        let pool_counts = self.download_proxy_pool_total.lock().unwrap();
+        for ((job_type, pool), count) in pool_counts.iter() {
+            let _ = writeln!(
+                out,
+                "fetchbox_download_proxy_pool_total{{job_type=\"{job_type}\",pool=\"{pool}\"}} {count}"
+            );
+        }
+
+        render_counter(&mut out, "fetchbox_download_attempts_total", "Total download attempts, before success/failure is known", self.download_attempts.load(Ordering::Relaxed));
+        render_counter(&mut out, "fetchbox_download_successes_total", "Total download attempts that completed successfully", self.download_successes.load(Ordering::Relaxed));
+        render_counter(&mut out, "fetchbox_retries_total", "Total tasks parked for a retry attempt", self.retries_total.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_download_failures_total Failed download attempts by error variant");
+        let _ = writeln!(out, "# TYPE fetchbox_download_failures_total counter");
+        let failure_counts = self.download_failures_total.lock().unwrap();
+        for (error_label, count) in failure_counts.iter() {
+            let _ = writeln!(
+                out,
+                "fetchbox_download_failures_total{{error=\"{error_label}\"}} {count}"
+            );
+        }
+
+        let _ = writeln!(out, "# HELP fetchbox_queue_depth Tasks enqueued but not yet claimed, per the lease-based claim/ack protocol");
+        let _ = writeln!(out, "# TYPE fetchbox_queue_depth gauge");
+        let _ = writeln!(out, "fetchbox_queue_depth {}", self.queue_depth.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_dlq_size Entries currently held in the dead-letter queue");
+        let _ = writeln!(out, "# TYPE fetchbox_dlq_size gauge");
+        let _ = writeln!(out, "fetchbox_dlq_size {}", self.dlq_size.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_storage_original_bytes Cumulative uncompressed bytes written to storage");
+        let _ = writeln!(out, "# TYPE fetchbox_storage_original_bytes gauge");
+        let _ = writeln!(out, "fetchbox_storage_original_bytes {}", self.storage_original_bytes.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_storage_stored_bytes Cumulative bytes actually written to storage after compression");
+        let _ = writeln!(out, "# TYPE fetchbox_storage_stored_bytes gauge");
+        let _ = writeln!(out, "fetchbox_storage_stored_bytes {}", self.storage_stored_bytes.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP fetchbox_worker_inflight Tasks currently dispatched to a worker and not yet acked");
+        let _ = writeln!(out, "# TYPE fetchbox_worker_inflight gauge");
+        for (worker_idx, load) in self.worker_loads.lock().unwrap().iter().enumerate() {
+            let _ = writeln!(out, "fetchbox_worker_inflight{{worker=\"{worker_idx}\"}} {load}");
+        }
+
+        out
+    }
+}
+
+fn render_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} counter");
+    let _ = writeln!(out, "{name} {value}");
 }
 
 #[derive(Debug, Clone)]
@@ -45,3 +284,141 @@ pub struct MetricsSnapshot {
     pub jobs_failed: u64,
     pub tasks_published: u64,
 }
+
+#[derive(Debug, Default)]
+struct HistogramState {
+    /// Per-bucket (non-cumulative) observation counts, same length/order as
+    /// the histogram's bucket bounds.
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// A Prometheus-style cumulative histogram, labeled by `(job_type, tenant)`.
+#[derive(Debug)]
+struct Histogram {
+    buckets: &'static [f64],
+    states: Mutex<HashMap<Labels, HistogramState>>,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn observe(&self, labels: Labels, value: f64) {
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(labels).or_insert_with(|| HistogramState {
+            bucket_counts: vec![0; self.buckets.len()],
+            sum: 0.0,
+            count: 0,
+        });
+
+        for (bucket_count, bound) in state.bucket_counts.iter_mut().zip(self.buckets) {
+            if value <= *bound {
+                *bucket_count += 1;
+                break;
+            }
+        }
+        state.sum += value;
+        state.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+
+        let states = self.states.lock().unwrap();
+        for ((job_type, tenant), state) in states.iter() {
+            let mut cumulative = 0;
+            for (bound, bucket_count) in self.buckets.iter().zip(&state.bucket_counts) {
+                cumulative += bucket_count;
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{job_type=\"{job_type}\",tenant=\"{tenant}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{job_type=\"{job_type}\",tenant=\"{tenant}\",le=\"+Inf\"}} {}",
+                state.count
+            );
+            let _ = writeln!(
+                out,
+                "{name}_sum{{job_type=\"{job_type}\",tenant=\"{tenant}\"}} {}",
+                state.sum
+            );
+            let _ = writeln!(
+                out,
+                "{name}_count{{job_type=\"{job_type}\",tenant=\"{tenant}\"}} {}",
+                state.count
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_observe_and_render() {
+        let histogram = Histogram::new(LATENCY_BUCKETS);
+        histogram.observe(("default".to_string(), "acme".to_string()), 0.2);
+        histogram.observe(("default".to_string(), "acme".to_string()), 3.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "fetchbox_download_duration_seconds", "test");
+
+        assert!(out.contains("fetchbox_download_duration_seconds_count{job_type=\"default\",tenant=\"acme\"} 2"));
+        assert!(out.contains("le=\"+Inf\"} 2"));
+    }
+
+    #[test]
+    fn test_render_prometheus_includes_counters() {
+        let metrics = Metrics::new();
+        metrics.job_accepted();
+        metrics.observe_download("default", "acme", Duration::from_millis(150), 2048, 200);
+        metrics.record_proxy_pool("default", "primary");
+
+        let rendered = metrics.render_prometheus();
+        assert!(rendered.contains("fetchbox_jobs_accepted_total 1"));
+        assert!(rendered.contains("fetchbox_download_status_total{job_type=\"default\",tenant=\"acme\",status=\"200\"} 1"));
+        assert!(rendered.contains("fetchbox_download_proxy_pool_total{job_type=\"default\",pool=\"primary\"} 1"));
+    }
+
+    #[test]
+    fn test_render_prometheus_includes_queue_and_download_outcomes() {
+        let metrics = Metrics::new();
+        metrics.record_download_attempt();
+        metrics.record_download_attempt();
+        metrics.record_download_success();
+        metrics.record_download_failure("timeout");
+        metrics.record_retry();
+        metrics.set_queue_depth(7);
+        metrics.set_dlq_size(2);
+        metrics.set_storage_compression_bytes(2048, 512);
+        metrics.set_worker_loads(vec![3, 0]);
+
+        let rendered = metrics.render_prometheus();
+        assert!(rendered.contains("fetchbox_download_attempts_total 2"));
+        assert!(rendered.contains("fetchbox_download_successes_total 1"));
+        assert!(rendered.contains("fetchbox_download_failures_total{error=\"timeout\"} 1"));
+        assert!(rendered.contains("fetchbox_retries_total 1"));
+        assert!(rendered.contains("fetchbox_queue_depth 7"));
+        assert!(rendered.contains("fetchbox_dlq_size 2"));
+        assert!(rendered.contains("fetchbox_storage_original_bytes 2048"));
+        assert!(rendered.contains("fetchbox_storage_stored_bytes 512"));
+        assert!(rendered.contains("fetchbox_worker_inflight{worker=\"0\"} 3"));
+        assert!(rendered.contains("fetchbox_worker_inflight{worker=\"1\"} 0"));
+    }
+}
@@ -1,7 +1,8 @@
-use crate::proto::DownloadTask;
+use crate::proto::{DeadLetterTask, DownloadTask};
 use crate::queue::store::{FjallQueue, QueueError};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info, warn};
 
@@ -12,34 +13,79 @@ pub struct TaskEnvelope {
     pub task: DownloadTask,
 }
 
-/// TaskBroker distributes tasks from the API to worker pool
+/// TaskBroker distributes tasks from the API to worker pool, with
+/// at-least-once delivery
 ///
 /// Architecture:
 /// 1. API calls `broker.enqueue(task)`
 /// 2. Broker persists task to FjallQueue (atomic, get seq)
-/// 3. Broker sends TaskEnvelope{seq, task} to worker via mpsc channel
-/// 4. Round-robin distribution across worker pool
+/// 3. Broker records a dispatch lease (`seq -> {worker_idx, dispatched_at}`)
+///    and sends TaskEnvelope{seq, task} to a worker via mpsc channel
+/// 4. Load-aware distribution across the worker pool: power-of-two-choices
+///    between two randomly sampled workers, picking whichever has fewer
+///    tasks in flight (see [`pick_worker`](Self::pick_worker))
 /// 5. Backpressure via bounded channels (default: 100 per worker)
+/// 6. The worker acks `seq` back over `ack_tx` once the task reaches a
+///    terminal state (see [`crate::worker::spawn_pool`]); the broker's ack
+///    listener then clears the task and its lease from Fjall and releases
+///    that worker's in-flight count
 ///
-/// The broker is NOT a separate task - it's just a struct with methods
-/// called by API handlers. Distribution is synchronous via mpsc::send().
+/// A task is never removed from the queue until it is acked or
+/// dead-lettered: [`spawn_reaper`](Self::spawn_reaper) periodically
+/// redelivers any lease older than `visibility_timeout` (bumping
+/// `DownloadTask.attempt`), dead-lettering it instead once `max_attempts` is
+/// exhausted, and [`recover`](Self::recover) replays whatever was still
+/// outstanding across a broker restart - the in-memory leases and channels
+/// don't survive a crash, but the Fjall record of "dispatched, never acked"
+/// does.
+///
+/// `visibility_timeout` is a crash-detection window, not a bound on how long
+/// a single download may legitimately take: [`crate::worker::spawn_pool`]
+/// renews a task's lease (via [`FjallQueue::renew_dispatch`]) on its own
+/// timer for as long as the task is still being processed, so a
+/// multi-gigabyte download that takes far longer than `visibility_timeout`
+/// never gets reaped out from under the worker still streaming it.
+///
+/// The broker is NOT a separate task for the enqueue/ack path - `enqueue`
+/// and the ack listener just operate on the shared `queue` handle. The
+/// reaper is the one genuinely background piece (see
+/// [`spawn_reaper`](Self::spawn_reaper)).
 pub struct TaskBroker {
     queue: Arc<RwLock<FjallQueue>>,
     worker_channels: Vec<mpsc::Sender<TaskEnvelope>>,
+    /// Tasks currently dispatched to each worker and not yet acked, indexed
+    /// the same as `worker_channels`; read by [`pick_worker`](Self::pick_worker)
+    /// and exposed via [`worker_loads`](Self::worker_loads). Shared with the
+    /// ack-listener task spawned in [`new`](Self::new), which decrements it.
+    worker_loads: Arc<Vec<AtomicUsize>>,
+    /// Tie-breaker when [`pick_worker`](Self::pick_worker)'s two sampled
+    /// workers have equal load
     next_worker: AtomicUsize,
+    rng_state: AtomicU64,
+    visibility_timeout: Duration,
+    max_attempts: u32,
 }
 
 impl TaskBroker {
-    /// Create a new TaskBroker with worker channels
+    /// Create a new TaskBroker with worker channels and an ack channel
     ///
     /// Returns:
     /// - TaskBroker instance (to be passed to API via Arc)
     /// - Vec of receivers (one per worker, for spawning workers)
+    /// - The `Sender` half of the ack channel - give a clone to each worker
+    ///   (see [`crate::worker::spawn_pool`]) to report task completion
+    ///
+    /// A background task is spawned here to drain acks off the `Receiver`
+    /// half and clear them out of Fjall; it runs for the lifetime of the
+    /// returned `Sender`s, so it winds down once every worker drops its
+    /// clone.
     pub fn new(
         queue: Arc<RwLock<FjallQueue>>,
         num_workers: usize,
         channel_size: usize,
-    ) -> (Self, Vec<mpsc::Receiver<TaskEnvelope>>) {
+        visibility_timeout: Duration,
+        max_attempts: u32,
+    ) -> (Self, Vec<mpsc::Receiver<TaskEnvelope>>, mpsc::Sender<u64>) {
         info!(
             num_workers,
             channel_size, "Creating TaskBroker with worker channels"
@@ -55,13 +101,39 @@ impl TaskBroker {
             debug!(worker_id, "Created worker channel");
         }
 
+        let worker_loads = Arc::new(
+            (0..num_workers.max(1)).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>(),
+        );
+
         let broker = Self {
             queue,
             worker_channels,
+            worker_loads: worker_loads.clone(),
             next_worker: AtomicUsize::new(0),
+            rng_state: AtomicU64::new(0),
+            visibility_timeout,
+            max_attempts,
         };
 
-        (broker, worker_receivers)
+        let ack_channel_size = channel_size.max(1) * num_workers.max(1);
+        let (ack_tx, mut ack_rx) = mpsc::channel::<u64>(ack_channel_size);
+        let ack_queue = broker.queue.clone();
+        tokio::spawn(async move {
+            while let Some(seq) = ack_rx.recv().await {
+                match ack_queue.write().await.ack_dispatch(seq) {
+                    Ok(Some(worker_idx)) => {
+                        if let Some(load) = worker_loads.get(worker_idx) {
+                            load.fetch_sub(1, Ordering::Relaxed);
+                        }
+                        debug!(seq, worker_idx, "Task acked");
+                    }
+                    Ok(None) => debug!(seq, "Task acked (no dispatch lease found)"),
+                    Err(e) => warn!(seq, error = %e, "Failed to clear acked task from queue"),
+                }
+            }
+        });
+
+        (broker, worker_receivers, ack_tx)
     }
 
     /// Enqueue a task: persist to Fjall + distribute to worker
@@ -89,30 +161,246 @@ impl TaskBroker {
             "Task persisted to queue"
         );
 
-        // Create envelope
-        let envelope = TaskEnvelope {
-            seq,
-            task: task.clone(),
-        };
+        self.dispatch(seq, task).await;
+        Ok(seq)
+    }
 
-        // Round-robin to next worker
-        let worker_idx = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.worker_channels.len();
+    /// Cheap splitmix-style PRNG draw - see
+    /// [`crate::worker::proxy::ProxySelector::next_rand`]'s comment: this
+    /// tree has no `rand` dependency, and power-of-two-choices only needs to
+    /// decorrelate which two workers get compared, not be cryptographically
+    /// random.
+    fn next_rand(&self) -> u64 {
+        let mut x = self
+            .rng_state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            ^ (Instant::now().elapsed().as_nanos() as u64);
+        x ^= x >> 30;
+        x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x ^= x >> 27;
+        x
+    }
+
+    /// Power-of-two-choices worker selection: sample two distinct worker
+    /// indices at random and return whichever has fewer tasks in flight,
+    /// breaking a tie with `next_worker`. Keeps dispatch O(1) regardless of
+    /// pool size while staying far more load-balanced than plain
+    /// round-robin once task durations are skewed.
+    fn pick_worker(&self) -> usize {
+        let n = self.worker_channels.len();
+        if n == 1 {
+            return 0;
+        }
+        let i = (self.next_rand() as usize) % n;
+        let mut j = (self.next_rand() as usize) % n;
+        while j == i {
+            j = (self.next_rand() as usize) % n;
+        }
+        let load_i = self.worker_loads[i].load(Ordering::Relaxed);
+        let load_j = self.worker_loads[j].load(Ordering::Relaxed);
+        match load_i.cmp(&load_j) {
+            std::cmp::Ordering::Less => i,
+            std::cmp::Ordering::Greater => j,
+            std::cmp::Ordering::Equal => {
+                if self.next_worker.fetch_add(1, Ordering::Relaxed) % 2 == 0 {
+                    i
+                } else {
+                    j
+                }
+            }
+        }
+    }
+
+    /// Pick a worker via [`pick_worker`](Self::pick_worker), record a
+    /// dispatch lease, and send the envelope - shared by
+    /// [`enqueue`](Self::enqueue), [`recover`](Self::recover), and the
+    /// reaper's redelivery path
+    async fn dispatch(&self, seq: u64, task: DownloadTask) {
+        let worker_idx = self.pick_worker();
+        self.worker_loads[worker_idx].fetch_add(1, Ordering::Relaxed);
+
+        if let Err(e) = self.queue.write().await.record_dispatch(seq, worker_idx) {
+            warn!(seq, worker_idx, error = %e, "Failed to record dispatch lease");
+        }
+
+        let envelope = TaskEnvelope { seq, task };
 
         // Send to worker (bounded channel, may block if full = backpressure)
         match self.worker_channels[worker_idx].send(envelope).await {
-            Ok(_) => {
-                debug!(seq, worker_idx, "Task sent to worker");
-                Ok(seq)
-            }
+            Ok(_) => debug!(seq, worker_idx, "Task sent to worker"),
             Err(_) => {
+                // Worker channel is closed, but the task and its dispatch
+                // lease are already persisted in Fjall - the reaper will
+                // notice it never gets acked and redeliver it once another
+                // worker (or this one, after a restart) is listening again.
                 warn!(seq, worker_idx, "Worker channel closed, task not delivered");
-                // Worker is dead, but task is already persisted in queue
-                // Could implement retry to another worker here
-                Ok(seq) // Return seq anyway, task is safe in Fjall
             }
         }
     }
 
+    /// Replay every task still outstanding from before a broker restart (see
+    /// [`FjallQueue::recover_pending`])
+    ///
+    /// Call once at startup, after the worker pool has started draining its
+    /// channels - dispatching potentially many tasks at once could otherwise
+    /// block on a full channel with nothing around to drain it yet.
+    pub async fn recover(&self) -> Result<usize, QueueError> {
+        let pending = self.queue.read().await.recover_pending()?;
+        let count = pending.len();
+        if count > 0 {
+            info!(count, "Replaying tasks outstanding from before restart");
+        }
+        for (seq, task) in pending {
+            self.dispatch(seq, task).await;
+        }
+        Ok(count)
+    }
+
+    /// Spawn the background reaper: on a timer, redeliver any dispatch
+    /// lease older than `visibility_timeout`, bumping `DownloadTask.attempt`,
+    /// or dead-letter it once `max_attempts` is exhausted (see
+    /// [`FjallQueue::expired_dispatches`])
+    pub fn spawn_reaper(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = (self.visibility_timeout / 2).max(Duration::from_millis(100));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let expired = {
+                    let queue = self.queue.read().await;
+                    match queue.expired_dispatches(self.visibility_timeout.as_millis() as u64) {
+                        Ok(expired) => expired,
+                        Err(e) => {
+                            warn!(error = %e, "Failed to scan for expired dispatch leases");
+                            continue;
+                        }
+                    }
+                };
+
+                for (seq, worker_idx, mut task) in expired {
+                    // Either way the old dispatch's slot is freed - it's
+                    // being dead-lettered or about to be redispatched to a
+                    // (possibly different) worker via `pick_worker`.
+                    if let Some(load) = self.worker_loads.get(worker_idx) {
+                        load.fetch_sub(1, Ordering::Relaxed);
+                    }
+
+                    if task.attempt >= self.max_attempts {
+                        warn!(seq, attempt = task.attempt, "Dispatch never acked, dead-lettering");
+                        let queue = self.queue.write().await;
+                        if let Err(e) = queue.dead_letter_dispatch(
+                            seq,
+                            "DISPATCH_TIMED_OUT".to_string(),
+                            "worker never acked within the visibility timeout".to_string(),
+                            task.attempt,
+                        ) {
+                            warn!(seq, error = %e, "Failed to dead-letter expired dispatch");
+                        }
+                        continue;
+                    }
+
+                    task.attempt += 1;
+                    warn!(seq, attempt = task.attempt, "Dispatch never acked, redelivering");
+                    if let Err(e) = self.queue.write().await.update_task(seq, &task) {
+                        warn!(seq, error = %e, "Failed to bump attempt before redelivery");
+                    }
+                    self.dispatch(seq, task).await;
+                }
+            }
+        })
+    }
+
+    /// List dead-lettered resources for a job (operator inspection)
+    pub async fn list_deadletters(&self, job_id: &str) -> Result<Vec<(u64, DeadLetterTask)>, QueueError> {
+        let queue = self.queue.read().await;
+        queue.list_dlq_for_job(job_id)
+    }
+
+    /// Re-enqueue a dead-lettered resource and clear its DLQ entry
+    ///
+    /// Returns `None` if no matching DLQ entry exists, otherwise the new
+    /// sequence number the task was re-enqueued under.
+    pub async fn replay_deadletter(
+        &self,
+        job_id: &str,
+        resource_id: &str,
+    ) -> Result<Option<u64>, QueueError> {
+        let entry = {
+            let queue = self.queue.read().await;
+            queue.find_dlq_entry(job_id, resource_id)?
+        };
+
+        let Some((seq, dlq_entry)) = entry else {
+            return Ok(None);
+        };
+        let Some(task) = dlq_entry.task else {
+            return Ok(None);
+        };
+
+        {
+            let queue = self.queue.write().await;
+            queue.remove_dlq_entry(seq)?;
+        }
+
+        let new_seq = self.enqueue(task).await?;
+        Ok(Some(new_seq))
+    }
+
+    /// Look up a DLQ entry by its Fjall sequence number without replaying or
+    /// removing it (see [`FjallQueue::get_dlq_task`]) - used by
+    /// [`crate::api::services::replay_dlq`] to check the entry's owning
+    /// tenant before [`replay_dlq`](Self::replay_dlq) actually replays it.
+    pub async fn get_dlq_entry(&self, seq: u64) -> Result<Option<DeadLetterTask>, QueueError> {
+        let queue = self.queue.read().await;
+        queue.get_dlq_task(seq)
+    }
+
+    /// Replay a single DLQ entry by its Fjall sequence number (see
+    /// [`FjallQueue::replay_dlq`])
+    pub async fn replay_dlq(&self, seq: u64) -> Result<u64, QueueError> {
+        let queue = self.queue.write().await;
+        queue.replay_dlq(seq)
+    }
+
+    /// The tenant that owns the task dispatched under `seq`, checking both
+    /// the still-enqueued task and (once it's exhausted retries) its DLQ
+    /// entry - used by [`crate::api::services::task_events`] to scope a
+    /// per-task SSE stream to its owning tenant.
+    ///
+    /// Returns `None` once a task has both succeeded and had its dispatch
+    /// lease acked (removing it from `tasks`) - there's no durable record of
+    /// its tenant left to check at that point.
+    pub async fn task_tenant(&self, seq: u64) -> Result<Option<String>, QueueError> {
+        let queue = self.queue.read().await;
+        if let Some(task) = queue.get_task(seq)? {
+            return Ok(Some(task.tenant));
+        }
+        if let Some(dlq) = queue.get_dlq_task(seq)? {
+            if let Some(task) = dlq.task {
+                return Ok(Some(task.tenant));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replay every DLQ entry matching `failure_code` (see
+    /// [`FjallQueue::replay_dlq_all`])
+    pub async fn replay_dlq_all(&self, failure_code: &str) -> Result<Vec<u64>, QueueError> {
+        let queue = self.queue.write().await;
+        queue.replay_dlq_all(failure_code)
+    }
+
+    /// Queue depth for `/metrics` (see [`FjallQueue::queue_depth`])
+    pub async fn queue_depth(&self) -> Result<u64, QueueError> {
+        self.queue.read().await.queue_depth()
+    }
+
+    /// DLQ entry count for `/metrics` (see [`FjallQueue::dlq_size`])
+    pub async fn dlq_size(&self) -> Result<u64, QueueError> {
+        self.queue.read().await.dlq_size()
+    }
+
     /// Get number of active workers
     pub fn num_workers(&self) -> usize {
         self.worker_channels.len()
@@ -122,6 +410,13 @@ impl TaskBroker {
     pub fn health_check(&self) -> bool {
         self.worker_channels.iter().all(|ch| !ch.is_closed())
     }
+
+    /// Tasks currently in flight per worker, indexed the same as
+    /// `worker_channels` - read by `/health` and rendered per-worker in
+    /// `/metrics` (see [`crate::observability::Metrics::set_worker_loads`])
+    pub fn worker_loads(&self) -> Vec<usize> {
+        self.worker_loads.iter().map(|l| l.load(Ordering::Relaxed)).collect()
+    }
 }
 
 #[cfg(test)]
@@ -151,55 +446,50 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
 
-        let (broker, mut receivers) = TaskBroker::new(queue.clone(), 2, 10);
+        let (broker, mut receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 2, 10, Duration::from_secs(30), 3);
         let broker = Arc::new(broker);
 
-        // Enqueue a task
         let task = create_test_task("job1", "res1");
         let seq = broker.enqueue(task.clone()).await.unwrap();
-
         assert_eq!(seq, 0);
-
-        // Should be received by first worker (round-robin starts at 0)
-        let envelope = receivers[0].recv().await.unwrap();
+        assert_eq!(broker.worker_loads().iter().sum::<usize>(), 1);
+
+        // Whichever of the two workers got it, it should be load 1 and the
+        // other load 0 - both workers start idle, so power-of-two-choices
+        // degrades to a coin flip between them.
+        let envelope = if let Ok(env) = receivers[0].try_recv() {
+            env
+        } else {
+            receivers[1].recv().await.unwrap()
+        };
         assert_eq!(envelope.seq, 0);
         assert_eq!(envelope.task.job_id, "job1");
-
-        // Next task should go to worker 1
-        let task2 = create_test_task("job2", "res2");
-        let seq2 = broker.enqueue(task2).await.unwrap();
-        assert_eq!(seq2, 1);
-
-        let envelope2 = receivers[1].recv().await.unwrap();
-        assert_eq!(envelope2.seq, 1);
-        assert_eq!(envelope2.task.job_id, "job2");
     }
 
     #[tokio::test]
-    async fn test_round_robin_distribution() {
+    async fn test_load_aware_distribution_balances_unacked_tasks() {
         let temp_dir = TempDir::new().unwrap();
         let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
 
-        let (broker, mut receivers) = TaskBroker::new(queue.clone(), 3, 10);
+        let (broker, _receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 3, 20, Duration::from_secs(30), 3);
         let broker = Arc::new(broker);
 
-        // Enqueue 6 tasks
-        for i in 0..6 {
+        // Nothing gets acked, so every enqueue should land on whichever
+        // worker is currently least loaded - power-of-two-choices on a pool
+        // this small sees every worker often enough that load should never
+        // drift by more than one task.
+        for i in 0..9 {
             let task = create_test_task(&format!("job{}", i), &format!("res{}", i));
             broker.enqueue(task).await.unwrap();
         }
 
-        // Each worker should receive 2 tasks
-        for worker_id in 0..3 {
-            let env1 = receivers[worker_id].recv().await.unwrap();
-            let env2 = receivers[worker_id].recv().await.unwrap();
-
-            // Worker 0 gets tasks 0, 3
-            // Worker 1 gets tasks 1, 4
-            // Worker 2 gets tasks 2, 5
-            assert_eq!(env1.seq, worker_id as u64);
-            assert_eq!(env2.seq, (worker_id + 3) as u64);
-        }
+        let loads = broker.worker_loads();
+        assert_eq!(loads.iter().sum::<usize>(), 9);
+        let max = *loads.iter().max().unwrap();
+        let min = *loads.iter().min().unwrap();
+        assert!(max - min <= 1, "worker loads too skewed: {:?}", loads);
     }
 
     #[tokio::test]
@@ -207,7 +497,8 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
 
-        let (broker, _receivers) = TaskBroker::new(queue.clone(), 1, 10);
+        let (broker, _receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 1, 10, Duration::from_secs(30), 3);
         // Drop receivers immediately - simulates worker crash
 
         let task = create_test_task("job1", "res1");
@@ -217,4 +508,85 @@ mod tests {
         let retrieved = queue.read().await.get_task(seq).unwrap().unwrap();
         assert_eq!(retrieved.job_id, "job1");
     }
+
+    #[tokio::test]
+    async fn test_ack_clears_task_from_queue() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
+
+        let (broker, mut receivers, ack_tx) =
+            TaskBroker::new(queue.clone(), 1, 10, Duration::from_secs(30), 3);
+
+        let seq = broker.enqueue(create_test_task("job1", "res1")).await.unwrap();
+        receivers[0].recv().await.unwrap();
+
+        ack_tx.send(seq).await.unwrap();
+        // The ack listener runs on its own spawned task - give it a turn.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(queue.read().await.get_task(seq).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reaper_redelivers_unacked_dispatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
+
+        let (broker, mut receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 1, 10, Duration::from_millis(0), 3);
+        let broker = Arc::new(broker);
+        let _reaper = broker.clone().spawn_reaper();
+
+        let seq = broker.enqueue(create_test_task("job1", "res1")).await.unwrap();
+        let first = receivers[0].recv().await.unwrap();
+        assert_eq!(first.seq, seq);
+        assert_eq!(first.task.attempt, 1);
+
+        // Never acked - the reaper should notice the lease expired
+        // immediately (visibility_timeout is zero) and redeliver with a
+        // bumped attempt.
+        let redelivered = receivers[0].recv().await.unwrap();
+        assert_eq!(redelivered.seq, seq);
+        assert_eq!(redelivered.task.attempt, 2);
+    }
+
+    #[tokio::test]
+    async fn test_reaper_dead_letters_exhausted_dispatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
+
+        let (broker, mut receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 1, 10, Duration::from_millis(0), 1);
+        let broker = Arc::new(broker);
+        let _reaper = broker.clone().spawn_reaper();
+
+        let seq = broker.enqueue(create_test_task("job1", "res1")).await.unwrap();
+        receivers[0].recv().await.unwrap();
+
+        // max_attempts is 1, and the task already arrived once - the next
+        // expiry should dead-letter it instead of redelivering.
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(queue.read().await.get_dlq_task(seq).unwrap().is_some());
+        assert!(queue.read().await.get_task(seq).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_recover_replays_outstanding_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = Arc::new(RwLock::new(FjallQueue::open(temp_dir.path()).unwrap()));
+
+        // Simulate a task dispatched by a prior (now-crashed) broker: just
+        // persisted, with no dispatch lease or completion record.
+        let seq = queue.write().await.enqueue(&create_test_task("job1", "res1")).unwrap();
+
+        let (broker, mut receivers, _ack_tx) =
+            TaskBroker::new(queue.clone(), 1, 10, Duration::from_secs(30), 3);
+
+        let count = broker.recover().await.unwrap();
+        assert_eq!(count, 1);
+
+        let envelope = receivers[0].recv().await.unwrap();
+        assert_eq!(envelope.seq, seq);
+        assert_eq!(envelope.task.job_id, "job1");
+    }
 }
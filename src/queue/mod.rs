@@ -1,5 +1,33 @@
 pub mod broker;
+pub mod scheduler;
 pub mod store;
 
 pub use broker::{TaskBroker, TaskEnvelope};
-pub use store::FjallQueue;
+pub use scheduler::Scheduler;
+pub use store::{FjallQueue, QueueError};
+
+use std::path::Path;
+
+type AnyError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Open the [`FjallQueue`] next to a ledger path, matching
+/// [`crate::worker::pool::run`]'s layout. Used by the `fetchbox dlq` CLI
+/// subcommand, which talks to the queue directly rather than through a
+/// running `fetchbox api`/`fetchbox worker` process - it must not run
+/// alongside one, since Fjall's per-path lock is exclusive.
+fn open_queue_for_cli(ledger_path: &str) -> Result<FjallQueue, AnyError> {
+    let queue_path = Path::new(ledger_path).parent().unwrap().join("queue");
+    Ok(FjallQueue::open(queue_path)?)
+}
+
+/// `fetchbox dlq replay <seq>` - replay a single DLQ entry (see
+/// [`FjallQueue::replay_dlq`])
+pub fn replay_dlq(ledger_path: &str, seq: u64) -> Result<u64, AnyError> {
+    Ok(open_queue_for_cli(ledger_path)?.replay_dlq(seq)?)
+}
+
+/// `fetchbox dlq replay-all <failure-code>` - replay every DLQ entry
+/// matching `failure_code` (see [`FjallQueue::replay_dlq_all`])
+pub fn replay_dlq_all(ledger_path: &str, failure_code: &str) -> Result<Vec<u64>, AnyError> {
+    Ok(open_queue_for_cli(ledger_path)?.replay_dlq_all(failure_code)?)
+}
@@ -1,11 +1,13 @@
 use crate::proto::{DeadLetterTask, DownloadTask};
 use fjall::{Config, Keyspace, PartitionCreateOptions, PartitionHandle};
 use prost::Message;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 #[derive(Error, Debug)]
 pub enum QueueError {
@@ -15,6 +17,9 @@ pub enum QueueError {
     #[error("Protobuf decode error: {0}")]
     ProtobufDecode(#[from] prost::DecodeError),
 
+    #[error("JSON (de)serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+
     #[error("Task not found: seq={0}")]
     TaskNotFound(u64),
 
@@ -24,20 +29,76 @@ pub enum QueueError {
 
 pub type Result<T> = std::result::Result<T, QueueError>;
 
+/// Bytes already received for a resumable download, keyed by the task's
+/// Fjall sequence so a worker restart can pick up where a prior attempt
+/// left off instead of refetching from byte zero. `validator` is the
+/// `ETag`/`Last-Modified` sent back as `If-Range` on the resuming request
+/// (see [`crate::worker::http::ResumeFrom`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialDownload {
+    pub bytes: Vec<u8>,
+    pub validator: String,
+}
+
+/// A task's claim record while it's leased out to a worker, keyed by the
+/// task's Fjall sequence
+///
+/// `lease_deadline_ms` is a Unix-epoch millisecond timestamp past which the
+/// claim is considered abandoned (see [`FjallQueue::requeue_expired`]);
+/// `attempts` survives across repeated expiries so the reaper can dead-letter
+/// a task that keeps timing out instead of leasing it out forever.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InflightLease {
+    worker_id: String,
+    lease_deadline_ms: u64,
+    attempts: u32,
+}
+
+/// A task's dispatch record while [`TaskBroker`](crate::queue::TaskBroker)
+/// is waiting on its ack, keyed by the task's Fjall sequence
+///
+/// Distinct from [`InflightLease`]: that one backs the pull-based
+/// `claim_next`/`ack`/`requeue_expired` protocol used by the standalone
+/// `fetchbox worker` binary, while this backs `TaskBroker`'s push-to-mpsc
+/// protocol (see [`FjallQueue::record_dispatch`]). The two are never mixed
+/// for the same queue, so they get their own partition rather than sharing
+/// `inflight`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DispatchLease {
+    worker_idx: usize,
+    dispatched_at_ms: u64,
+}
+
 /// FjallQueue manages task persistence and DLQ using Fjall embedded database
 ///
 /// Architecture:
 /// - `tasks` partition: u64 (big-endian) → DownloadTask (protobuf)
 /// - `metadata` partition: "next_seq" → u64 (atomic counter)
 /// - `dlq` partition: u64 (big-endian) → DeadLetterTask (protobuf)
+/// - `partials` partition: u64 (big-endian) → PartialDownload (JSON)
+/// - `inflight` partition: u64 (big-endian) → InflightLease (JSON)
+/// - `dispatch_leases` partition: u64 (big-endian) → DispatchLease (JSON)
 ///
 /// The queue uses sequential u64 IDs for efficient storage and indexing.
 /// Tasks are persisted atomically before being sent to workers via mpsc channels.
+///
+/// [`claim_next`](Self::claim_next)/[`ack`](Self::ack)/
+/// [`requeue_expired`](Self::requeue_expired) form a separate,
+/// lease-based consumption protocol from [`pull_pending`](Self::pull_pending)
+/// /[`commit_offset`](Self::commit_offset): the former guarantees a task is
+/// delivered to at most one worker at a time within its lease window (and
+/// self-heals if that worker crashes), while the latter simply streams
+/// everything in order and relies on the caller's own in-memory retry
+/// bookkeeping (see [`crate::worker::pool::run_pool`]). A single deployment
+/// should pick one consumption style per queue, not mix both.
 pub struct FjallQueue {
     keyspace: Keyspace,
     tasks: PartitionHandle,
     metadata: PartitionHandle,
     dlq: PartitionHandle,
+    partials: PartitionHandle,
+    inflight: PartitionHandle,
+    dispatch_leases: PartitionHandle,
     seq_counter: Arc<AtomicU64>,
 }
 
@@ -51,6 +112,10 @@ impl FjallQueue {
         let tasks = keyspace.open_partition("tasks", PartitionCreateOptions::default())?;
         let metadata = keyspace.open_partition("metadata", PartitionCreateOptions::default())?;
         let dlq = keyspace.open_partition("dlq", PartitionCreateOptions::default())?;
+        let partials = keyspace.open_partition("partials", PartitionCreateOptions::default())?;
+        let inflight = keyspace.open_partition("inflight", PartitionCreateOptions::default())?;
+        let dispatch_leases =
+            keyspace.open_partition("dispatch_leases", PartitionCreateOptions::default())?;
 
         // Load the current sequence counter from metadata
         let current_seq = metadata
@@ -65,6 +130,9 @@ impl FjallQueue {
             tasks,
             metadata,
             dlq,
+            partials,
+            inflight,
+            dispatch_leases,
             seq_counter: Arc::new(AtomicU64::new(current_seq)),
         })
     }
@@ -109,6 +177,321 @@ impl FjallQueue {
         }
     }
 
+    /// Pull up to `limit` pending tasks with `seq >= from_seq`, in order
+    ///
+    /// Used by the standalone `fetchbox worker` binary ([`crate::worker::pool`])
+    /// to pull batches straight out of Fjall instead of the in-process
+    /// `TaskBroker` mpsc channels, which only exist within the `fetchbox api`
+    /// process that created them.
+    pub fn pull_pending(&self, from_seq: u64, limit: usize) -> Result<Vec<(u64, DownloadTask)>> {
+        let mut results = Vec::new();
+        let mut seq = from_seq;
+
+        while results.len() < limit && seq < self.current_seq() {
+            if let Some(task) = self.get_task(seq)? {
+                results.push((seq, task));
+            }
+            seq += 1;
+        }
+
+        Ok(results)
+    }
+
+    /// Next sequence number the standalone worker pool should pull: every
+    /// task below it has already reached a terminal state, so a restart
+    /// resumes from here instead of reprocessing
+    pub fn commit_offset(&self) -> Result<u64> {
+        let offset = self
+            .metadata
+            .get(b"worker_commit_offset")?
+            .map(|bytes| u64::from_be_bytes(bytes.as_ref().try_into().unwrap_or([0u8; 8])))
+            .unwrap_or(0);
+
+        Ok(offset)
+    }
+
+    /// Advance the persisted commit offset; a no-op if `next_seq` is behind
+    /// the current offset, so out-of-order acks can't rewind it
+    pub fn set_commit_offset(&self, next_seq: u64) -> Result<()> {
+        if next_seq > self.commit_offset()? {
+            self.metadata
+                .insert(b"worker_commit_offset", next_seq.to_be_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Next sequence number [`claim_next`](Self::claim_next) should start
+    /// scanning from
+    fn consumer_cursor(&self) -> Result<u64> {
+        let cursor = self
+            .metadata
+            .get(b"consumer_cursor")?
+            .map(|bytes| u64::from_be_bytes(bytes.as_ref().try_into().unwrap_or([0u8; 8])))
+            .unwrap_or(0);
+
+        Ok(cursor)
+    }
+
+    fn set_consumer_cursor(&self, seq: u64) -> Result<()> {
+        self.metadata
+            .insert(b"consumer_cursor", seq.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Claim the next undelivered task for `worker_id`, leasing it out for
+    /// `lease` before another claim can take it over
+    ///
+    /// Guarantees a task is delivered to at most one worker at a time within
+    /// its lease window: scanning starts at the persisted `consumer_cursor`
+    /// and skips any `seq` currently held by a live (non-expired) lease,
+    /// advancing the cursor past every task claimed for the first time. A
+    /// task whose lease expired without an [`ack`](Self::ack) is not
+    /// rediscovered here - [`requeue_expired`](Self::requeue_expired) is
+    /// responsible for rewinding the cursor back to it.
+    pub fn claim_next(&self, worker_id: &str, lease: Duration) -> Result<Option<(u64, DownloadTask)>> {
+        let now = now_ms();
+        let mut cursor = self.consumer_cursor()?;
+        let end = self.current_seq();
+
+        let claimed = loop {
+            if cursor >= end {
+                break None;
+            }
+
+            let key = cursor.to_be_bytes();
+
+            if self.tasks.get(key)?.is_none() {
+                // Already acked (or never existed) - move past it.
+                cursor += 1;
+                continue;
+            }
+
+            let prior_attempts = match self.inflight.get(key)? {
+                Some(bytes) => {
+                    let lease: InflightLease = serde_json::from_slice(&bytes)?;
+                    if lease.lease_deadline_ms > now {
+                        // Still held by another worker.
+                        cursor += 1;
+                        continue;
+                    }
+                    lease.attempts
+                }
+                None => 0,
+            };
+
+            let record = InflightLease {
+                worker_id: worker_id.to_string(),
+                lease_deadline_ms: now + lease.as_millis() as u64,
+                attempts: prior_attempts.max(1),
+            };
+            self.inflight.insert(key, serde_json::to_vec(&record)?)?;
+
+            let task = self.get_task(cursor)?.ok_or(QueueError::TaskNotFound(cursor))?;
+            let seq = cursor;
+            cursor += 1;
+            debug!(seq, worker_id, "Task claimed");
+            break Some((seq, task));
+        };
+
+        self.set_consumer_cursor(cursor)?;
+        Ok(claimed)
+    }
+
+    /// Acknowledge a successfully processed task, removing it and its lease
+    pub fn ack(&self, seq: u64) -> Result<()> {
+        let key = seq.to_be_bytes();
+        self.tasks.remove(key)?;
+        self.inflight.remove(key)?;
+        debug!(seq, "Task acknowledged");
+        Ok(())
+    }
+
+    /// Record that `seq` was just handed to `worker_idx` over
+    /// [`TaskBroker`](crate::queue::TaskBroker)'s mpsc channel, for
+    /// [`expired_dispatches`](Self::expired_dispatches) to later notice if it
+    /// never gets acked
+    pub fn record_dispatch(&self, seq: u64, worker_idx: usize) -> Result<()> {
+        let lease = DispatchLease { worker_idx, dispatched_at_ms: now_ms() };
+        self.dispatch_leases.insert(seq.to_be_bytes(), serde_json::to_vec(&lease)?)?;
+        Ok(())
+    }
+
+    /// Bump a dispatch lease's `dispatched_at_ms` to now, keeping its
+    /// `worker_idx` - the heartbeat a worker still actively processing `seq`
+    /// sends (see [`crate::worker::spawn_pool`]) so
+    /// [`expired_dispatches`](Self::expired_dispatches) doesn't treat a
+    /// long-running download as an abandoned one.
+    ///
+    /// A no-op if the lease is already gone (acked or reaped out from under
+    /// the worker between its last heartbeat and this one) - nothing left to
+    /// renew.
+    pub fn renew_dispatch(&self, seq: u64) -> Result<()> {
+        let key = seq.to_be_bytes();
+        let Some(existing) = self.dispatch_leases.get(key)? else {
+            return Ok(());
+        };
+        let mut lease: DispatchLease = serde_json::from_slice(&existing)?;
+        lease.dispatched_at_ms = now_ms();
+        self.dispatch_leases.insert(key, serde_json::to_vec(&lease)?)?;
+        Ok(())
+    }
+
+    /// Acknowledge a task dispatched through [`TaskBroker`](crate::queue::TaskBroker),
+    /// removing it and its dispatch lease - the push-protocol counterpart to
+    /// [`ack`](Self::ack)
+    ///
+    /// Returns the `worker_idx` the cleared lease was dispatched to (`None`
+    /// if it was already gone), so the caller can release that worker's
+    /// in-flight count.
+    pub fn ack_dispatch(&self, seq: u64) -> Result<Option<usize>> {
+        let key = seq.to_be_bytes();
+        let worker_idx = self
+            .dispatch_leases
+            .get(key)?
+            .map(|v| serde_json::from_slice::<DispatchLease>(&v))
+            .transpose()?
+            .map(|lease| lease.worker_idx);
+        self.tasks.remove(key)?;
+        self.dispatch_leases.remove(key)?;
+        debug!(seq, "Dispatched task acknowledged");
+        Ok(worker_idx)
+    }
+
+    /// Overwrite the persisted task at `seq`, e.g. to bump `attempt` before
+    /// a visibility-timeout redelivery
+    pub fn update_task(&self, seq: u64, task: &DownloadTask) -> Result<()> {
+        self.tasks.insert(seq.to_be_bytes(), task.encode_to_vec())?;
+        Ok(())
+    }
+
+    /// Dispatch leases older than `visibility_timeout_ms`, paired with the
+    /// `worker_idx` they were dispatched to and their still-persisted task -
+    /// a worker that received one of these and never acked it has either
+    /// crashed or is still stuck processing it; either way
+    /// [`TaskBroker`](crate::queue::TaskBroker)'s reaper treats it as lost
+    /// and redelivers, releasing `worker_idx`'s in-flight count first
+    pub fn expired_dispatches(
+        &self,
+        visibility_timeout_ms: u64,
+    ) -> Result<Vec<(u64, usize, DownloadTask)>> {
+        let now = now_ms();
+        let mut expired = Vec::new();
+
+        for item in self.dispatch_leases.iter() {
+            let (key, value) = item?;
+            let lease: DispatchLease = serde_json::from_slice(&value)?;
+            if now.saturating_sub(lease.dispatched_at_ms) < visibility_timeout_ms {
+                continue;
+            }
+            let seq = u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8]));
+            if let Some(task) = self.get_task(seq)? {
+                expired.push((seq, lease.worker_idx, task));
+            } else {
+                // Acked/dead-lettered between the scan and now but the lease
+                // hadn't been cleaned up yet - drop the stale lease.
+                self.dispatch_leases.remove(seq.to_be_bytes())?;
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Move a dispatched task straight to the DLQ and drop its lease,
+    /// bypassing [`move_to_dlq`](Self::move_to_dlq)'s "keep the row around"
+    /// default - once `TaskBroker` gives up on a task it should stop
+    /// showing up in [`expired_dispatches`](Self::expired_dispatches)
+    pub fn dead_letter_dispatch(
+        &self,
+        seq: u64,
+        failure_code: String,
+        failure_message: String,
+        attempts: u32,
+    ) -> Result<()> {
+        self.move_to_dlq(seq, failure_code, failure_message, attempts)?;
+        let key = seq.to_be_bytes();
+        self.tasks.remove(key)?;
+        self.dispatch_leases.remove(key)?;
+        Ok(())
+    }
+
+    /// Every task still sitting in `tasks` with no DLQ entry, in `seq` order
+    ///
+    /// Called once at process startup: a crashed `TaskBroker` takes its
+    /// in-memory dispatch leases and mpsc channels down with it, so the only
+    /// durable record of what's still outstanding is "present in `tasks`,
+    /// absent from `dlq`" - every such task is replayed through
+    /// [`TaskBroker::enqueue`](crate::queue::TaskBroker)'s dispatch path as
+    /// if it had just arrived.
+    pub fn recover_pending(&self) -> Result<Vec<(u64, DownloadTask)>> {
+        let mut pending = Vec::new();
+
+        for item in self.tasks.iter() {
+            let (key, value) = item?;
+            let seq = u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8]));
+            if self.dlq.get(seq.to_be_bytes())?.is_some() {
+                continue;
+            }
+            let task = DownloadTask::decode(&*value)?;
+            pending.push((seq, task));
+        }
+
+        pending.sort_by_key(|(seq, _)| *seq);
+        Ok(pending)
+    }
+
+    /// Reap leases past their deadline: a task still under `max_attempts` is
+    /// released so [`claim_next`](Self::claim_next) picks it up again, while
+    /// one that has exhausted its attempts is moved to the DLQ instead.
+    ///
+    /// Returns the number of expired leases handled.
+    pub fn requeue_expired(&self, now_ms: u64, max_attempts: u32) -> Result<usize> {
+        let mut expired = Vec::new();
+        for item in self.inflight.iter() {
+            let (key, value) = item?;
+            let lease: InflightLease = serde_json::from_slice(&value)?;
+            if lease.lease_deadline_ms <= now_ms {
+                let seq = u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8]));
+                expired.push((seq, lease));
+            }
+        }
+
+        let mut cursor = self.consumer_cursor()?;
+        for (seq, lease) in &expired {
+            let key = seq.to_be_bytes();
+            let attempts = lease.attempts + 1;
+
+            if attempts > max_attempts {
+                warn!(seq, attempts, max_attempts, "Lease expired too many times, moving to DLQ");
+                self.move_to_dlq(
+                    *seq,
+                    "LEASE_EXPIRED".to_string(),
+                    format!("worker {} never acked within its lease", lease.worker_id),
+                    attempts,
+                )?;
+                self.inflight.remove(key)?;
+                // Unlike a plain lease release, this task is done for good -
+                // remove it so `claim_next` doesn't pick it back up.
+                self.tasks.remove(key)?;
+            } else {
+                warn!(seq, attempts, worker_id = %lease.worker_id, "Lease expired, releasing for redelivery");
+                let released = InflightLease {
+                    worker_id: String::new(),
+                    lease_deadline_ms: 0,
+                    attempts,
+                };
+                self.inflight.insert(key, serde_json::to_vec(&released)?)?;
+                cursor = cursor.min(*seq);
+            }
+        }
+
+        if !expired.is_empty() {
+            self.set_consumer_cursor(cursor)?;
+        }
+
+        Ok(expired.len())
+    }
+
     /// Move a task to the Dead Letter Queue (DLQ)
     ///
     /// Called when a task exhausts all retries or encounters a permanent failure.
@@ -172,11 +555,148 @@ impl FjallQueue {
         Ok(results)
     }
 
+    /// List DLQ entries belonging to a single job, for operator inspection
+    ///
+    /// The DLQ is still keyed by sequence number, so this scans the whole
+    /// partition and filters by the original task's `job_id`. Fine at the
+    /// scale a single job's dead letters are expected to reach.
+    pub fn list_dlq_for_job(&self, job_id: &str) -> Result<Vec<(u64, DeadLetterTask)>> {
+        let mut results = Vec::new();
+
+        for item in self.dlq.iter() {
+            let (key, value) = item?;
+            let dlq_task = DeadLetterTask::decode(&*value)?;
+            if dlq_task.task.as_ref().is_some_and(|t| t.job_id == job_id) {
+                let seq = u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8]));
+                results.push((seq, dlq_task));
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Find a single DLQ entry by job_id + resource_id, for operator replay
+    pub fn find_dlq_entry(
+        &self,
+        job_id: &str,
+        resource_id: &str,
+    ) -> Result<Option<(u64, DeadLetterTask)>> {
+        for item in self.dlq.iter() {
+            let (key, value) = item?;
+            let dlq_task = DeadLetterTask::decode(&*value)?;
+            if let Some(task) = &dlq_task.task {
+                if task.job_id == job_id && task.resource_id == resource_id {
+                    let seq = u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8]));
+                    return Ok(Some((seq, dlq_task)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Persist a task's partially-received download body, overwriting any
+    /// prior entry for the same sequence
+    pub fn save_partial(&self, seq: u64, partial: &PartialDownload) -> Result<()> {
+        let key = seq.to_be_bytes();
+        let value = serde_json::to_vec(partial)?;
+        self.partials.insert(key, value)?;
+        debug!(seq, bytes = partial.bytes.len(), "Saved partial download");
+        Ok(())
+    }
+
+    /// Retrieve a task's partially-received download body, if one was saved
+    pub fn get_partial(&self, seq: u64) -> Result<Option<PartialDownload>> {
+        let key = seq.to_be_bytes();
+        match self.partials.get(key)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Drop a task's saved partial download, once it either completes or is
+    /// restarted from scratch (e.g. the server stopped honoring the range)
+    pub fn remove_partial(&self, seq: u64) -> Result<()> {
+        let key = seq.to_be_bytes();
+        self.partials.remove(key)?;
+        debug!(seq, "Removed partial download");
+        Ok(())
+    }
+
+    /// Remove a DLQ entry by sequence number, after it has been replayed
+    pub fn remove_dlq_entry(&self, seq: u64) -> Result<()> {
+        let key = seq.to_be_bytes();
+        self.dlq.remove(key)?;
+        debug!(seq, "Removed DLQ entry");
+        Ok(())
+    }
+
+    /// Replay a single DLQ entry: re-enqueue its inner [`DownloadTask`] under
+    /// a fresh sequence (with `attempt` reset to 1, since whatever exhausted
+    /// it no longer applies) and remove the DLQ entry. Returns the new
+    /// sequence number.
+    ///
+    /// Unlike [`TaskBroker::replay_deadletter`](crate::queue::TaskBroker::replay_deadletter),
+    /// which looks a DLQ entry up by `job_id`/`resource_id` and leaves
+    /// `attempt` untouched, this is keyed by the DLQ's own `seq` and always
+    /// gives the replay a clean slate - see
+    /// `fetchbox dlq replay` ([`crate::queue::replay_dlq`]).
+    pub fn replay_dlq(&self, seq: u64) -> Result<u64> {
+        let dlq_entry = self
+            .get_dlq_task(seq)?
+            .ok_or(QueueError::TaskNotFound(seq))?;
+        let mut task = dlq_entry.task.ok_or(QueueError::TaskNotFound(seq))?;
+        task.attempt = 1;
+
+        let new_seq = self.enqueue(&task)?;
+        self.dlq.remove(seq.to_be_bytes())?;
+        info!(seq, new_seq, "Replayed DLQ entry");
+
+        Ok(new_seq)
+    }
+
+    /// Replay every DLQ entry whose `failure_code` matches, e.g. bulk-retrying
+    /// every `NETWORK_ERROR` once a dead proxy is fixed. Returns the new
+    /// sequence number for each entry replayed, in DLQ iteration order; a
+    /// single entry failing to replay aborts the rest, same as any other
+    /// `?`-propagated Fjall operation here.
+    pub fn replay_dlq_all(&self, failure_code: &str) -> Result<Vec<u64>> {
+        let mut matching = Vec::new();
+        for item in self.dlq.iter() {
+            let (key, value) = item?;
+            let dlq_task = DeadLetterTask::decode(&*value)?;
+            if dlq_task.failure_code == failure_code {
+                matching.push(u64::from_be_bytes(key.as_ref().try_into().unwrap_or([0u8; 8])));
+            }
+        }
+
+        matching.into_iter().map(|seq| self.replay_dlq(seq)).collect()
+    }
+
     /// Get current sequence counter value
     pub fn current_seq(&self) -> u64 {
         self.seq_counter.load(Ordering::SeqCst)
     }
 
+    /// Queue depth for the lease-based claim/ack protocol: tasks enqueued
+    /// but not yet claimed by [`claim_next`](Self::claim_next), i.e.
+    /// `current_seq - consumer_cursor`. A deployment consuming purely
+    /// through [`pull_pending`](Self::pull_pending)/
+    /// [`commit_offset`](Self::commit_offset) (or the in-process
+    /// `TaskBroker` mpsc channels) never advances the consumer cursor, so
+    /// this stays pinned at `current_seq` there - see
+    /// [`crate::observability::Metrics::set_queue_depth`].
+    pub fn queue_depth(&self) -> Result<u64> {
+        Ok(self.current_seq().saturating_sub(self.consumer_cursor()?))
+    }
+
+    /// Number of entries currently held in the DLQ partition; scans the
+    /// whole partition like [`list_dlq_for_job`](Self::list_dlq_for_job),
+    /// fine at the scale a DLQ is expected to reach.
+    pub fn dlq_size(&self) -> Result<u64> {
+        Ok(self.dlq.iter().count() as u64)
+    }
+
     /// Flush all writes to disk
     pub fn flush(&self) -> Result<()> {
         self.keyspace.persist(fjall::PersistMode::SyncAll)?;
@@ -268,6 +788,30 @@ mod tests {
         assert!(dlq_task.task.is_some());
     }
 
+    #[test]
+    fn test_save_get_remove_partial() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        let task = create_test_task("partial_job");
+        let seq = queue.enqueue(&task).unwrap();
+
+        assert!(queue.get_partial(seq).unwrap().is_none());
+
+        let partial = PartialDownload {
+            bytes: vec![1, 2, 3, 4],
+            validator: "\"etag-123\"".to_string(),
+        };
+        queue.save_partial(seq, &partial).unwrap();
+
+        let loaded = queue.get_partial(seq).unwrap().unwrap();
+        assert_eq!(loaded.bytes, vec![1, 2, 3, 4]);
+        assert_eq!(loaded.validator, "\"etag-123\"");
+
+        queue.remove_partial(seq).unwrap();
+        assert!(queue.get_partial(seq).unwrap().is_none());
+    }
+
     #[test]
     fn test_persistence_across_reopens() {
         let temp_dir = TempDir::new().unwrap();
@@ -288,4 +832,169 @@ mod tests {
         let old_task = queue.get_task(seq).unwrap().unwrap();
         assert_eq!(old_task.job_id, "job1");
     }
+
+    #[test]
+    fn test_pull_pending_respects_limit_and_offset() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        queue.enqueue(&create_test_task("job1")).unwrap();
+        queue.enqueue(&create_test_task("job2")).unwrap();
+        queue.enqueue(&create_test_task("job3")).unwrap();
+
+        let batch = queue.pull_pending(0, 2).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].0, 0);
+        assert_eq!(batch[1].0, 1);
+
+        let rest = queue.pull_pending(2, 10).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].0, 2);
+    }
+
+    #[test]
+    fn test_commit_offset_is_monotonic() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        assert_eq!(queue.commit_offset().unwrap(), 0);
+
+        queue.set_commit_offset(5).unwrap();
+        assert_eq!(queue.commit_offset().unwrap(), 5);
+
+        // Stale/out-of-order acks can't rewind the offset
+        queue.set_commit_offset(2).unwrap();
+        assert_eq!(queue.commit_offset().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_claim_next_then_ack() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        queue.enqueue(&create_test_task("job1")).unwrap();
+        queue.enqueue(&create_test_task("job2")).unwrap();
+
+        let (seq1, task1) = queue.claim_next("worker-a", Duration::from_secs(30)).unwrap().unwrap();
+        assert_eq!(seq1, 0);
+        assert_eq!(task1.job_id, "job1");
+
+        // Already leased to worker-a - worker-b gets the next one instead.
+        let (seq2, task2) = queue.claim_next("worker-b", Duration::from_secs(30)).unwrap().unwrap();
+        assert_eq!(seq2, 1);
+        assert_eq!(task2.job_id, "job2");
+
+        assert!(queue.claim_next("worker-c", Duration::from_secs(30)).unwrap().is_none());
+
+        queue.ack(seq1).unwrap();
+        assert!(queue.get_task(seq1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_requeue_expired_releases_under_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        queue.enqueue(&create_test_task("job1")).unwrap();
+        let (seq, _) = queue
+            .claim_next("worker-a", Duration::from_millis(0))
+            .unwrap()
+            .unwrap();
+
+        let reaped = queue.requeue_expired(now_ms(), 3).unwrap();
+        assert_eq!(reaped, 1);
+
+        // Released, under the attempt cap - a new worker can claim it again.
+        let (reclaimed_seq, _) = queue
+            .claim_next("worker-b", Duration::from_secs(30))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reclaimed_seq, seq);
+        assert!(queue.get_dlq_task(seq).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_queue_depth_and_dlq_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        queue.enqueue(&create_test_task("job1")).unwrap();
+        let seq2 = queue.enqueue(&create_test_task("job2")).unwrap();
+        queue.enqueue(&create_test_task("job3")).unwrap();
+
+        assert_eq!(queue.queue_depth().unwrap(), 0); // nothing claimed yet
+        assert_eq!(queue.dlq_size().unwrap(), 0);
+
+        queue.claim_next("worker-a", Duration::from_secs(30)).unwrap();
+        assert_eq!(queue.queue_depth().unwrap(), 2); // one of three claimed
+
+        queue
+            .move_to_dlq(seq2, "NETWORK_ERROR".to_string(), "boom".to_string(), 3)
+            .unwrap();
+        assert_eq!(queue.dlq_size().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_replay_dlq_resets_attempt_and_clears_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        let seq = queue.enqueue(&create_test_task("job1")).unwrap();
+        queue
+            .move_to_dlq(seq, "NETWORK_ERROR".to_string(), "Connection timeout".to_string(), 3)
+            .unwrap();
+
+        let new_seq = queue.replay_dlq(seq).unwrap();
+        assert!(queue.get_dlq_task(seq).unwrap().is_none());
+
+        let replayed = queue.get_task(new_seq).unwrap().unwrap();
+        assert_eq!(replayed.job_id, "job1");
+        assert_eq!(replayed.attempt, 1);
+    }
+
+    #[test]
+    fn test_replay_dlq_all_filters_by_failure_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        let seq1 = queue.enqueue(&create_test_task("job1")).unwrap();
+        let seq2 = queue.enqueue(&create_test_task("job2")).unwrap();
+        let seq3 = queue.enqueue(&create_test_task("job3")).unwrap();
+        queue
+            .move_to_dlq(seq1, "NETWORK_ERROR".to_string(), "boom".to_string(), 3)
+            .unwrap();
+        queue
+            .move_to_dlq(seq2, "VALIDATION_FAILED".to_string(), "bad mime".to_string(), 3)
+            .unwrap();
+        queue
+            .move_to_dlq(seq3, "NETWORK_ERROR".to_string(), "boom again".to_string(), 3)
+            .unwrap();
+
+        let replayed = queue.replay_dlq_all("NETWORK_ERROR").unwrap();
+        assert_eq!(replayed.len(), 2);
+        assert!(queue.get_dlq_task(seq1).unwrap().is_none());
+        assert!(queue.get_dlq_task(seq3).unwrap().is_none());
+        assert!(queue.get_dlq_task(seq2).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_requeue_expired_dead_letters_past_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = FjallQueue::open(temp_dir.path()).unwrap();
+
+        queue.enqueue(&create_test_task("job1")).unwrap();
+        let (seq, _) = queue
+            .claim_next("worker-a", Duration::from_millis(0))
+            .unwrap()
+            .unwrap();
+
+        // attempts starts at 1 on first claim, so a cap of 1 is exceeded by
+        // the very first expiry.
+        let reaped = queue.requeue_expired(now_ms(), 1).unwrap();
+        assert_eq!(reaped, 1);
+
+        let dlq_task = queue.get_dlq_task(seq).unwrap().unwrap();
+        assert_eq!(dlq_task.failure_code, "LEASE_EXPIRED");
+        assert!(queue.claim_next("worker-b", Duration::from_secs(30)).unwrap().is_none());
+    }
 }
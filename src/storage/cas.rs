@@ -0,0 +1,94 @@
+//! Content-addressed storage (CAS) helpers
+//!
+//! Borrows the content-addressable model [cacache](https://crates.io/crates/cacache)
+//! popularized, scaled down to what this crate needs: a `sha256-<hex>`
+//! integrity string over a blob and a deterministic storage key derived from
+//! it, so two identical uploads converge on the same blob instead of paying
+//! for a duplicate. Two callers build on these primitives, each suited to
+//! its own dedup check:
+//!
+//! - [`crate::api::services::ingest_job`] pairs [`storage_key`] with a
+//!   `FjallStore` metadata-partition index (`integrity -> storage key`, see
+//!   [`crate::ledger::partitions::encode_cas_key`]) to short-circuit the
+//!   manifest upload entirely when the index already has an entry whose blob
+//!   still exists.
+//! - [`crate::storage::StorageClient::upload_content_addressed`] uses
+//!   [`storage_key_with_prefix`] directly and checks the object store itself
+//!   (no ledger index) - a fit for download artifacts, which `StorageClient`
+//!   handles with no `FjallStore` dependency in scope.
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CasError {
+    #[error("invalid integrity string: {0}")]
+    InvalidIntegrity(String),
+}
+
+/// Compute the `sha256-<hex>` integrity string for `data`
+pub fn compute_integrity(data: &[u8]) -> String {
+    format!("sha256-{}", hex::encode(Sha256::digest(data)))
+}
+
+/// Derive the hash-sharded storage key for an integrity string under the
+/// `cas/` prefix, e.g. `cas/ab/cd/abcd1234...`. Shorthand for
+/// [`storage_key_with_prefix`] with `"cas"` - see that function for why the
+/// key is sharded.
+pub fn storage_key(integrity: &str) -> Result<String, CasError> {
+    storage_key_with_prefix("cas", integrity)
+}
+
+/// Derive the hash-sharded storage key for an integrity string under an
+/// arbitrary prefix, e.g. `resources/ab/cd/abcd1234...` - sharding by the
+/// first two hex-byte pairs keeps a single object store "directory" from
+/// collecting every blob the store has ever seen as it grows.
+pub fn storage_key_with_prefix(prefix: &str, integrity: &str) -> Result<String, CasError> {
+    let hash = integrity
+        .strip_prefix("sha256-")
+        .ok_or_else(|| CasError::InvalidIntegrity(integrity.to_string()))?;
+    if hash.len() < 4 {
+        return Err(CasError::InvalidIntegrity(integrity.to_string()));
+    }
+    Ok(format!("{}/{}/{}/{}", prefix, &hash[0..2], &hash[2..4], hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_integrity_is_stable_sha256() {
+        let integrity = compute_integrity(b"hello world");
+        assert_eq!(
+            integrity,
+            "sha256-b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_storage_key_shards_by_hash_prefix() {
+        let integrity = compute_integrity(b"hello world");
+        let key = storage_key(&integrity).unwrap();
+        assert_eq!(
+            key,
+            "cas/b9/4d/b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_storage_key_rejects_non_sha256_integrity() {
+        assert!(storage_key("md5-deadbeef").is_err());
+        assert!(storage_key("sha256-").is_err());
+    }
+
+    #[test]
+    fn test_storage_key_with_prefix_shards_under_custom_prefix() {
+        let integrity = compute_integrity(b"hello world");
+        let key = storage_key_with_prefix("resources", &integrity).unwrap();
+        assert_eq!(
+            key,
+            "resources/b9/4d/b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+}
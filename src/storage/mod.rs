@@ -1,11 +1,51 @@
 //! Object storage abstraction for manifests and artifacts
 //! Uses Apache Arrow object_store crate
+//!
+//! [`StorageClient::from_config`]/[`StorageClient::from_url`] build against
+//! whichever `object_store` backend `storage.provider` selects; the `aws`,
+//! `gcp`, and `azure` `object_store` Cargo features must all be enabled for
+//! every provider to be available, same as `aws` already needs to be today
+//! for `StorageProvider::S3`.
+
+pub mod cas;
 
 use async_trait::async_trait;
-use object_store::{ObjectStore, path::Path as StoragePath};
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use object_store::{
+    Attribute, Attributes, BackoffConfig, GetOptions, GetRange, ObjectStore, PutOptions,
+    RetryConfig, aws::AmazonS3Builder, azure::MicrosoftAzureBuilder,
+    gcp::GoogleCloudStorageBuilder, local::LocalFileSystem, path::Path as StoragePath,
+};
+use std::fmt;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::config::{StorageCompression, StorageConfig, StorageProvider, StorageRetryConfig};
+
+/// Per-object codec tag prefixed to every payload [`StorageClient`] writes,
+/// so `download` can decompress correctly even after `storage.compression`
+/// changes - mixed-codec data stays readable rather than only the codec
+/// active at read time.
+const CODEC_RAW: u8 = 0;
+const CODEC_ZSTD: u8 = 1;
+
+/// Default part size for [`StorageClient::upload_multipart`] - matches
+/// [`crate::worker::WorkerConfig`]'s default `upload_part_size_bytes`, which
+/// is the only caller today.
+pub const DEFAULT_MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Prepend `codec`'s tag byte to `payload`
+fn frame(codec: u8, payload: Vec<u8>) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(payload.len() + 1);
+    framed.push(codec);
+    framed.extend_from_slice(&payload);
+    framed
+}
+
 #[derive(Debug, Error)]
 pub enum StorageError {
     #[error("Upload failed: {0}")]
@@ -19,6 +59,21 @@ pub enum StorageError {
 
     #[error("Object store error: {0}")]
     ObjectStoreError(#[from] object_store::Error),
+
+    #[error("Invalid storage configuration: {0}")]
+    ConfigError(String),
+
+    /// An `if_match`/`if_unmodified_since` predicate in a
+    /// [`StorageClient::download_opts`] call didn't hold - the object
+    /// changed since the caller last saw it
+    #[error("Precondition failed for {0}")]
+    PreconditionFailed(String),
+
+    /// An `if_none_match`/`if_modified_since` predicate in a
+    /// [`StorageClient::download_opts`] call held - the object is unchanged,
+    /// so the caller can reuse its cached copy instead of re-downloading
+    #[error("Not modified: {0}")]
+    NotModified(String),
 }
 
 /// Storage result type
@@ -29,7 +84,132 @@ pub type Result<T> = std::result::Result<T, StorageError>;
 pub struct UploadMetadata {
     pub key: String,
     pub etag: Option<String>,
+    /// Original (uncompressed) payload size
     pub size: usize,
+    /// Bytes actually written to the object store, including the 1-byte
+    /// codec tag - smaller than `size` when `storage.compression` is zstd
+    /// and the payload compressed well
+    pub stored_size: usize,
+    /// The payload's `sha256-<hex>` integrity string (see [`cas`]), if this
+    /// upload went through [`StorageClient::upload_content_addressed`];
+    /// `None` for a plain [`StorageClient::upload`]/[`StorageClient::upload_multipart`]
+    pub digest: Option<String>,
+    /// `true` if [`StorageClient::upload_content_addressed`] found an
+    /// existing blob at the derived key and skipped the upload; always
+    /// `false` outside that path
+    pub deduped: bool,
+}
+
+/// Byte range for a [`StorageClient::download_opts`] request - mirrors
+/// `object_store::GetRange`'s three shapes without leaking that type
+/// through our public API.
+#[derive(Debug, Clone)]
+pub enum ByteRange {
+    /// `start..end`, both absolute byte offsets into the object
+    Bounded(Range<u64>),
+    /// Everything from `offset` to the end of the object
+    Offset(u64),
+    /// The last `n` bytes of the object
+    Suffix(u64),
+}
+
+impl From<ByteRange> for GetRange {
+    fn from(range: ByteRange) -> Self {
+        match range {
+            ByteRange::Bounded(r) => GetRange::Bounded(r),
+            ByteRange::Offset(o) => GetRange::Offset(o),
+            ByteRange::Suffix(n) => GetRange::Suffix(n),
+        }
+    }
+}
+
+/// Request options for [`StorageClient::download_opts`]: an optional byte
+/// range, plus conditional predicates evaluated against the object's
+/// current ETag/last-modified time before any bytes are transferred.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadOptions {
+    /// Fetch only this range instead of the whole object - lets a caller
+    /// resume a partial read after an interruption
+    pub range: Option<ByteRange>,
+    /// Only return the object if its current ETag matches; a mismatch
+    /// surfaces as [`StorageError::PreconditionFailed`]
+    pub if_match: Option<String>,
+    /// Only return the object if its current ETag does *not* match (e.g.
+    /// revalidating a cached copy); a match surfaces as
+    /// [`StorageError::NotModified`]
+    pub if_none_match: Option<String>,
+    /// Only return the object if it's been modified since this time; an
+    /// unmodified object surfaces as [`StorageError::NotModified`]
+    pub if_modified_since: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Bytes plus metadata returned by [`StorageClient::download_opts`]
+#[derive(Debug, Clone)]
+pub struct DownloadedObject {
+    pub bytes: Vec<u8>,
+    pub etag: Option<String>,
+    pub size: usize,
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+}
+
+/// Cumulative original-vs-stored bytes across every [`StorageClient::upload`]/
+/// [`StorageClient::upload_multipart`] call, for
+/// [`crate::observability::Metrics::set_storage_compression_bytes`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompressionStats {
+    pub original_bytes: u64,
+    pub stored_bytes: u64,
+}
+
+/// Wraps an in-progress `object_store` multipart upload so it's always
+/// aborted rather than left dangling: an early `?` return from
+/// [`StorageClient::upload_multipart`] drops this guard, and cancellation of
+/// the enclosing future (e.g. a client disconnect) drops it too - both paths
+/// previously only worked for the explicit-error case, leaving orphaned
+/// parts behind on the cancellation path.
+///
+/// [`Self::complete`] takes `self` by value specifically so a successful
+/// finish consumes the guard before `Drop` ever runs.
+struct AbortOnDropUpload {
+    upload: Option<Box<dyn object_store::MultipartUpload>>,
+}
+
+impl AbortOnDropUpload {
+    fn new(upload: Box<dyn object_store::MultipartUpload>) -> Self {
+        Self { upload: Some(upload) }
+    }
+
+    async fn put_part(&mut self, data: Bytes) -> Result<()> {
+        self.upload
+            .as_mut()
+            .expect("put_part called after complete")
+            .put_part(data.into())
+            .await?;
+        Ok(())
+    }
+
+    async fn complete(mut self) -> Result<object_store::PutResult> {
+        let result = self
+            .upload
+            .as_mut()
+            .expect("complete called twice")
+            .complete()
+            .await?;
+        self.upload = None;
+        Ok(result)
+    }
+}
+
+impl Drop for AbortOnDropUpload {
+    fn drop(&mut self) {
+        if let Some(mut upload) = self.upload.take() {
+            tokio::spawn(async move {
+                if let Err(e) = upload.abort().await {
+                    tracing::warn!(error = %e, "failed to abort orphaned multipart upload");
+                }
+            });
+        }
+    }
 }
 
 /// Storage client wrapping object_store
@@ -37,41 +217,307 @@ pub struct UploadMetadata {
 pub struct StorageClient {
     store: Arc<dyn ObjectStore>,
     pub bucket: String,
+    compression: StorageCompression,
+    original_bytes: Arc<AtomicU64>,
+    stored_bytes: Arc<AtomicU64>,
 }
 
 impl StorageClient {
     /// Create new storage client with any object_store backend
     pub fn new(store: Arc<dyn ObjectStore>, bucket: String) -> Self {
-        Self { store, bucket }
+        Self {
+            store,
+            bucket,
+            compression: StorageCompression::None,
+            original_bytes: Arc::new(AtomicU64::new(0)),
+            stored_bytes: Arc::new(AtomicU64::new(0)),
+        }
     }
 
     /// Create in-memory storage for testing/development
     pub fn in_memory() -> Self {
-        Self {
-            store: Arc::new(object_store::memory::InMemory::new()),
-            bucket: "fetchbox-local".to_string(),
+        Self::new(
+            Arc::new(object_store::memory::InMemory::new()),
+            "fetchbox-local".to_string(),
+        )
+    }
+
+    /// Compress payloads with `compression` instead of storing them as-is
+    pub fn with_compression(mut self, compression: StorageCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Cumulative original/stored bytes across every write so far, for the
+    /// `fetchbox_storage_*_bytes_total` metrics
+    pub fn compression_stats(&self) -> CompressionStats {
+        CompressionStats {
+            original_bytes: self.original_bytes.load(Ordering::Relaxed),
+            stored_bytes: self.stored_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Frame `data` for storage: a 1-byte codec tag ([`CODEC_RAW`] or
+    /// [`CODEC_ZSTD`]) followed by the (possibly compressed) payload. Falls
+    /// back to storing raw if zstd compression errors, rather than failing
+    /// the upload over a codec that isn't strictly required for an
+    /// already-encoded payload.
+    fn encode_payload(&self, data: Vec<u8>) -> Vec<u8> {
+        match &self.compression {
+            StorageCompression::None => frame(CODEC_RAW, data),
+            StorageCompression::Zstd { level } => match zstd::stream::encode_all(data.as_slice(), *level) {
+                Ok(compressed) => frame(CODEC_ZSTD, compressed),
+                Err(e) => {
+                    tracing::warn!(error = %e, "zstd compression failed, storing payload raw");
+                    frame(CODEC_RAW, data)
+                }
+            },
+        }
+    }
+
+    /// Reverse [`Self::encode_payload`], dispatching on the leading codec
+    /// tag rather than `self.compression` - an object written under a
+    /// previous `storage.compression` setting must still decode correctly.
+    fn decode_payload(framed: Bytes) -> Result<Vec<u8>> {
+        let Some((codec, body)) = framed.split_first() else {
+            return Ok(Vec::new());
+        };
+        match *codec {
+            CODEC_RAW => Ok(body.to_vec()),
+            CODEC_ZSTD => zstd::stream::decode_all(body)
+                .map_err(|e| StorageError::DownloadFailed(format!("zstd decompression failed: {e}"))),
+            other => Err(StorageError::DownloadFailed(format!(
+                "unrecognized storage codec tag {other}"
+            ))),
+        }
+    }
+
+    fn record_compression(&self, original: usize, stored: usize) {
+        self.original_bytes.fetch_add(original as u64, Ordering::Relaxed);
+        self.stored_bytes.fetch_add(stored as u64, Ordering::Relaxed);
+    }
+
+    /// Build a storage client from `[storage]` config, selecting the backend
+    /// by `config.provider`.
+    ///
+    /// `StorageProvider::Local` is the in-memory store (tests/dev, nothing
+    /// persisted); `File` builds a real `object_store::local::LocalFileSystem`
+    /// rooted at `config.path`; `S3`/`Gcs`/`Azure` build the matching cloud
+    /// backend from `config`'s bucket/region/endpoint and the credentials
+    /// loaded from the environment by [`crate::config::sources::load`].
+    ///
+    /// TLS for the cloud backends comes from `object_store`'s own HTTP
+    /// client - selecting rustls over native-tls is a Cargo feature choice
+    /// on the `object_store` dependency (e.g. `tls-webpki-roots`), not
+    /// something this function configures at runtime, so there's no rustls
+    /// handle to thread through here.
+    pub fn from_config(config: &StorageConfig) -> Result<Self> {
+        let retry = Self::build_retry_config(&config.retry);
+
+        match config.provider {
+            StorageProvider::Local => Ok(Self::in_memory().with_compression(config.compression.clone())),
+            StorageProvider::File => {
+                let path = config.path.as_deref().ok_or_else(|| {
+                    StorageError::ConfigError("File storage requires storage.path".to_string())
+                })?;
+                let store = LocalFileSystem::new_with_prefix(path)
+                    .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+
+                Ok(Self::new(Arc::new(store), config.bucket.clone())
+                    .with_compression(config.compression.clone()))
+            }
+            StorageProvider::S3 => {
+                let mut builder = AmazonS3Builder::new()
+                    .with_bucket_name(&config.bucket)
+                    .with_retry(retry);
+
+                if let Some(region) = &config.region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(endpoint) = &config.endpoint {
+                    builder = builder.with_endpoint(endpoint).with_allow_http(true);
+                }
+                let access_key = config.access_key.as_deref().ok_or_else(|| {
+                    StorageError::ConfigError(
+                        "S3 storage requires access_key (set S3_ACCESS_KEY or AWS_ACCESS_KEY_ID)"
+                            .to_string(),
+                    )
+                })?;
+                let secret_key = config.secret_key.as_deref().ok_or_else(|| {
+                    StorageError::ConfigError(
+                        "S3 storage requires secret_key (set S3_SECRET_KEY or AWS_SECRET_ACCESS_KEY)"
+                            .to_string(),
+                    )
+                })?;
+                builder = builder
+                    .with_access_key_id(access_key)
+                    .with_secret_access_key(secret_key);
+
+                let store = builder
+                    .build()
+                    .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+
+                Ok(Self::new(Arc::new(store), config.bucket.clone())
+                    .with_compression(config.compression.clone()))
+            }
+            StorageProvider::Gcs => {
+                // No access_key/secret_key check here, unlike S3/Azure:
+                // `from_env` reads `GOOGLE_SERVICE_ACCOUNT`/`GOOGLE_APPLICATION_CREDENTIALS`
+                // itself, and `build()` below fails with a clear error if
+                // neither is set.
+                let mut builder = GoogleCloudStorageBuilder::from_env()
+                    .with_bucket_name(&config.bucket)
+                    .with_retry(retry);
+                if let Some(endpoint) = &config.endpoint {
+                    builder = builder.with_url(endpoint.clone());
+                }
+
+                let store = builder
+                    .build()
+                    .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+
+                Ok(Self::new(Arc::new(store), config.bucket.clone())
+                    .with_compression(config.compression.clone()))
+            }
+            StorageProvider::Azure => {
+                // Azure has no S3-style access/secret pair, so this reuses
+                // `access_key`/`secret_key` as account name/access key
+                // rather than adding two more near-duplicate config fields.
+                let account = config.access_key.as_deref().ok_or_else(|| {
+                    StorageError::ConfigError(
+                        "Azure storage requires access_key (storage account name)".to_string(),
+                    )
+                })?;
+                let access_key = config.secret_key.as_deref().ok_or_else(|| {
+                    StorageError::ConfigError(
+                        "Azure storage requires secret_key (storage account access key)".to_string(),
+                    )
+                })?;
+                let mut builder = MicrosoftAzureBuilder::new()
+                    .with_account(account)
+                    .with_access_key(access_key)
+                    .with_container_name(&config.bucket)
+                    .with_retry(retry);
+                if let Some(endpoint) = &config.endpoint {
+                    builder = builder.with_endpoint(endpoint.clone());
+                }
+
+                let store = builder
+                    .build()
+                    .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+
+                Ok(Self::new(Arc::new(store), config.bucket.clone())
+                    .with_compression(config.compression.clone()))
+            }
         }
     }
 
-    /// Upload bytes to storage
+    /// Build a storage client by parsing a connection URL instead of a
+    /// pre-populated [`StorageConfig`] - `scheme` selects the backend
+    /// (`s3://`, `gs://`, `az://`, `file://`, `memory://`) and `authority`
+    /// becomes `bucket` (ignored for `file://`/`memory://`, which have no
+    /// bucket concept). Everything else - credentials, region, retry policy
+    /// - still comes from `config`; the URL only ever supplies "where",
+    /// mirroring how most `object_store` deployments keep that separate
+    /// from "how".
+    pub fn from_url(url: &str, config: &StorageConfig) -> Result<Self> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| StorageError::ConfigError(format!("invalid storage URL '{url}': {e}")))?;
+
+        let mut config = config.clone();
+        if let Some(host) = parsed.host_str() {
+            config.bucket = host.to_string();
+        }
+
+        config.provider = match parsed.scheme() {
+            "s3" => StorageProvider::S3,
+            "gs" => StorageProvider::Gcs,
+            "az" => StorageProvider::Azure,
+            "file" => {
+                config.path = Some(parsed.path().to_string());
+                StorageProvider::File
+            }
+            "memory" => StorageProvider::Local,
+            other => {
+                return Err(StorageError::ConfigError(format!(
+                    "unsupported storage URL scheme '{other}' (expected s3/gs/az/file/memory)"
+                )));
+            }
+        };
+
+        Self::from_config(&config)
+    }
+
+    /// Translate [`StorageRetryConfig`] into `object_store`'s own retry
+    /// type, shared by every networked backend's `.with_retry(...)`
+    /// (`S3`/`Gcs`/`Azure` - `LocalFileSystem` has no network to retry
+    /// over, so `File`/`Local` never call this).
+    ///
+    /// `retry.jitter` has no separate knob to wire in here: `object_store`'s
+    /// backoff already randomizes each delay on its own, so the field is
+    /// kept on [`StorageRetryConfig`] for operators to read as documentation
+    /// of the behavior they should expect, not as something this function
+    /// threads through.
+    fn build_retry_config(retry: &StorageRetryConfig) -> RetryConfig {
+        RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(retry.base_delay_ms),
+                max_backoff: Duration::from_secs(30),
+                base: 2.0,
+            },
+            max_retries: retry.max_retries,
+            retry_timeout: Duration::from_secs(retry.retry_timeout_secs),
+        }
+    }
+
+    /// Upload bytes to storage, compressed per `storage.compression` (see
+    /// [`Self::encode_payload`])
     pub async fn upload(&self, key: &str, data: Vec<u8>) -> Result<UploadMetadata> {
+        self.upload_with_content_type(key, data, None).await
+    }
+
+    /// [`Self::upload`], additionally tagging the object with a
+    /// `Content-Type` attribute - used by
+    /// [`crate::worker::runner::download_and_store`] once a resource's
+    /// actual MIME has been sniffed from its bytes (see
+    /// [`crate::worker::validate::sniff_mime`]), so a consumer reading the
+    /// object straight out of the bucket sees accurate metadata rather than
+    /// none at all.
+    pub async fn upload_with_content_type(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<UploadMetadata> {
         let path = StoragePath::from(key);
-        let size = data.len();
+        let original_size = data.len();
+        let framed = self.encode_payload(data);
+        let stored_size = framed.len();
+        self.record_compression(original_size, stored_size);
+
+        let attributes = content_type
+            .map(|ct| Attributes::from_iter([(Attribute::ContentType, ct.to_string().into())]))
+            .unwrap_or_default();
+        let opts = PutOptions { attributes, ..Default::default() };
 
         let put_result = self.store
-            .put(&path, data.into())
+            .put_opts(&path, framed.into(), opts)
             .await?;
 
-        tracing::info!(key, size, "Uploaded to storage");
+        tracing::info!(key, original_size, stored_size, "Uploaded to storage");
 
         Ok(UploadMetadata {
             key: key.to_string(),
             etag: put_result.e_tag.clone(),
-            size,
+            size: original_size,
+            stored_size,
+            digest: None,
+            deduped: false,
         })
     }
 
-    /// Download from storage
+    /// Download from storage, transparently decompressing per the
+    /// downloaded object's own codec tag (see [`Self::decode_payload`])
     pub async fn download(&self, key: &str) -> Result<Vec<u8>> {
         let path = StoragePath::from(key);
 
@@ -79,11 +525,179 @@ impl StorageClient {
             .get(&path)
             .await?;
 
-        let bytes = result.bytes().await?;
+        let framed = result.bytes().await?;
+        let bytes = Self::decode_payload(framed)?;
 
         tracing::info!(key, size = bytes.len(), "Downloaded from storage");
 
-        Ok(bytes.to_vec())
+        Ok(bytes)
+    }
+
+    /// Range-aware, conditional counterpart to [`Self::download`]: fetches
+    /// via `ObjectStore::get_opts` instead of always pulling the whole
+    /// object, so a caller can resume an interrupted partial read
+    /// (`opts.range`) or revalidate a cached copy without re-downloading
+    /// unchanged bytes (`opts.if_none_match`/`opts.if_modified_since`).
+    ///
+    /// Unlike [`Self::download`], the returned bytes are **not** passed
+    /// through [`Self::decode_payload`] - a byte range can't generally be
+    /// decompressed on its own, so this returns exactly what's stored at
+    /// `key` (the codec tag included, for objects written by
+    /// [`Self::upload`]). Objects written by [`Self::upload_multipart`] are
+    /// always raw ([`CODEC_RAW`]), so this is the natural fit for resuming
+    /// those.
+    pub async fn download_opts(&self, key: &str, opts: DownloadOptions) -> Result<DownloadedObject> {
+        let path = StoragePath::from(key);
+
+        let get_options = GetOptions {
+            range: opts.range.map(Into::into),
+            if_match: opts.if_match,
+            if_none_match: opts.if_none_match,
+            if_modified_since: opts.if_modified_since,
+            ..Default::default()
+        };
+
+        let result = self
+            .store
+            .get_opts(&path, get_options)
+            .await
+            .map_err(Self::map_conditional_error)?;
+
+        let etag = result.meta.e_tag.clone();
+        let last_modified = result.meta.last_modified;
+        let bytes = result.bytes().await.map_err(Self::map_conditional_error)?;
+
+        tracing::info!(key, size = bytes.len(), "Downloaded from storage (with options)");
+
+        Ok(DownloadedObject {
+            size: bytes.len(),
+            bytes: bytes.to_vec(),
+            etag,
+            last_modified,
+        })
+    }
+
+    /// Map `object_store`'s conditional-request errors onto
+    /// [`StorageError::PreconditionFailed`]/[`StorageError::NotModified`] so
+    /// [`Self::download_opts`] callers can match on them instead of parsing
+    /// the underlying error's `Display` output
+    fn map_conditional_error(e: object_store::Error) -> StorageError {
+        match e {
+            object_store::Error::Precondition { path, source } => {
+                StorageError::PreconditionFailed(format!("{}: {}", path, source))
+            }
+            object_store::Error::NotModified { path, source } => {
+                StorageError::NotModified(format!("{}: {}", path, source))
+            }
+            other => other.into(),
+        }
+    }
+
+    /// Upload a chunk stream via a multipart upload, for bodies too large to
+    /// buffer fully in memory first (see
+    /// [`crate::worker::runner::StreamingPolicy`]). Use
+    /// [`DEFAULT_MULTIPART_PART_SIZE`] for `part_size` unless a caller has a
+    /// reason to tune it.
+    ///
+    /// Incoming chunks are coalesced into `part_size`-sized pieces before
+    /// each `put_part` call - the source stream (a [`reqwest`] body via
+    /// [`crate::worker::http::HttpClient::download_stream`]) yields chunks
+    /// of whatever size the server happened to write, which rarely lines up
+    /// with a good multipart part size. A short final part is flushed on
+    /// completion even if it never reached `part_size`.
+    ///
+    /// Always stores with the raw codec tag regardless of
+    /// `storage.compression`: compressing would mean buffering the whole
+    /// body to frame it as one object anyway, which defeats the point of
+    /// streaming in the first place (see [`crate::worker::runner::StreamingPolicy`]
+    /// for the same buffered-vs-streamed tradeoff applied to validation).
+    ///
+    /// If `stream` yields an error, or this call is dropped before finishing
+    /// (e.g. the enclosing task is cancelled on client disconnect), the
+    /// in-progress upload is aborted rather than left dangling - see
+    /// [`AbortOnDropUpload`]. Per-part progress is the caller's
+    /// responsibility to track on the way in, same as
+    /// [`crate::worker::runner::download_and_store_streaming`] already does
+    /// by wrapping `stream` with `inspect_ok` before passing it here.
+    pub async fn upload_multipart<S, E>(
+        &self,
+        key: &str,
+        mut stream: S,
+        part_size: usize,
+    ) -> Result<UploadMetadata>
+    where
+        S: Stream<Item = std::result::Result<Bytes, E>> + Unpin,
+        E: fmt::Display,
+    {
+        let path = StoragePath::from(key);
+        let mut upload = AbortOnDropUpload::new(self.store.put_multipart(&path).await?);
+        let mut pending = BytesMut::new();
+        pending.extend_from_slice(&[CODEC_RAW]);
+        let mut original_size = 0usize;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| StorageError::UploadFailed(e.to_string()))?;
+            original_size += chunk.len();
+            pending.extend_from_slice(&chunk);
+            while pending.len() >= part_size {
+                let part = pending.split_to(part_size);
+                upload.put_part(part.freeze()).await?;
+            }
+        }
+        if !pending.is_empty() {
+            upload.put_part(pending.freeze()).await?;
+        }
+
+        let put_result = upload.complete().await?;
+        let stored_size = original_size + 1;
+        self.record_compression(original_size, stored_size);
+
+        tracing::info!(key, original_size, stored_size, "Uploaded to storage (multipart)");
+
+        Ok(UploadMetadata {
+            key: key.to_string(),
+            etag: put_result.e_tag.clone(),
+            size: original_size,
+            stored_size,
+            digest: None,
+            deduped: false,
+        })
+    }
+
+    /// Content-addressed upload: derives the storage key from `data`'s
+    /// SHA-256 digest (see [`cas::storage_key_with_prefix`]) instead of
+    /// taking one from the caller, and skips the upload entirely when an
+    /// object already exists at that key - identical artifacts converge on
+    /// one blob rather than paying for a duplicate upload and transfer.
+    ///
+    /// Checks existence directly against the object store rather than
+    /// through a ledger index, unlike the similar manifest-dedup path in
+    /// [`crate::api::services::ingest_job`] (see [`cas`]) - `StorageClient`
+    /// has no `FjallStore` dependency in scope, and the key is deterministic
+    /// from the digest anyway, so there's nothing an index would add here.
+    ///
+    /// `prefix` namespaces the hash-sharded key, e.g. `"resources"` yields
+    /// keys like `resources/ab/cd/abcd1234...`.
+    pub async fn upload_content_addressed(&self, prefix: &str, data: Vec<u8>) -> Result<UploadMetadata> {
+        let digest = cas::compute_integrity(&data);
+        let key = cas::storage_key_with_prefix(prefix, &digest)
+            .map_err(|e| StorageError::ConfigError(e.to_string()))?;
+
+        if self.exists(&key).await? {
+            tracing::info!(key, digest = %digest, "Content-addressed upload deduped, blob already exists");
+            return Ok(UploadMetadata {
+                size: data.len(),
+                key,
+                etag: None,
+                stored_size: 0,
+                digest: Some(digest),
+                deduped: true,
+            });
+        }
+
+        let mut uploaded = self.upload(&key, data).await?;
+        uploaded.digest = Some(digest);
+        Ok(uploaded)
     }
 
     /// Check if key exists
@@ -97,3 +711,240 @@ impl StorageClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_url_file_scheme_builds_local_filesystem() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("file://{}", dir.path().display());
+        let config = StorageConfig::default();
+
+        let storage = StorageClient::from_url(&url, &config).unwrap();
+        assert_eq!(storage.bucket, config.bucket);
+    }
+
+    #[test]
+    fn test_from_url_memory_scheme_builds_in_memory_store() {
+        let config = StorageConfig::default();
+        let storage = StorageClient::from_url("memory://local-bucket", &config).unwrap();
+        // The authority is ignored for `memory://`; the in-memory store keeps its own bucket name.
+        assert_eq!(storage.bucket, "fetchbox-local");
+    }
+
+    #[test]
+    fn test_from_url_rejects_unsupported_scheme() {
+        let config = StorageConfig::default();
+        let result = StorageClient::from_url("ftp://example.com", &config);
+        assert!(matches!(result, Err(StorageError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_from_config_file_requires_path() {
+        let mut config = StorageConfig::default();
+        config.provider = StorageProvider::File;
+        config.path = None;
+
+        let result = StorageClient::from_config(&config);
+        assert!(matches!(result, Err(StorageError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_upload_download_roundtrips_uncompressed() {
+        let storage = StorageClient::in_memory();
+        let uploaded = storage.upload("k", b"hello world".to_vec()).await.unwrap();
+
+        assert_eq!(uploaded.size, 11);
+        assert_eq!(uploaded.stored_size, 12); // + 1-byte codec tag
+        assert_eq!(storage.download("k").await.unwrap(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_upload_with_content_type_roundtrips() {
+        let storage = StorageClient::in_memory();
+        let uploaded = storage
+            .upload_with_content_type("k", b"fake png bytes".to_vec(), Some("image/png"))
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.key, "k");
+        assert_eq!(storage.download("k").await.unwrap(), b"fake png bytes");
+    }
+
+    #[tokio::test]
+    async fn test_upload_download_roundtrips_zstd_compressed() {
+        let storage = StorageClient::in_memory()
+            .with_compression(StorageCompression::Zstd { level: 3 });
+        let payload = "a".repeat(4096).into_bytes();
+
+        let uploaded = storage.upload("k", payload.clone()).await.unwrap();
+
+        assert_eq!(uploaded.size, payload.len());
+        assert!(uploaded.stored_size < payload.len(), "highly repetitive payload should compress");
+        assert_eq!(storage.download("k").await.unwrap(), payload);
+    }
+
+    #[tokio::test]
+    async fn test_download_reads_object_written_under_a_different_codec() {
+        // A reader's current `compression` setting must not matter: each
+        // object decodes per its own tag, so a config change mid-flight
+        // doesn't strand previously-written objects.
+        let store: Arc<dyn ObjectStore> = Arc::new(object_store::memory::InMemory::new());
+        let writer = StorageClient::new(store.clone(), "fetchbox-local".to_string())
+            .with_compression(StorageCompression::Zstd { level: 3 });
+        writer.upload("k", b"tagged payload".to_vec()).await.unwrap();
+
+        let reader = StorageClient::new(store, "fetchbox-local".to_string());
+        assert_eq!(reader.download("k").await.unwrap(), b"tagged payload");
+    }
+
+    #[tokio::test]
+    async fn test_compression_stats_accumulate_across_uploads() {
+        let storage = StorageClient::in_memory()
+            .with_compression(StorageCompression::Zstd { level: 3 });
+        storage.upload("a", vec![1u8; 1024]).await.unwrap();
+        storage.upload("b", vec![2u8; 1024]).await.unwrap();
+
+        let stats = storage.compression_stats();
+        assert_eq!(stats.original_bytes, 2048);
+        assert!(stats.stored_bytes < stats.original_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_upload_multipart_is_readable_by_download() {
+        let storage = StorageClient::in_memory()
+            .with_compression(StorageCompression::Zstd { level: 3 });
+        let chunks: Vec<std::result::Result<Bytes, std::io::Error>> =
+            vec![Ok(Bytes::from_static(b"part-one")), Ok(Bytes::from_static(b"part-two"))];
+        let stream = futures::stream::iter(chunks);
+
+        let uploaded = storage
+            .upload_multipart("k", stream, 4)
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.size, 16);
+        // Multipart bypasses compression - only the raw codec tag is added.
+        assert_eq!(uploaded.stored_size, 17);
+        assert_eq!(storage.download("k").await.unwrap(), b"part-onepart-two");
+    }
+
+    #[tokio::test]
+    async fn test_upload_multipart_aborts_on_stream_error() {
+        let storage = StorageClient::in_memory();
+        let chunks: Vec<std::result::Result<Bytes, std::io::Error>> = vec![
+            Ok(Bytes::from_static(b"part-one")),
+            Err(std::io::Error::other("connection reset")),
+        ];
+        let stream = futures::stream::iter(chunks);
+
+        let result = storage.upload_multipart("k", stream, 4).await;
+
+        assert!(matches!(result, Err(StorageError::UploadFailed(_))));
+        assert!(!storage.exists("k").await.unwrap(), "aborted upload must not leave a visible object");
+    }
+
+    #[tokio::test]
+    async fn test_upload_multipart_aborts_when_dropped_before_completion() {
+        let storage = StorageClient::in_memory();
+        // A stream that never resolves: simulates the enclosing future being
+        // cancelled mid-upload rather than returning an error.
+        let upload = storage.upload_multipart(
+            "k",
+            futures::stream::pending::<std::result::Result<Bytes, std::io::Error>>(),
+            4,
+        );
+        tokio::time::timeout(Duration::from_millis(10), upload)
+            .await
+            .expect_err("stream never resolves, so the timeout should fire first");
+
+        // Give the Drop-spawned abort task a chance to run.
+        tokio::task::yield_now().await;
+        assert!(!storage.exists("k").await.unwrap(), "dropped upload must not leave a visible object");
+    }
+
+    #[tokio::test]
+    async fn test_upload_content_addressed_derives_key_from_digest() {
+        let storage = StorageClient::in_memory();
+
+        let uploaded = storage
+            .upload_content_addressed("resources", b"hello world".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            uploaded.key,
+            "resources/b9/4d/b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_eq!(uploaded.digest.as_deref(), Some(cas::compute_integrity(b"hello world").as_str()));
+        assert!(!uploaded.deduped);
+        assert_eq!(storage.download(&uploaded.key).await.unwrap(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_upload_content_addressed_dedups_identical_payload() {
+        let storage = StorageClient::in_memory();
+
+        let first = storage
+            .upload_content_addressed("resources", b"same bytes".to_vec())
+            .await
+            .unwrap();
+        assert!(!first.deduped);
+
+        let second = storage
+            .upload_content_addressed("resources", b"same bytes".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(second.key, first.key);
+        assert!(second.deduped, "identical payload should be deduped, not re-uploaded");
+    }
+
+    #[tokio::test]
+    async fn test_download_opts_fetches_byte_range() {
+        let storage = StorageClient::in_memory();
+        storage.upload("k", b"hello world".to_vec()).await.unwrap();
+
+        // Offset by 1 to skip the leading codec tag byte this crate's own
+        // `upload` prepends - `download_opts` returns exactly what's stored.
+        let opts = DownloadOptions {
+            range: Some(ByteRange::Bounded(1..6)),
+            ..Default::default()
+        };
+        let result = storage.download_opts("k", opts).await.unwrap();
+
+        assert_eq!(result.bytes, b"hello");
+        assert_eq!(result.size, 5);
+    }
+
+    #[tokio::test]
+    async fn test_download_opts_if_none_match_returns_not_modified() {
+        let storage = StorageClient::in_memory();
+        let uploaded = storage.upload("k", b"hello world".to_vec()).await.unwrap();
+        let etag = uploaded.etag.expect("in-memory store assigns an ETag");
+
+        let opts = DownloadOptions {
+            if_none_match: Some(etag),
+            ..Default::default()
+        };
+        let result = storage.download_opts("k", opts).await;
+
+        assert!(matches!(result, Err(StorageError::NotModified(_))));
+    }
+
+    #[tokio::test]
+    async fn test_download_opts_if_match_stale_etag_returns_precondition_failed() {
+        let storage = StorageClient::in_memory();
+        storage.upload("k", b"hello world".to_vec()).await.unwrap();
+
+        let opts = DownloadOptions {
+            if_match: Some("\"stale-etag\"".to_string()),
+            ..Default::default()
+        };
+        let result = storage.download_opts("k", opts).await;
+
+        assert!(matches!(result, Err(StorageError::PreconditionFailed(_))));
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-host concurrency limiting for outbound downloads
+//!
+//! [`WorkerConfig::max_inflight_tasks`](super::WorkerConfig) bounds the
+//! worker pool as a whole, but that budget is shared across every resource
+//! in flight - a job whose resources all live on one slow/rate-limited host
+//! could otherwise claim every slot and starve every other job's downloads.
+//! [`HostLimiter`] adds a second, per-host [`Semaphore`], sized independently
+//! of the pool, so a single domain can hold at most `max_per_host` downloads
+//! at once no matter how many workers are free.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Caps concurrent downloads per host, lazily creating a [`Semaphore`] the
+/// first time a host is seen.
+pub struct HostLimiter {
+    max_per_host: usize,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimiter {
+    pub fn new(max_per_host: usize) -> Self {
+        Self {
+            max_per_host: max_per_host.max(1),
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn semaphore_for(&self, host: &str) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().unwrap();
+        semaphores
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+            .clone()
+    }
+
+    /// Wait for a free slot on `host`'s semaphore. The returned permit must
+    /// be held for the duration of the download and dropped once it
+    /// completes (success or failure) to free the slot.
+    pub async fn acquire(&self, host: &str) -> OwnedSemaphorePermit {
+        self.semaphore_for(host)
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed")
+    }
+}
+
+/// Extract the host FetchBox should rate-limit `url` by, falling back to the
+/// full URL itself if it can't be parsed (so a malformed URL still gets its
+/// own isolated slot instead of panicking or bypassing the limiter).
+pub fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_host_of_extracts_host() {
+        assert_eq!(host_of("https://example.com/file.jpg"), "example.com");
+        assert_eq!(host_of("https://a.example.com:8080/x"), "a.example.com");
+    }
+
+    #[test]
+    fn test_host_of_falls_back_to_url_when_unparsable() {
+        assert_eq!(host_of("not a url"), "not a url");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_caps_concurrency_per_host() {
+        let limiter = Arc::new(HostLimiter::new(1));
+
+        let first = limiter.acquire("example.com").await;
+
+        // A second acquire for the same host should not resolve while the
+        // first permit is still held.
+        let limiter2 = limiter.clone();
+        let blocked = tokio::spawn(async move { limiter2.acquire("example.com").await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_secs(1), blocked)
+            .await
+            .expect("permit should free up once the first is dropped");
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_different_hosts_do_not_share_a_slot() {
+        let limiter = HostLimiter::new(1);
+
+        let _a = limiter.acquire("a.example.com").await;
+        let b = tokio::time::timeout(Duration::from_millis(50), limiter.acquire("b.example.com")).await;
+
+        assert!(b.is_ok());
+    }
+}
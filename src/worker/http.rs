@@ -1,10 +1,11 @@
 //! HTTP client for downloading resources
 
-use bytes::Bytes;
-use reqwest::{Client, Proxy};
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use reqwest::{Client, Proxy, Response};
 use std::time::Duration;
 use thiserror::Error;
-use tracing::{debug, warn};
+use tracing::debug;
 
 #[derive(Debug, Error)]
 pub enum DownloadError {
@@ -19,17 +20,105 @@ pub enum DownloadError {
 
     #[error("Too many redirects")]
     TooManyRedirects,
+
+    /// The connection dropped partway through the body. `partial` holds
+    /// whatever bytes had already arrived, and `accept_ranges`/`etag`/
+    /// `last_modified` carry the response metadata needed to build a
+    /// [`ResumeFrom`] for the next attempt - see
+    /// [`crate::queue::FjallQueue::save_partial`].
+    #[error("download interrupted after {received_len} bytes: {message}")]
+    PartialBody {
+        partial: Bytes,
+        received_len: usize,
+        accept_ranges: bool,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        message: String,
+    },
+
+    /// `Content-Length` (or the running total while streaming) exceeded
+    /// [`HttpConfig::max_content_length`] - checked both up front and as
+    /// bytes arrive, so an unbounded or mislabeled response can't exhaust
+    /// worker memory.
+    #[error("response exceeds maximum content length of {limit} bytes (got at least {actual})")]
+    ContentTooLarge { limit: u64, actual: u64 },
+}
+
+impl DownloadError {
+    /// Stable, low-cardinality label for
+    /// `fetchbox_download_failures_total{error=...}` (see
+    /// [`crate::observability::Metrics::record_download_failure`]) - a
+    /// fixed set of variant names rather than the free-form `Display`
+    /// message, which would blow up the metric's cardinality with raw
+    /// URLs/status text.
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            DownloadError::RequestFailed(_) => "request_failed",
+            DownloadError::Timeout => "timeout",
+            DownloadError::InvalidUrl(_) => "invalid_url",
+            DownloadError::TooManyRedirects => "too_many_redirects",
+            DownloadError::PartialBody { .. } => "partial_body",
+            DownloadError::ContentTooLarge { .. } => "content_too_large",
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, DownloadError>;
 
+/// Body and response metadata from a completed download
+///
+/// Carries the `Content-Type`/`Content-Length` headers alongside the body so
+/// callers (see [`crate::worker::validate::ContentValidator`]) can validate
+/// the response without re-issuing the request. `accept_ranges`/`etag`/
+/// `last_modified` let a caller decide whether a later retry can resume via
+/// [`ResumeFrom`] instead of re-downloading from byte zero, and `status`
+/// alone distinguishes a `206 Partial Content` resume from a `200 OK` full
+/// body (the server ignoring - or invalidating - the `Range`/`If-Range`
+/// request).
+#[derive(Debug, Clone)]
+pub struct DownloadResponse {
+    pub bytes: Bytes,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub accept_ranges: bool,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Response metadata without the body, returned alongside the chunk stream
+/// from [`HttpClient::download_stream`]
+#[derive(Debug, Clone)]
+pub struct DownloadMeta {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub accept_ranges: bool,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Where to resume a previously interrupted download from
+///
+/// `validator` is the `ETag` (preferred) or `Last-Modified` captured from
+/// the original response, sent back as `If-Range` so a resource that
+/// changed between attempts restarts cleanly instead of corrupting the
+/// buffer with a stale tail.
+#[derive(Debug, Clone)]
+pub struct ResumeFrom {
+    pub received_bytes: u64,
+    pub validator: String,
+}
+
 /// HTTP client configuration
 #[derive(Debug, Clone)]
 pub struct HttpConfig {
     pub connect_timeout: Duration,
     pub request_timeout: Duration,
-    pub max_retries: u32,
     pub user_agent: String,
+    /// Hard cap on a single response body; `None` leaves it unbounded (see
+    /// [`DownloadError::ContentTooLarge`])
+    pub max_content_length: Option<u64>,
 }
 
 impl Default for HttpConfig {
@@ -37,8 +126,8 @@ impl Default for HttpConfig {
         Self {
             connect_timeout: Duration::from_secs(10),
             request_timeout: Duration::from_secs(60),
-            max_retries: 3,
             user_agent: "FetchBox/0.1.0".to_string(),
+            max_content_length: None,
         }
     }
 }
@@ -72,53 +161,167 @@ impl HttpClient {
         Ok(Self { client, config })
     }
 
-    /// Download a resource with retry
+    /// Download a resource, a single attempt
+    ///
+    /// Retries no longer happen in here: a failed attempt used to block this
+    /// call behind an inline `tokio::time::sleep`, which meant one stalled
+    /// URL serialized the whole retry budget and starved every other
+    /// in-flight download on the worker. Retry scheduling now lives one
+    /// layer up, in [`super::retry::RetryScheduler`], so a failed attempt is
+    /// parked and the worker immediately moves on to other ready work.
+    ///
+    /// When `resume` is `Some`, issues `Range: bytes=<received>-` with an
+    /// `If-Range` validator so an interrupted transfer can continue where it
+    /// left off instead of restarting from byte zero; a `206` response means
+    /// the body is only the missing tail, while a `200` means the server
+    /// ignored (or invalidated) the range and the caller must discard
+    /// whatever it had buffered.
+    ///
+    /// Returns the response body alongside its status code and the
+    /// `Content-Type`/`Content-Length` headers, so callers can feed the
+    /// status into [`crate::observability::Metrics::observe_download`] and
+    /// the rest into [`crate::worker::validate::ContentValidator`]. If the
+    /// connection drops mid-body, the bytes received so far come back via
+    /// [`DownloadError::PartialBody`] rather than being discarded.
     pub async fn download(
         &self,
         url: &str,
         headers: Vec<(String, String)>,
-    ) -> Result<Bytes> {
-        let mut attempts = 0;
-        let mut last_error = String::new();
+        resume: Option<&ResumeFrom>,
+    ) -> Result<DownloadResponse> {
+        self.download_once(url, &headers, resume).await
+    }
 
-        loop {
-            attempts += 1;
+    /// Download once (no retry)
+    async fn download_once(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        resume: Option<&ResumeFrom>,
+    ) -> Result<DownloadResponse> {
+        let (response, meta) = self.send(url, headers, resume).await?;
 
-            match self.download_once(url, &headers).await {
-                Ok(bytes) => {
-                    if attempts > 1 {
-                        debug!(url, attempts, "Download succeeded after retry");
+        // Stream the body instead of buffering it in one shot, so a
+        // connection dropped mid-transfer still yields whatever arrived
+        // before it failed (see `DownloadError::PartialBody`), and a
+        // mislabeled/unbounded response is caught mid-flight rather than
+        // only once it's fully buffered.
+        let mut buffer = BytesMut::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => {
+                    buffer.extend_from_slice(&chunk);
+                    if let Some(limit) = self.config.max_content_length {
+                        if buffer.len() as u64 > limit {
+                            return Err(DownloadError::ContentTooLarge {
+                                limit,
+                                actual: buffer.len() as u64,
+                            });
+                        }
                     }
-                    return Ok(bytes);
                 }
                 Err(e) => {
-                    last_error = e.to_string();
-
-                    if attempts >= self.config.max_retries {
-                        warn!(url, attempts, error = %last_error, "Download failed after retries");
-                        return Err(DownloadError::RequestFailed(format!(
-                            "Failed after {} attempts: {}",
-                            attempts, last_error
-                        )));
-                    }
+                    let received_len = buffer.len();
+                    return Err(DownloadError::PartialBody {
+                        partial: buffer.freeze(),
+                        received_len,
+                        accept_ranges: meta.accept_ranges,
+                        etag: meta.etag,
+                        last_modified: meta.last_modified,
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+        let bytes = buffer.freeze();
+
+        debug!(url, size = bytes.len(), "Download completed");
 
-                    warn!(url, attempts, error = %last_error, "Download failed, retrying");
+        Ok(DownloadResponse {
+            bytes,
+            status: meta.status,
+            content_type: meta.content_type,
+            content_length: meta.content_length,
+            accept_ranges: meta.accept_ranges,
+            etag: meta.etag,
+            last_modified: meta.last_modified,
+        })
+    }
 
-                    // Exponential backoff: 1s, 2s, 4s
-                    let backoff = Duration::from_secs(2u64.pow(attempts - 1));
-                    tokio::time::sleep(backoff).await;
+    /// Download a resource as a chunk stream instead of a single buffered
+    /// body, for responses too large to hold in memory at once (see
+    /// [`super::runner::StreamingPolicy`]). The caller is expected to pipe
+    /// chunks straight into [`crate::storage::StorageClient::upload_multipart`]
+    /// as they arrive.
+    ///
+    /// Unlike [`download`](Self::download), a connection drop mid-stream
+    /// surfaces as a plain [`DownloadError::RequestFailed`] item on the
+    /// stream rather than [`DownloadError::PartialBody`] - there is no
+    /// single buffer to hand back, since the bytes already yielded have
+    /// presumably already been uploaded by the caller.
+    pub async fn download_stream(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        resume: Option<&ResumeFrom>,
+    ) -> Result<(DownloadMeta, impl Stream<Item = Result<Bytes>> + Send)> {
+        let (response, meta) = self.send(url, headers, resume).await?;
+
+        let limit = self.config.max_content_length;
+        let body = response.bytes_stream().scan(0u64, move |received, chunk| {
+            let item = match chunk {
+                Ok(bytes) => {
+                    *received += bytes.len() as u64;
+                    match limit {
+                        Some(limit) if *received > limit => Err(DownloadError::ContentTooLarge {
+                            limit,
+                            actual: *received,
+                        }),
+                        _ => Ok(bytes),
+                    }
                 }
-            }
+                Err(e) => Err(DownloadError::RequestFailed(e.to_string())),
+            };
+            futures::future::ready(Some(item))
+        });
+
+        Ok((meta, body))
+    }
+
+    /// Peek a resource's size via `HEAD`, without downloading the body
+    ///
+    /// Used by [`super::runner::download_and_store`] to decide between the
+    /// buffered and streaming upload paths before committing to either one.
+    /// Any failure - network error, non-success status, missing
+    /// `Content-Length` - is treated as "unknown size" rather than an error,
+    /// since plenty of servers don't support `HEAD` well and the caller
+    /// falls back to the safe (buffered) default in that case.
+    pub async fn peek_content_length(&self, url: &str, headers: &[(String, String)]) -> Option<u64> {
+        let mut request = self.client.head(url);
+        for (name, value) in headers {
+            request = request.header(name, value);
         }
+        let response = request.send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.content_length()
     }
 
-    /// Download once (no retry)
-    async fn download_once(
+    /// Send the GET request (with `Range`/`If-Range` headers when `resume`
+    /// is set), check the status, and extract the response metadata shared
+    /// by [`download_once`](Self::download_once) and
+    /// [`download_stream`](Self::download_stream). Enforces
+    /// `max_content_length` against `Content-Length` up front, before any
+    /// body bytes are read.
+    async fn send(
         &self,
         url: &str,
         headers: &[(String, String)],
-    ) -> Result<Bytes> {
-        debug!(url, "Starting download");
+        resume: Option<&ResumeFrom>,
+    ) -> Result<(Response, DownloadMeta)> {
+        debug!(url, resuming_from = resume.map(|r| r.received_bytes), "Starting download");
 
         let mut request = self.client.get(url);
 
@@ -127,6 +330,12 @@ impl HttpClient {
             request = request.header(name, value);
         }
 
+        if let Some(resume) = resume {
+            request = request
+                .header(reqwest::header::RANGE, format!("bytes={}-", resume.received_bytes))
+                .header(reqwest::header::IF_RANGE, resume.validator.clone());
+        }
+
         let response = request
             .send()
             .await
@@ -150,15 +359,45 @@ impl HttpClient {
             )));
         }
 
-        // Read response body
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| DownloadError::RequestFailed(format!("Failed to read body: {}", e)))?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let content_length = response.content_length();
+        let accept_ranges = response
+            .headers()
+            .get(reqwest::header::ACCEPT_RANGES)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let last_modified = response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
 
-        debug!(url, size = bytes.len(), "Download completed");
+        if let (Some(limit), Some(actual)) = (self.config.max_content_length, content_length) {
+            if actual > limit {
+                return Err(DownloadError::ContentTooLarge { limit, actual });
+            }
+        }
 
-        Ok(bytes)
+        Ok((
+            response,
+            DownloadMeta {
+                status: status.as_u16(),
+                content_type,
+                content_length,
+                accept_ranges,
+                etag,
+                last_modified,
+            },
+        ))
     }
 }
 
@@ -171,7 +410,6 @@ mod tests {
         let config = HttpConfig::default();
         assert_eq!(config.connect_timeout, Duration::from_secs(10));
         assert_eq!(config.request_timeout, Duration::from_secs(60));
-        assert_eq!(config.max_retries, 3);
         assert_eq!(config.user_agent, "FetchBox/0.1.0");
     }
 }
@@ -0,0 +1,158 @@
+//! Per-job structured log capture
+//!
+//! [`super::runner::process_task`] already emits ordinary `tracing`
+//! `info!`/`warn!` events while a task runs, but those only ever reached
+//! stdout - nothing let an API client ask for a given job's logs.
+//! [`with_job_scope`] binds a task-local job id for the lifetime of a
+//! task's processing future; [`JobLogLayer`], layered onto the global
+//! subscriber by [`init_tracing`], reads that task-local on every event
+//! emitted inside the scope and forwards a [`LogRecord`] to
+//! [`spawn_log_writer`] rather than writing to [`FjallStore`] directly, so
+//! handler code keeps using ordinary macros with no explicit wiring and a
+//! burst of logging can never block the worker task emitting it.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use crate::ledger::{FjallStore, LogRecord};
+
+tokio::task_local! {
+    static CURRENT_JOB_ID: String;
+}
+
+/// Bind `job_id` as the task-local target for every `tracing` event emitted
+/// inside `fut`, so [`JobLogLayer`] can attribute them without callers
+/// threading a job id through every `info!`/`warn!` call
+pub async fn with_job_scope<F: Future>(job_id: String, fut: F) -> F::Output {
+    CURRENT_JOB_ID.scope(job_id, fut).await
+}
+
+/// Install the global `tracing` subscriber (console output plus
+/// [`JobLogLayer`]) and spawn its batched store writer. Call once, after
+/// opening `store` - see `crate::api::server::run`/`crate::worker::pool::run`.
+pub fn init_tracing(store: FjallStore) {
+    let (layer, writer) = JobLogLayer::new(store);
+    tokio::spawn(writer);
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(layer)
+        .init();
+}
+
+/// Batch size [`spawn_log_writer`] flushes at, whichever comes first
+/// against [`FLUSH_INTERVAL`]
+const BATCH_SIZE: usize = 100;
+
+/// Max delay before a partially filled batch is flushed anyway, so a
+/// lightly-logged job's output still shows up in a reasonable time
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+struct PendingLog {
+    job_id: String,
+    record: LogRecord,
+}
+
+/// [`tracing_subscriber::Layer`] that appends every event emitted inside a
+/// [`with_job_scope`] future to that job's log stream
+pub struct JobLogLayer {
+    tx: mpsc::UnboundedSender<PendingLog>,
+}
+
+impl JobLogLayer {
+    /// Create a layer plus the background writer future that feeds it;
+    /// callers must spawn the writer themselves (see [`init_tracing`])
+    fn new(store: FjallStore) -> (Self, impl Future<Output = ()>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, spawn_log_writer(store, rx))
+    }
+}
+
+impl<S: Subscriber> Layer<S> for JobLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let Ok(job_id) = CURRENT_JOB_ID.try_with(|id| id.clone()) else {
+            // Not inside a `with_job_scope` future (e.g. startup/shutdown
+            // logging) - nothing to attribute this event to.
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let record = LogRecord {
+            timestamp: chrono::Utc::now(),
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message.unwrap_or_default(),
+            fields: visitor.fields,
+        };
+
+        // Only fails if the writer task has already shut down (process
+        // exiting); nothing useful to do about that here.
+        let _ = self.tx.send(PendingLog { job_id, record });
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.fields.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+/// Drain `rx`, batching up to [`BATCH_SIZE`] records or [`FLUSH_INTERVAL`]
+/// (whichever comes first) before writing them into `store`'s `logs`
+/// partition with a single [`FjallStore::append_log_batch`] call
+async fn spawn_log_writer(store: FjallStore, mut rx: mpsc::UnboundedReceiver<PendingLog>) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            received = rx.recv() => {
+                match received {
+                    Some(pending) => {
+                        batch.push((pending.job_id, pending.record));
+                        if batch.len() >= BATCH_SIZE {
+                            flush_batch(&store, &mut batch);
+                        }
+                    }
+                    None => {
+                        flush_batch(&store, &mut batch);
+                        return;
+                    }
+                }
+            }
+            _ = flush.tick() => {
+                flush_batch(&store, &mut batch);
+            }
+        }
+    }
+}
+
+fn flush_batch(store: &FjallStore, batch: &mut Vec<(String, LogRecord)>) {
+    if batch.is_empty() {
+        return;
+    }
+    if let Err(e) = store.append_log_batch(batch) {
+        tracing::warn!(error = %e, count = batch.len(), "Failed to flush job log batch");
+    }
+    batch.clear();
+}
@@ -0,0 +1,211 @@
+//! Run manifest - a machine-readable record of what was fetched
+//!
+//! [`record_resource`] is called once per resource, right after its bytes
+//! land in storage (see [`super::runner::download_and_store`]). It read-
+//! modifies-writes the JSON document at the task's `manifest_key` so that,
+//! for a multi-resource job, every resource's [`ManifestRecord`] ends up
+//! keyed by `resource_id` in the same [`RunManifest`] - mirroring how a CI
+//! system reserves an artifacts directory and records job results alongside
+//! the produced bytes.
+//!
+//! There is no lock, in-process or cross-process, around the
+//! read-modify-write, so two resources of the same job finishing at the same
+//! instant can race and one update can be lost. That's an acceptable
+//! tradeoff here: the manifest is a convenience record for consumers, not
+//! the source of truth (the ledger's [`crate::api::models::JobSnapshot`] is).
+//! A record lost that way is not restored by later writes, which each add
+//! only their own resource's record.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::storage::StorageClient;
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+
+    #[error("failed to serialize run manifest: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ManifestError>;
+
+/// Per-resource outcome recorded into the job's [`RunManifest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestRecord {
+    pub resource_id: String,
+    pub storage_bucket: String,
+    pub storage_key: String,
+    pub size_bytes: u64,
+    pub checksum_sha256: String,
+    pub http_status: u16,
+    pub content_type: Option<String>,
+    /// Media type sniffed from the body's magic bytes (see
+    /// [`crate::worker::validate::sniff_mime`]) - distinct from
+    /// `content_type`, which is only what the server's `Content-Type`
+    /// header claimed
+    pub detected_mime_type: Option<String>,
+    pub proxy_pool: String,
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Job-level run manifest, keyed by `resource_id` so repeated writes from
+/// different resources of the same job merge rather than clobber
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub job_id: String,
+    #[serde(default)]
+    pub resources: BTreeMap<String, ManifestRecord>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RunManifest {
+    fn new(job_id: &str) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            resources: BTreeMap::new(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Strip a `s3://bucket/` (or any `scheme://host/`) prefix off `manifest_key`
+/// so it can be used as a plain [`StorageClient`] key - the client is already
+/// bound to a single bucket, so the scheme/bucket portion carried in
+/// `manifest_key` (see `crate::api::services::ingest_job`) is redundant here.
+fn storage_key(manifest_key: &str) -> &str {
+    match manifest_key.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or(rest, |(_, path)| path),
+        None => manifest_key,
+    }
+}
+
+/// Merge `record` into the run manifest stored at `manifest_key`, creating it
+/// if this is the job's first resource to complete
+pub async fn record_resource(
+    storage: &StorageClient,
+    manifest_key: &str,
+    job_id: &str,
+    record: ManifestRecord,
+) -> Result<()> {
+    let key = storage_key(manifest_key);
+
+    let mut manifest = match storage.download(key).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!(
+                manifest_key,
+                error = %e,
+                "Existing run manifest unreadable, starting a fresh one"
+            );
+            RunManifest::new(job_id)
+        }),
+        Err(_) => RunManifest::new(job_id),
+    };
+
+    manifest.resources.insert(record.resource_id.clone(), record);
+    manifest.updated_at = Utc::now();
+
+    let bytes = serde_json::to_vec_pretty(&manifest)?;
+    storage.upload(key, bytes).await?;
+
+    Ok(())
+}
+
+/// Look up one resource's [`ManifestRecord`] out of the run manifest at
+/// `manifest_key`, for [`crate::api::services::get_resource_artifact`]
+///
+/// Returns `Ok(None)` if the job has no run manifest yet (no resource has
+/// completed) or the manifest has no entry for `resource_id` - both read as
+/// "not found yet" to the caller, not an error.
+pub async fn get_resource_record(
+    storage: &StorageClient,
+    manifest_key: &str,
+    resource_id: &str,
+) -> Result<Option<ManifestRecord>> {
+    let key = storage_key(manifest_key);
+
+    let manifest: RunManifest = match storage.download(key).await {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(_) => return Ok(None),
+    };
+
+    Ok(manifest.resources.get(resource_id).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(resource_id: &str) -> ManifestRecord {
+        let now = Utc::now();
+        ManifestRecord {
+            resource_id: resource_id.to_string(),
+            storage_bucket: "fetchbox-local".to_string(),
+            storage_key: format!("resources/gallery/job-1/{resource_id}"),
+            size_bytes: 1024,
+            checksum_sha256: "deadbeef".to_string(),
+            http_status: 200,
+            content_type: Some("image/jpeg".to_string()),
+            detected_mime_type: Some("image/jpeg".to_string()),
+            proxy_pool: "direct".to_string(),
+            attempt: 1,
+            started_at: now,
+            completed_at: now,
+        }
+    }
+
+    #[test]
+    fn test_storage_key_strips_scheme_and_bucket() {
+        assert_eq!(
+            storage_key("s3://fetchbox-default/manifests/job-1.json"),
+            "manifests/job-1.json"
+        );
+    }
+
+    #[test]
+    fn test_storage_key_passthrough_without_scheme() {
+        assert_eq!(storage_key("manifests/job-1.json"), "manifests/job-1.json");
+    }
+
+    #[tokio::test]
+    async fn test_record_resource_creates_manifest_on_first_write() {
+        let storage = StorageClient::in_memory();
+        let manifest_key = "s3://fetchbox-local/manifests/job-1.json";
+
+        record_resource(&storage, manifest_key, "job-1", record("res-1"))
+            .await
+            .unwrap();
+
+        let bytes = storage.download("manifests/job-1.json").await.unwrap();
+        let manifest: RunManifest = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(manifest.job_id, "job-1");
+        assert_eq!(manifest.resources.len(), 1);
+        assert!(manifest.resources.contains_key("res-1"));
+    }
+
+    #[tokio::test]
+    async fn test_record_resource_merges_across_writes() {
+        let storage = StorageClient::in_memory();
+        let manifest_key = "s3://fetchbox-local/manifests/job-2.json";
+
+        record_resource(&storage, manifest_key, "job-2", record("res-1"))
+            .await
+            .unwrap();
+        record_resource(&storage, manifest_key, "job-2", record("res-2"))
+            .await
+            .unwrap();
+
+        let bytes = storage.download("manifests/job-2.json").await.unwrap();
+        let manifest: RunManifest = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(manifest.resources.len(), 2);
+        assert!(manifest.resources.contains_key("res-1"));
+        assert!(manifest.resources.contains_key("res-2"));
+    }
+}
@@ -1,20 +1,63 @@
 //! Download worker service
 //!
-//! Tower-based worker that receives tasks from mpsc channels,
-//! downloads resources, uploads to storage, and emits status/log updates.
+//! Workers receive tasks from the [`crate::queue::TaskBroker`]'s per-worker
+//! `mpsc` channels, download resources, upload them to storage, and fold the
+//! outcome into the job's ledger snapshot. See [`spawn_pool`].
 
+pub mod host_limit;
 pub mod http;
+pub mod job_log;
+pub mod manifest;
+pub mod notify;
+pub mod pool;
+pub mod proxy;
+pub mod retry;
 pub mod runner;
+pub mod status_stream;
+pub mod task_state;
+pub mod validate;
 
-// TODO: Implement Tower Service-based worker (Phase 4)
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, RwLock};
+use tracing::{info, warn};
+
+use crate::handlers::HandlerRegistry;
+use crate::ledger::FjallStore;
+use crate::observability::Metrics;
+use crate::proto::DownloadTask;
+use crate::queue::{FjallQueue, TaskEnvelope};
+use crate::storage::StorageClient;
+use host_limit::HostLimiter;
+use notify::NotificationDispatcher;
+use proxy::ProxyRotator;
+use status_stream::StatusBroadcaster;
+use validate::ContentValidator;
 
 type AnyError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
-/// Worker configuration
+/// Worker configuration shared by the embedded pool ([`spawn_pool`]) and the
+/// standalone `fetchbox worker` binary ([`pool::run`])
 #[derive(Debug, Clone)]
 pub struct WorkerConfig {
     pub max_inflight_tasks: usize,
     pub poll_interval_ms: u64,
+    /// Pending tasks pulled from `FjallQueue` per poll (standalone pool only)
+    pub batch_size: usize,
+    pub max_task_attempts: u32,
+    /// Maximum downloads in flight against any single host at once, across
+    /// the whole pool (see [`host_limit::HostLimiter`])
+    pub max_downloads_per_host: usize,
+    /// Responses at or below this size are buffered fully in memory;
+    /// larger ones stream directly into a multipart upload (see
+    /// [`runner::StreamingPolicy`])
+    pub stream_threshold_bytes: u64,
+    /// Part size used for the streaming multipart upload path
+    pub upload_part_size_bytes: usize,
+    /// Hard cap on a single response body; `None` leaves it unbounded
+    pub max_content_length_bytes: Option<u64>,
 }
 
 impl Default for WorkerConfig {
@@ -22,9 +65,181 @@ impl Default for WorkerConfig {
         Self {
             max_inflight_tasks: 32,
             poll_interval_ms: 100,
+            batch_size: 50,
+            max_task_attempts: 3,
+            max_downloads_per_host: 4,
+            stream_threshold_bytes: 8 * 1024 * 1024,
+            upload_part_size_bytes: 8 * 1024 * 1024,
+            max_content_length_bytes: None,
         }
     }
 }
 
-// Old Iggy-based worker implementation removed
-// Will be replaced with Tower Service-based worker in Phase 4
+/// Spawn one background task per worker receiver, each draining its
+/// [`TaskEnvelope`] channel and processing tasks via
+/// [`runner::process_task`].
+///
+/// A [`retry::RetryScheduler`] is kept per worker for tasks that come back
+/// as [`runner::TaskOutcome::Retry`]: fresh envelopes off the channel are
+/// always tried first (via a non-blocking `try_recv`), and only once none
+/// are immediately available does the worker peek its scheduler and
+/// re-dispatch whatever backoff windows have elapsed, sleeping at most
+/// until the nearest `wake_at` the rest of the time. This keeps one slow
+/// host's backoff from blocking the channel the way the old inline
+/// `tokio::time::sleep` in [`super::http::HttpClient::download`] did.
+///
+/// `ack_tx` reports a task's Fjall sequence back to
+/// [`crate::queue::TaskBroker`] once it reaches a terminal outcome
+/// (succeeded or dead-lettered) - `Retry` isn't terminal, so the broker
+/// still considers the task dispatched and its visibility-timeout reaper
+/// stays the backstop if this worker dies before a retry finishes.
+///
+/// While a task is actively being processed, its dispatch lease is renewed
+/// every `lease_heartbeat_interval` via [`FjallQueue::renew_dispatch`] - the
+/// visibility timeout is a crash-detection window, not a download-duration
+/// budget, and without this a multi-gigabyte download that legitimately
+/// outruns it would get redispatched to a second worker while the first is
+/// still streaming it to storage (see [`runner::process_task`]).
+///
+/// Returns the join handles so callers can await shutdown; dropping them is
+/// fine too since workers keep running detached on the runtime.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_pool(
+    receivers: Vec<mpsc::Receiver<TaskEnvelope>>,
+    storage: Arc<StorageClient>,
+    ledger: Arc<FjallStore>,
+    queue: Arc<RwLock<FjallQueue>>,
+    max_task_attempts: u32,
+    proxy_rotator: Option<Arc<ProxyRotator>>,
+    validator: Arc<ContentValidator>,
+    notifier: Arc<NotificationDispatcher>,
+    metrics: Arc<Metrics>,
+    host_limiter: Arc<HostLimiter>,
+    streaming_policy: runner::StreamingPolicy,
+    status_broadcaster: Arc<StatusBroadcaster>,
+    registry: Arc<HandlerRegistry>,
+    ack_tx: mpsc::Sender<u64>,
+    lease_heartbeat_interval: Duration,
+) -> Vec<tokio::task::JoinHandle<()>> {
+    receivers
+        .into_iter()
+        .enumerate()
+        .map(|(worker_id, mut rx)| {
+            let storage = storage.clone();
+            let ledger = ledger.clone();
+            let queue = queue.clone();
+            let proxy_rotator = proxy_rotator.clone();
+            let validator = validator.clone();
+            let notifier = notifier.clone();
+            let metrics = metrics.clone();
+            let host_limiter = host_limiter.clone();
+            let status_broadcaster = status_broadcaster.clone();
+            let registry = registry.clone();
+            let ack_tx = ack_tx.clone();
+            tokio::spawn(async move {
+                info!(worker_id, "Download worker started");
+
+                let mut scheduler = retry::RetryScheduler::new();
+                let mut ready: VecDeque<(u64, DownloadTask)> = VecDeque::new();
+
+                'work: loop {
+                    // A fresh envelope always jumps ahead of parked
+                    // retries; only once none is immediately available do
+                    // we fall back to whatever's ready in `scheduler`.
+                    let fresh = match rx.try_recv() {
+                        Ok(envelope) => Some((envelope.seq, envelope.task)),
+                        Err(mpsc::error::TryRecvError::Disconnected) => break 'work,
+                        Err(mpsc::error::TryRecvError::Empty) => None,
+                    };
+
+                    let (seq, task) = if let Some(entry) = fresh {
+                        entry
+                    } else if let Some(entry) = ready.pop_front() {
+                        entry
+                    } else {
+                        ready.extend(scheduler.drain_ready());
+                        if let Some(entry) = ready.pop_front() {
+                            entry
+                        } else if let Some(wake) = scheduler.next_wake() {
+                            // Nothing ready - block on whichever comes
+                            // first: a fresh envelope, or the nearest
+                            // parked retry's backoff window elapsing.
+                            tokio::select! {
+                                biased;
+                                envelope = rx.recv() => match envelope {
+                                    Some(e) => (e.seq, e.task),
+                                    None => break 'work,
+                                },
+                                _ = tokio::time::sleep_until(tokio::time::Instant::from_std(wake)) => continue 'work,
+                            }
+                        } else {
+                            match rx.recv().await {
+                                Some(e) => (e.seq, e.task),
+                                None => break 'work,
+                            }
+                        }
+                    };
+
+                    let job_id = task.job_id.clone();
+                    let process_fut = job_log::with_job_scope(
+                        job_id,
+                        runner::process_task(
+                            seq,
+                            task,
+                            storage.clone(),
+                            ledger.clone(),
+                            queue.clone(),
+                            max_task_attempts,
+                            proxy_rotator.clone(),
+                            validator.clone(),
+                            notifier.clone(),
+                            metrics.clone(),
+                            host_limiter.clone(),
+                            streaming_policy,
+                            status_broadcaster.clone(),
+                            registry.clone(),
+                        ),
+                    );
+                    tokio::pin!(process_fut);
+
+                    // Renew this task's dispatch lease on a timer for as
+                    // long as `process_fut` is still running, so the
+                    // broker's reaper doesn't mistake an in-progress
+                    // download for an abandoned one (see `spawn_pool`'s
+                    // doc comment).
+                    let mut heartbeat = tokio::time::interval(lease_heartbeat_interval);
+                    heartbeat.tick().await; // first tick fires immediately
+                    let result = loop {
+                        tokio::select! {
+                            biased;
+                            res = &mut process_fut => break res,
+                            _ = heartbeat.tick() => {
+                                if let Err(e) = queue.write().await.renew_dispatch(seq) {
+                                    warn!(worker_id, seq, error = %e, "Failed to renew dispatch lease");
+                                }
+                            }
+                        }
+                    };
+
+                    match result {
+                        Ok(runner::TaskOutcome::Retry { task, attempt }) => {
+                            scheduler.schedule(seq, task, attempt);
+                        }
+                        Ok(runner::TaskOutcome::Succeeded | runner::TaskOutcome::DeadLettered) => {
+                            if ack_tx.send(seq).await.is_err() {
+                                warn!(worker_id, seq, "Ack channel closed, broker may be shutting down");
+                            }
+                        }
+                        Err(e) => {
+                            warn!(worker_id, seq, error = %e, "Task processing failed");
+                        }
+                    }
+                }
+                info!(worker_id, "Download worker channel closed, shutting down");
+            })
+        })
+        .collect()
+}
+
+// Old Iggy-based worker implementation removed in favor of the in-process
+// pool above; see [`crate::messaging`] for the rationale.
@@ -0,0 +1,383 @@
+//! Completion notifications on terminal task transitions
+//!
+//! [`NotificationDispatcher`] fires a best-effort, fire-and-forget
+//! notification whenever [`super::runner::process_task`] drives a task to
+//! `Succeeded`, `Failed`, or `DeadLettered` (see [`super::task_state`]).
+//! Each `job_type` routes independently, per its
+//! [`crate::config::HandlerConfig::notify`] section:
+//!
+//! ```toml
+//! [handlers.gallery.notify.webhook]
+//! url = "https://hooks.example.com/fetchbox"
+//!
+//! [handlers.gallery.notify.email]
+//! smtp_host = "smtp.example.com"
+//! from = "fetchbox@example.com"
+//! to = ["oncall@example.com"]
+//! ```
+//!
+//! [`WebhookNotifier`] posts on every terminal state; [`EmailNotifier`] only
+//! fires for `Failed`/`DeadLettered`, since it's meant for alerting rather
+//! than routine completion tracking. Delivery never blocks task completion:
+//! [`NotificationDispatcher::dispatch`] spawns each notifier on its own
+//! task and returns immediately.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::HandlerConfig;
+use crate::worker::task_state::TaskState;
+
+/// A single terminal-state event, ready to hand to every notifier routed
+/// for `job_type`
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationEvent {
+    pub job_id: String,
+    pub resource_id: String,
+    pub tenant: String,
+    pub state: String,
+    pub storage_key: Option<String>,
+    pub error: Option<String>,
+}
+
+impl NotificationEvent {
+    pub fn new(
+        job_id: impl Into<String>,
+        resource_id: impl Into<String>,
+        tenant: impl Into<String>,
+        state: TaskState,
+        storage_key: Option<String>,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            job_id: job_id.into(),
+            resource_id: resource_id.into(),
+            tenant: tenant.into(),
+            state: format!("{state:?}"),
+            storage_key,
+            error,
+        }
+    }
+}
+
+/// A single notification sink; implementations must be best-effort - a
+/// failed delivery is logged, never propagated, since a notifier going down
+/// must not hold up task completion
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, event: &NotificationEvent);
+}
+
+/// POSTs `event` as JSON to a configured URL, retrying on failure
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    max_retries: u32,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: &crate::config::WebhookNotifyConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            url: config.url.clone(),
+            max_retries: config.max_retries,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &NotificationEvent) {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.client.post(&self.url).json(event).send().await {
+                Ok(response) if response.status().is_success() => return,
+                Ok(response) => {
+                    warn!(
+                        url = self.url,
+                        status = response.status().as_u16(),
+                        attempt,
+                        "Webhook notification rejected"
+                    );
+                }
+                Err(e) => {
+                    warn!(url = self.url, attempt, error = %e, "Webhook notification failed");
+                }
+            }
+
+            if attempt >= self.max_retries {
+                warn!(url = self.url, attempts = attempt, "Webhook notification exhausted retries, giving up");
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt - 1))).await;
+        }
+    }
+}
+
+/// Emails a failure/dead-letter alert via SMTP
+///
+/// Routine `Succeeded` notifications never reach here - see
+/// [`NotificationDispatcher::dispatch`], which only invokes email notifiers
+/// for `Failed`/`DeadLettered` events.
+pub struct EmailNotifier {
+    config: crate::config::EmailNotifyConfig,
+}
+
+impl EmailNotifier {
+    pub fn new(config: crate::config::EmailNotifyConfig) -> Self {
+        Self { config }
+    }
+
+    fn render_body(event: &NotificationEvent) -> String {
+        format!(
+            "FetchBox resource {} in job {} reached state {}\n\nstorage_key: {}\nerror: {}",
+            event.resource_id,
+            event.job_id,
+            event.state,
+            event.storage_key.as_deref().unwrap_or("-"),
+            event.error.as_deref().unwrap_or("-"),
+        )
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, event: &NotificationEvent) {
+        use lettre::message::Mailbox;
+        use lettre::transport::smtp::AsyncSmtpTransport;
+        use lettre::{AsyncTransport, Message, Tokio1Executor};
+
+        let subject = format!("FetchBox alert: {} {}", event.job_id, event.state);
+        let body = Self::render_body(event);
+
+        let from: Mailbox = match self.config.from.parse() {
+            Ok(mailbox) => mailbox,
+            Err(e) => {
+                warn!(error = %e, "Invalid notify.email.from address, skipping email alert");
+                return;
+            }
+        };
+
+        for recipient in &self.config.to {
+            let to: Mailbox = match recipient.parse() {
+                Ok(mailbox) => mailbox,
+                Err(e) => {
+                    warn!(recipient, error = %e, "Invalid notify.email.to address, skipping");
+                    continue;
+                }
+            };
+
+            let message = match Message::builder()
+                .from(from.clone())
+                .to(to)
+                .subject(&subject)
+                .body(body.clone())
+            {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!(error = %e, "Failed to build email alert");
+                    continue;
+                }
+            };
+
+            let mailer = match AsyncSmtpTransport::<Tokio1Executor>::relay(&self.config.smtp_host)
+            {
+                Ok(builder) => builder.port(self.config.smtp_port).build(),
+                Err(e) => {
+                    warn!(host = self.config.smtp_host, error = %e, "Failed to build SMTP transport");
+                    continue;
+                }
+            };
+
+            if let Err(e) = mailer.send(message).await {
+                warn!(recipient, error = %e, "Failed to send email alert");
+            }
+        }
+    }
+}
+
+/// Routes terminal-state events to the notifiers configured for each
+/// `job_type`, built once at startup from `config.handlers`
+pub struct NotificationDispatcher {
+    notifiers: HashMap<String, Vec<(Arc<dyn Notifier>, bool)>>,
+}
+
+impl NotificationDispatcher {
+    /// `bool` alongside each notifier marks whether it should fire for
+    /// every terminal state (`true`, webhooks) or only failures (`false`,
+    /// email)
+    pub fn new(handlers: &HashMap<String, HandlerConfig>) -> Self {
+        let mut notifiers: HashMap<String, Vec<(Arc<dyn Notifier>, bool)>> = HashMap::new();
+
+        for (job_type, config) in handlers {
+            let Some(notify) = &config.notify else {
+                continue;
+            };
+
+            let mut routes: Vec<(Arc<dyn Notifier>, bool)> = Vec::new();
+            if let Some(webhook) = &notify.webhook {
+                routes.push((Arc::new(WebhookNotifier::new(webhook)), true));
+            }
+            if let Some(email) = &notify.email {
+                routes.push((Arc::new(EmailNotifier::new(email.clone())), false));
+            }
+
+            if !routes.is_empty() {
+                notifiers.insert(job_type.clone(), routes);
+            }
+        }
+
+        Self { notifiers }
+    }
+
+    /// Fire every notifier routed for `job_type` without waiting for them -
+    /// each runs on its own spawned task so a slow/unreachable endpoint
+    /// never delays task completion. Notifiers marked failure-only (email)
+    /// are skipped unless `event.state` is `Failed`/`DeadLettered`.
+    pub fn dispatch(self: &Arc<Self>, job_type: &str, event: NotificationEvent) {
+        let Some(routes) = self.notifiers.get(job_type) else {
+            return;
+        };
+
+        let is_failure = matches!(event.state.as_str(), "Failed" | "DeadLettered");
+        let event = Arc::new(event);
+
+        for (notifier, fires_on_success) in routes {
+            if !fires_on_success && !is_failure {
+                continue;
+            }
+            let notifier = notifier.clone();
+            let event = event.clone();
+            tokio::spawn(async move { notifier.notify(&event).await });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NotifyConfig, WebhookNotifyConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn handler_config(notify: NotifyConfig) -> HandlerConfig {
+        HandlerConfig {
+            handler: "fetchbox::handlers::DefaultHandler".to_string(),
+            storage_bucket: None,
+            default_headers: Default::default(),
+            options: serde_json::Value::Null,
+            proxy_pool: None,
+            notify: Some(notify),
+            wasm_module: None,
+        }
+    }
+
+    struct CountingNotifier(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Notifier for CountingNotifier {
+        async fn notify(&self, _event: &NotificationEvent) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_dispatcher_skips_job_types_without_notify_config() {
+        let dispatcher = NotificationDispatcher::new(&HashMap::new());
+        assert!(dispatcher.notifiers.is_empty());
+    }
+
+    #[test]
+    fn test_dispatcher_builds_webhook_route() {
+        let mut handlers = HashMap::new();
+        handlers.insert(
+            "gallery".to_string(),
+            handler_config(NotifyConfig {
+                webhook: Some(WebhookNotifyConfig {
+                    url: "https://example.com/hook".to_string(),
+                    max_retries: 3,
+                    timeout_secs: 10,
+                }),
+                email: None,
+            }),
+        );
+
+        let dispatcher = NotificationDispatcher::new(&handlers);
+        assert_eq!(dispatcher.notifiers.get("gallery").unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_email_route_only_fires_on_failure() {
+        let succeeded = NotificationEvent::new(
+            "job-1",
+            "res-1",
+            "acme",
+            TaskState::Succeeded,
+            Some("key".to_string()),
+            None,
+        );
+        assert_eq!(succeeded.state, "Succeeded");
+
+        let failed = NotificationEvent::new(
+            "job-1",
+            "res-1",
+            "acme",
+            TaskState::Failed,
+            None,
+            Some("boom".to_string()),
+        );
+        assert_eq!(failed.state, "Failed");
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let dispatcher = Arc::new(NotificationDispatcher {
+            notifiers: HashMap::from([(
+                "gallery".to_string(),
+                vec![(
+                    Arc::new(CountingNotifier(calls.clone())) as Arc<dyn Notifier>,
+                    false,
+                )],
+            )]),
+        });
+        dispatcher.dispatch("gallery", succeeded);
+        dispatcher.dispatch("gallery", failed);
+
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_fires_spawned_notifiers() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let dispatcher = Arc::new(NotificationDispatcher {
+            notifiers: HashMap::from([(
+                "gallery".to_string(),
+                vec![(
+                    Arc::new(CountingNotifier(calls.clone())) as Arc<dyn Notifier>,
+                    true,
+                )],
+            )]),
+        });
+
+        let event = NotificationEvent::new(
+            "job-1", "res-1", "acme", TaskState::Succeeded, None, None,
+        );
+        dispatcher.dispatch("gallery", event);
+
+        // Give the spawned task a chance to run.
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
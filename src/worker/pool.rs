@@ -0,0 +1,338 @@
+//! Standalone worker pool for the `fetchbox worker` binary
+//!
+//! Unlike [`super::spawn_pool`], which is fed by `TaskBroker`'s in-process
+//! `mpsc` channels inside the `fetchbox api` process, this pulls batches
+//! directly out of [`FjallQueue`] via `pull_pending`, so it can run as its
+//! own OS process. Pulled tasks are pushed onto an internal bounded `mpsc`
+//! channel sized to the configured concurrency, which that many worker
+//! futures drain - the push/pull split gives natural backpressure, since
+//! the puller blocks once every worker slot is full. `FjallQueue`'s
+//! persisted commit offset only advances past a task once
+//! [`super::runner::process_task`] has driven it to a terminal state, so a
+//! restart never re-pulls completed work.
+//!
+//! Fjall holds an exclusive lock per path, so this must point at a ledger
+//! that isn't also open in a `fetchbox api` process with its own embedded
+//! pool (set that deployment's `server.worker.num_workers` to 0 instead).
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, RwLock, mpsc};
+use tracing::{info, warn};
+
+use super::host_limit::HostLimiter;
+use super::notify::NotificationDispatcher;
+use super::proxy::ProxyRotator;
+use super::status_stream::StatusBroadcaster;
+use super::validate::ContentValidator;
+use super::{WorkerConfig, retry, runner};
+use crate::config::Config;
+use crate::handlers::HandlerRegistry;
+use crate::ledger::FjallStore;
+use crate::observability::Metrics;
+use crate::proto::DownloadTask;
+use crate::queue::FjallQueue;
+use crate::storage::StorageClient;
+
+type AnyError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Load config, open the Fjall ledger/queue at `ledger_path`, and run the
+/// standalone worker pool until a ctrl_c/SIGTERM shutdown signal arrives,
+/// draining in-flight tasks before returning. Mirrors `crate::api::run`'s
+/// process wiring.
+pub async fn run(
+    ledger_path: String,
+    concurrency: Option<usize>,
+    batch_size: Option<usize>,
+) -> Result<(), AnyError> {
+    info!("Loading configuration");
+    let config = Config::load().map_err(|e| format!("Failed to load config: {}", e))?;
+
+    info!(path = %ledger_path, "Opening Fjall store");
+    let ledger = Arc::new(
+        FjallStore::open(&ledger_path).map_err(|e| format!("Failed to open Fjall store: {}", e))?,
+    );
+
+    // Installs console logging plus the per-job structured log layer (see
+    // `crate::worker::job_log`); mirrors `crate::api::server::run`.
+    super::job_log::init_tracing((*ledger).clone());
+
+    let queue_path = std::path::Path::new(&ledger_path)
+        .parent()
+        .unwrap()
+        .join("queue");
+    info!(path = ?queue_path, "Opening FjallQueue");
+    let queue = Arc::new(RwLock::new(
+        FjallQueue::open(&queue_path).map_err(|e| format!("Failed to open queue: {}", e))?,
+    ));
+
+    let storage = Arc::new(
+        StorageClient::from_config(&config.storage)
+            .map_err(|e| format!("Failed to initialize storage: {}", e))?,
+    );
+    let metrics = Arc::new(Metrics::new());
+    let proxy_rotator = Arc::new(ProxyRotator::new(config.proxy.clone()));
+    let validator = Arc::new(ContentValidator::new(&config.handlers));
+    let notifier = Arc::new(NotificationDispatcher::new(&config.handlers));
+    // No `fetchbox api` process is around to expose these over
+    // `GET /tasks/{seq}/events` in this standalone binary, but
+    // `runner::process_task` takes one unconditionally - see `spawn_pool`
+    // for the embedded-pool counterpart that does serve it.
+    let status_broadcaster = Arc::new(StatusBroadcaster::new());
+
+    // Initialize handler registry, then layer in any job types configured
+    // with a `wasm_module` on top of the compiled-in defaults - mirrors
+    // `crate::api::server::run`, since this standalone binary has no API
+    // process around to have built one already.
+    let mut registry = HandlerRegistry::with_defaults();
+    registry.register_wasm_handlers(&config.handlers);
+    let registry = Arc::new(registry);
+
+    let worker_config = WorkerConfig {
+        max_inflight_tasks: concurrency.unwrap_or(config.server.worker.num_workers),
+        batch_size: batch_size.unwrap_or(config.server.worker.batch_size),
+        max_task_attempts: config.server.worker.max_task_attempts,
+        max_downloads_per_host: config.server.worker.max_downloads_per_host,
+        stream_threshold_bytes: config.server.worker.stream_threshold.as_u64(),
+        upload_part_size_bytes: config.server.worker.upload_part_size.as_u64() as usize,
+        max_content_length_bytes: config.server.worker.max_content_length.map(|s| s.as_u64()),
+        ..WorkerConfig::default()
+    };
+    let host_limiter = Arc::new(HostLimiter::new(worker_config.max_downloads_per_host));
+
+    info!(
+        max_inflight_tasks = worker_config.max_inflight_tasks,
+        batch_size = worker_config.batch_size,
+        max_downloads_per_host = worker_config.max_downloads_per_host,
+        "FetchBox standalone worker pool starting"
+    );
+    run_pool(
+        queue,
+        storage,
+        ledger,
+        proxy_rotator,
+        validator,
+        notifier,
+        metrics,
+        host_limiter,
+        status_broadcaster,
+        registry,
+        worker_config,
+        shutdown_signal(),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut sigterm =
+            signal(SignalKind::terminate()).expect("failed to install signal handler");
+        sigterm.recv().await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received");
+}
+
+/// Run the standalone worker pool until `shutdown` resolves, draining
+/// in-flight tasks before returning.
+#[allow(clippy::too_many_arguments)]
+async fn run_pool(
+    queue: Arc<RwLock<FjallQueue>>,
+    storage: Arc<StorageClient>,
+    ledger: Arc<FjallStore>,
+    proxy_rotator: Arc<ProxyRotator>,
+    validator: Arc<ContentValidator>,
+    notifier: Arc<NotificationDispatcher>,
+    metrics: Arc<Metrics>,
+    host_limiter: Arc<HostLimiter>,
+    status_broadcaster: Arc<StatusBroadcaster>,
+    registry: Arc<HandlerRegistry>,
+    config: WorkerConfig,
+    shutdown: impl Future<Output = ()>,
+) {
+    let (tx, rx) = mpsc::channel::<(u64, DownloadTask)>(config.max_inflight_tasks);
+    let rx = Arc::new(Mutex::new(rx));
+
+    let streaming_policy = runner::StreamingPolicy {
+        stream_threshold_bytes: config.stream_threshold_bytes,
+        upload_part_size_bytes: config.upload_part_size_bytes,
+        max_content_length_bytes: config.max_content_length_bytes,
+    };
+
+    let worker_handles: Vec<_> = (0..config.max_inflight_tasks)
+        .map(|worker_id| {
+            let rx = rx.clone();
+            let storage = storage.clone();
+            let ledger = ledger.clone();
+            let queue = queue.clone();
+            let proxy_rotator = proxy_rotator.clone();
+            let validator = validator.clone();
+            let notifier = notifier.clone();
+            let metrics = metrics.clone();
+            let host_limiter = host_limiter.clone();
+            let status_broadcaster = status_broadcaster.clone();
+            let registry = registry.clone();
+            let max_task_attempts = config.max_task_attempts;
+
+            tokio::spawn(async move {
+                let mut scheduler = retry::RetryScheduler::new();
+                let mut ready: VecDeque<(u64, DownloadTask)> = VecDeque::new();
+
+                'work: loop {
+                    // A fresh task off the shared channel always jumps
+                    // ahead of this worker's own parked retries; only once
+                    // none is immediately available (or another worker
+                    // currently holds the channel lock) do we fall back to
+                    // whatever's ready in `scheduler`.
+                    let fresh = match rx.try_lock() {
+                        Ok(mut guard) => match guard.try_recv() {
+                            Ok(entry) => Some(entry),
+                            Err(mpsc::error::TryRecvError::Disconnected) => break 'work,
+                            Err(mpsc::error::TryRecvError::Empty) => None,
+                        },
+                        Err(_) => None,
+                    };
+
+                    let (seq, task) = if let Some(entry) = fresh {
+                        entry
+                    } else if let Some(entry) = ready.pop_front() {
+                        entry
+                    } else {
+                        ready.extend(scheduler.drain_ready());
+                        if let Some(entry) = ready.pop_front() {
+                            entry
+                        } else if let Some(wake) = scheduler.next_wake() {
+                            // Nothing ready - block on whichever comes
+                            // first: a fresh task, or the nearest parked
+                            // retry's backoff window elapsing.
+                            tokio::select! {
+                                biased;
+                                entry = async { rx.lock().await.recv().await } => match entry {
+                                    Some(e) => e,
+                                    None => break 'work,
+                                },
+                                _ = tokio::time::sleep_until(tokio::time::Instant::from_std(wake)) => continue 'work,
+                            }
+                        } else {
+                            match rx.lock().await.recv().await {
+                                Some(e) => e,
+                                None => break 'work,
+                            }
+                        }
+                    };
+
+                    let job_id = task.job_id.clone();
+                    match super::job_log::with_job_scope(
+                        job_id,
+                        runner::process_task(
+                            seq,
+                            task,
+                            storage.clone(),
+                            ledger.clone(),
+                            queue.clone(),
+                            max_task_attempts,
+                            None,
+                            validator.clone(),
+                            notifier.clone(),
+                            metrics.clone(),
+                            host_limiter.clone(),
+                            streaming_policy,
+                            status_broadcaster.clone(),
+                            registry.clone(),
+                        ),
+                    )
+                    .await
+                    {
+                        Ok(runner::TaskOutcome::Retry { task, attempt }) => {
+                            // Not terminal yet - parked in-memory, so the
+                            // commit offset must not advance past `seq`
+                            // until the retry itself resolves.
+                            scheduler.schedule(seq, task, attempt);
+                        }
+                        outcome => {
+                            if let Err(e) = &outcome {
+                                warn!(worker_id, seq, error = %e, "Task processing failed");
+                            }
+
+                            // The task reached a terminal state either way -
+                            // it either completed or was moved to the
+                            // dead-letter queue - so the commit offset can
+                            // safely skip past it.
+                            let queue = queue.read().await;
+                            if let Err(e) = queue.set_commit_offset(seq + 1) {
+                                warn!(worker_id, seq, error = %e, "Failed to persist commit offset");
+                            }
+                        }
+                    }
+                }
+                info!(worker_id, "Worker channel closed, shutting down");
+            })
+        })
+        .collect();
+
+    tokio::pin!(shutdown);
+    let poll_interval = std::time::Duration::from_millis(config.poll_interval_ms);
+
+    'pull: loop {
+        let cursor = match queue.read().await.commit_offset() {
+            Ok(offset) => offset,
+            Err(e) => {
+                warn!(error = %e, "Failed to read commit offset");
+                0
+            }
+        };
+
+        let batch = match queue.read().await.pull_pending(cursor, config.batch_size) {
+            Ok(batch) => batch,
+            Err(e) => {
+                warn!(error = %e, "Failed to pull pending tasks");
+                Vec::new()
+            }
+        };
+
+        if batch.is_empty() {
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {}
+                _ = &mut shutdown => break 'pull,
+            }
+            continue;
+        }
+
+        for entry in batch {
+            tokio::select! {
+                send_result = tx.send(entry) => {
+                    if send_result.is_err() {
+                        break 'pull;
+                    }
+                }
+                _ = &mut shutdown => break 'pull,
+            }
+        }
+    }
+
+    info!("Worker pool shutting down, draining in-flight tasks");
+    drop(tx);
+    for handle in worker_handles {
+        let _ = handle.await;
+    }
+}
@@ -0,0 +1,605 @@
+//! Proxy pool rotation for the download path
+//!
+//! A [`ProxyHint`] names a `primary_pool` plus an ordered list of
+//! `fallback_pools`. [`ProxyRotator`] resolves each pool name (recursively,
+//! via [`crate::config::ProxyGraph`], which also flattens that pool's own
+//! configured fallback chain) into its tiered list of endpoints and tries
+//! them in order - primary pool first, then each `fallback_pools` entry -
+//! until one serves the request or every pool is exhausted.
+//!
+//! Pools that rack up `eject_after_failures` consecutive failures are
+//! temporarily ejected from rotation so a dead proxy pool stops poisoning
+//! every download; they're re-admitted once `cooldown_secs` has elapsed
+//! since the ejection.
+//!
+//! Within a pool, [`ProxySelector`] picks which endpoint of a tier to try
+//! next: power-of-two-choices between two randomly sampled healthy
+//! endpoints, scored by EWMA latency times outstanding requests, so load
+//! spreads toward whichever endpoint is currently fastest and least busy
+//! rather than a flat round-robin. An endpoint that racks up a tier's own
+//! `max_retries` consecutive failures is circuit-broken for that tier's own
+//! `retry_backoff_ms` (see [`crate::config::ResolvedTier::retry_schedule`]) -
+//! excluded from selection, but without skipping the rest of its tier, which
+//! a transient blip on one endpoint shouldn't do. A fallback chain mixes
+//! pools with different retry policies, so [`ProxySelector::ordered_endpoints`]
+//! pairs each endpoint with the policy of the tier it came from rather than
+//! the root pool's. [`ProxyRotator::download`] only moves on to the next
+//! tier once every endpoint in the current one is circuit-broken.
+//!
+//! A pool configured with `allow_direct_fallback` gets a final synthetic
+//! tier of one [`ProxyEndpoint::direct`] endpoint (see [`ProxyGraph`]),
+//! which this module dials with no proxy configured at all rather than
+//! hard-failing the download once every proxy tier is exhausted.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+use tracing::warn;
+
+use super::http::{DownloadResponse, HttpClient, HttpConfig};
+use crate::config::{
+    ProxyConfig, ProxyDirectory, ProxyEndpoint, ProxyGraph, ResolvedProxyPool, RetrySchedule,
+};
+use crate::handlers::types::ProxyHint;
+
+/// EWMA smoothing factor applied to each newly observed endpoint latency
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Runtime health of a single proxy endpoint, keyed by URI in
+/// [`ProxySelector`]
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    /// Exponentially-weighted moving average of observed latency, in
+    /// milliseconds; `0.0` until the first completed request, which biases
+    /// selection toward trying endpoints that haven't been measured yet
+    ewma_latency_ms: f64,
+    /// Requests currently in flight against this endpoint
+    outstanding: u32,
+    consecutive_failures: u32,
+    /// Set once `consecutive_failures` crosses a pool's `max_retries`;
+    /// cleared on the next success
+    broken_until: Option<Instant>,
+}
+
+/// Health-aware endpoint selection within a [`ResolvedProxyPool`]'s tiers.
+///
+/// Tracks per-endpoint EWMA latency, outstanding-request count, and a
+/// circuit breaker, shared across every pool a [`ProxyRotator`] resolves
+/// (endpoints are keyed by URI, so the same proxy reused across pools
+/// accumulates one health record). See the module docs for the selection
+/// and tier-escalation rules.
+#[derive(Debug, Default)]
+pub struct ProxySelector {
+    health: Mutex<HashMap<String, EndpointHealth>>,
+    rng_state: AtomicU64,
+}
+
+impl ProxySelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cheap splitmix-style PRNG draw - see [`super::retry::backoff_for`]'s
+    /// comment: this tree has no `rand` dependency, and power-of-two-choices
+    /// only needs to decorrelate which two endpoints get compared, not be
+    /// cryptographically random.
+    fn next_rand(&self) -> u64 {
+        let mut x = self
+            .rng_state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            ^ (Instant::now().elapsed().as_nanos() as u64);
+        x ^= x >> 30;
+        x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x ^= x >> 27;
+        x
+    }
+
+    fn is_broken(&self, uri: &str) -> bool {
+        self.health
+            .lock()
+            .unwrap()
+            .get(uri)
+            .and_then(|h| h.broken_until)
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn score(&self, uri: &str) -> f64 {
+        let health = self.health.lock().unwrap();
+        health
+            .get(uri)
+            .map(|h| h.ewma_latency_ms * f64::from(h.outstanding + 1))
+            .unwrap_or(0.0)
+    }
+
+    /// Power-of-two-choices draw from `candidates`, removing and returning
+    /// the winner. A single candidate is returned directly.
+    fn pick<'a>(&self, candidates: &mut Vec<&'a ProxyEndpoint>) -> &'a ProxyEndpoint {
+        if candidates.len() == 1 {
+            return candidates.remove(0);
+        }
+        let i = (self.next_rand() as usize) % candidates.len();
+        let mut j = (self.next_rand() as usize) % candidates.len();
+        while j == i {
+            j = (self.next_rand() as usize) % candidates.len();
+        }
+        let winner = if self.score(&candidates[i].uri) <= self.score(&candidates[j].uri) {
+            i
+        } else {
+            j
+        };
+        candidates.remove(winner)
+    }
+
+    /// Flattens `resolved`'s tiers into a single try order: within each
+    /// tier, healthy non-circuit-broken endpoints are drawn out one at a
+    /// time via power-of-two-choices; a tier with no such endpoints left is
+    /// skipped entirely, which is the only way a later tier gets tried. Each
+    /// endpoint is paired with its own tier's [`RetrySchedule`], since a
+    /// fallback chain mixes pools with different retry policies.
+    pub fn ordered_endpoints<'a>(
+        &self,
+        resolved: &'a ResolvedProxyPool,
+    ) -> Vec<(&'a ProxyEndpoint, RetrySchedule)> {
+        let mut ordered = Vec::new();
+        for tier in &resolved.tiers {
+            let mut candidates: Vec<&ProxyEndpoint> = tier
+                .endpoints
+                .iter()
+                .filter(|e| e.healthy && !self.is_broken(&e.uri))
+                .collect();
+            while !candidates.is_empty() {
+                ordered.push((self.pick(&mut candidates), tier.retry_schedule));
+            }
+            if !ordered.is_empty() {
+                break;
+            }
+        }
+        ordered
+    }
+
+    /// Mark a request as started against `uri`, for outstanding-count scoring
+    pub fn record_start(&self, uri: &str) {
+        let mut health = self.health.lock().unwrap();
+        health.entry(uri.to_string()).or_default().outstanding += 1;
+    }
+
+    /// Fold a completed request's latency into `uri`'s EWMA and clear its
+    /// circuit breaker
+    pub fn record_success(&self, uri: &str, latency: Duration) {
+        let mut health = self.health.lock().unwrap();
+        let entry = health.entry(uri.to_string()).or_default();
+        entry.outstanding = entry.outstanding.saturating_sub(1);
+        let sample_ms = latency.as_secs_f64() * 1000.0;
+        entry.ewma_latency_ms = if entry.ewma_latency_ms == 0.0 {
+            sample_ms
+        } else {
+            entry.ewma_latency_ms * (1.0 - EWMA_ALPHA) + sample_ms * EWMA_ALPHA
+        };
+        entry.consecutive_failures = 0;
+        entry.broken_until = None;
+    }
+
+    /// Record a failed request against `uri`, circuit-breaking it for
+    /// `cooldown` once it accrues `circuit_break_after` consecutive failures
+    pub fn record_failure(&self, uri: &str, circuit_break_after: u32, cooldown: Duration) {
+        let mut health = self.health.lock().unwrap();
+        let entry = health.entry(uri.to_string()).or_default();
+        entry.outstanding = entry.outstanding.saturating_sub(1);
+        entry.consecutive_failures += 1;
+        if entry.consecutive_failures >= circuit_break_after.max(1) {
+            entry.broken_until = Some(Instant::now() + cooldown);
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProxyRotationError {
+    #[error("every proxy pool was exhausted or ejected; last error: {0}")]
+    AllPoolsExhausted(String),
+}
+
+impl ProxyRotationError {
+    /// See [`super::http::DownloadError::metric_label`] - every pool/endpoint
+    /// tried underneath collapses to this one label, since the per-attempt
+    /// [`super::http::DownloadError`] variant is already discarded by the
+    /// time every pool is exhausted.
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            ProxyRotationError::AllPoolsExhausted(_) => "proxy_pools_exhausted",
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ProxyRotationError>;
+
+/// Consecutive-failure tracking for a single pool
+#[derive(Debug, Default)]
+struct PoolHealth {
+    consecutive_failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+/// Rotates a download across a [`ProxyHint`]'s primary and fallback pools,
+/// ejecting pools that fail repeatedly.
+pub struct ProxyRotator {
+    proxy_config: ProxyConfig,
+    health: Mutex<HashMap<String, PoolHealth>>,
+    /// Per-endpoint latency/load tracking and circuit breaking, consulted
+    /// to order endpoints within a tier - see [`ProxySelector`]
+    selector: ProxySelector,
+    /// Background-refreshed view of pools with a `discovery` source (see
+    /// [`crate::config::ProxyDiscovery`]); `None` means every pool is
+    /// resolved fresh from static config on each call, via [`ProxyGraph`]
+    directory: Option<Arc<ProxyDirectory>>,
+}
+
+impl ProxyRotator {
+    pub fn new(proxy_config: ProxyConfig) -> Self {
+        Self {
+            proxy_config,
+            health: Mutex::new(HashMap::new()),
+            selector: ProxySelector::new(),
+            directory: None,
+        }
+    }
+
+    /// Consult `directory` for pools backed by a discovery source, instead
+    /// of resolving every pool fresh from static config on each download
+    pub fn with_directory(mut self, directory: Arc<ProxyDirectory>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    fn is_ejected(&self, pool: &str) -> bool {
+        let health = self.health.lock().unwrap();
+        health
+            .get(pool)
+            .and_then(|h| h.ejected_until)
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn record_failure(&self, pool: &str) {
+        let mut health = self.health.lock().unwrap();
+        let entry = health.entry(pool.to_string()).or_default();
+        entry.consecutive_failures += 1;
+        if entry.consecutive_failures >= self.proxy_config.eject_after_failures {
+            let cooldown = Duration::from_secs(self.proxy_config.cooldown_secs);
+            entry.ejected_until = Some(Instant::now() + cooldown);
+            warn!(
+                pool,
+                consecutive_failures = entry.consecutive_failures,
+                cooldown_secs = self.proxy_config.cooldown_secs,
+                "Ejecting proxy pool from rotation"
+            );
+        }
+    }
+
+    fn record_success(&self, pool: &str) {
+        let mut health = self.health.lock().unwrap();
+        health.remove(pool);
+    }
+
+    /// Primary pool first, then each fallback in order, skipping pools
+    /// currently ejected.
+    fn candidate_pools(&self, hint: &ProxyHint) -> Vec<String> {
+        std::iter::once(hint.primary_pool.clone())
+            .chain(hint.fallback_pools.iter().cloned())
+            .filter(|pool| !self.is_ejected(pool))
+            .collect()
+    }
+
+    /// Resolve `pool`, preferring `directory`'s discovery-refreshed view
+    /// when one exists for it (set by a pool's `discovery` source) and
+    /// falling back to a fresh static resolve otherwise - either because no
+    /// `directory` is configured, or `directory` hasn't resolved this pool
+    /// yet (e.g. before its first refresh tick)
+    async fn resolve_pool(
+        &self,
+        graph: &ProxyGraph<'_>,
+        pool: &str,
+    ) -> std::result::Result<crate::config::ResolvedProxyPool, crate::config::ResolverError> {
+        if let Some(directory) = &self.directory {
+            if let Some(resolved) = directory.get(pool).await {
+                return Ok(resolved);
+            }
+        }
+        graph.resolve(pool)
+    }
+
+    /// Attempt the download through `hint`'s pools, falling over to the next
+    /// pool/endpoint on a single failed [`HttpClient::download`] attempt.
+    /// Retrying the same endpoint after a backoff window is handled a layer
+    /// up, by [`super::retry::RetryScheduler`], once every pool here is
+    /// exhausted. Returns the response alongside the name of the pool that
+    /// ultimately served the request.
+    ///
+    /// Always issues a fresh, non-resuming request: a proxy pool failover
+    /// may route the next attempt through a different egress, and there's
+    /// no guarantee it reaches the same origin state the partial buffer was
+    /// captured against, so resumable downloads (see
+    /// [`super::runner::download_and_store`]) only apply to direct,
+    /// non-proxied tasks.
+    pub async fn download(
+        &self,
+        http_config: &HttpConfig,
+        hint: &ProxyHint,
+        url: &str,
+        headers: Vec<(String, String)>,
+    ) -> Result<(DownloadResponse, String)> {
+        let pools = self.candidate_pools(hint);
+        let graph = ProxyGraph::new(&self.proxy_config);
+        let mut last_error = "no candidate proxy pools available".to_string();
+
+        for pool in &pools {
+            let resolved = match self.resolve_pool(&graph, pool).await {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    last_error = e.to_string();
+                    continue;
+                }
+            };
+
+            let mut pool_failed = false;
+
+            for (endpoint, retry_schedule) in self.selector.ordered_endpoints(&resolved) {
+                let client = match HttpClient::new(http_config.clone(), endpoint.proxy_url()) {
+                    Ok(client) => client,
+                    Err(e) => {
+                        last_error = e.to_string();
+                        pool_failed = true;
+                        continue;
+                    }
+                };
+
+                self.selector.record_start(&endpoint.uri);
+                let started = Instant::now();
+                match client.download(url, headers.clone(), None).await {
+                    Ok(response) => {
+                        self.selector.record_success(&endpoint.uri, started.elapsed());
+                        self.record_success(pool);
+                        return Ok((response, pool.clone()));
+                    }
+                    Err(e) => {
+                        self.selector.record_failure(
+                            &endpoint.uri,
+                            retry_schedule.max_retries,
+                            Duration::from_millis(retry_schedule.base_backoff_ms),
+                        );
+                        last_error = e.to_string();
+                        pool_failed = true;
+                    }
+                }
+            }
+
+            if pool_failed {
+                self.record_failure(pool);
+            }
+        }
+
+        Err(ProxyRotationError::AllPoolsExhausted(last_error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProxyPoolConfig;
+
+    fn hint(primary: &str, fallbacks: &[&str]) -> ProxyHint {
+        ProxyHint {
+            primary_pool: primary.to_string(),
+            fallback_pools: fallbacks.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_candidate_pools_orders_primary_before_fallbacks() {
+        let rotator = ProxyRotator::new(ProxyConfig::default());
+        let hint = hint("primary", &["fallback1", "fallback2"]);
+        assert_eq!(
+            rotator.candidate_pools(&hint),
+            vec!["primary", "fallback1", "fallback2"]
+        );
+    }
+
+    #[test]
+    fn test_pool_is_ejected_after_threshold_and_recovers_after_cooldown() {
+        let mut config = ProxyConfig::default();
+        config.eject_after_failures = 2;
+        config.cooldown_secs = 0;
+        let rotator = ProxyRotator::new(config);
+
+        rotator.record_failure("primary");
+        assert!(!rotator.is_ejected("primary"));
+
+        rotator.record_failure("primary");
+        assert!(!rotator.is_ejected("primary")); // cooldown_secs=0 expires immediately
+    }
+
+    #[test]
+    fn test_success_clears_failure_history() {
+        let mut config = ProxyConfig::default();
+        config.eject_after_failures = 1;
+        let rotator = ProxyRotator::new(config);
+
+        rotator.record_failure("primary");
+        assert!(rotator.is_ejected("primary"));
+
+        rotator.record_success("primary");
+        assert!(!rotator.is_ejected("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_pool_not_found_error() {
+        let rotator = ProxyRotator::new(ProxyConfig::default());
+        let hint = hint("missing", &[]);
+
+        let result = rotator
+            .download(&HttpConfig::default(), &hint, "https://example.com", vec![])
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resolver_finds_configured_pool() {
+        let mut pools = HashMap::new();
+        pools.insert(
+            "primary".to_string(),
+            ProxyPoolConfig {
+                primary: vec!["http://proxy1.example.com:8080".to_string()],
+                fallbacks: vec![],
+                retry_backoff_ms: 500,
+                max_retries: 3,
+                discovery: None,
+                allow_direct_fallback: false,
+            },
+        );
+        let config = ProxyConfig {
+            pools,
+            ..Default::default()
+        };
+        let graph = ProxyGraph::new(&config);
+        let resolved = graph.resolve("primary").unwrap();
+        assert_eq!(resolved.tiers.len(), 1);
+        assert_eq!(resolved.tiers[0].endpoints.len(), 1);
+    }
+
+    fn endpoint(uri: &str) -> ProxyEndpoint {
+        ProxyEndpoint {
+            uri: uri.to_string(),
+            healthy: true,
+            last_seen: None,
+            zone: None,
+            kind: crate::config::ProxyEndpointKind::Proxied,
+        }
+    }
+
+    fn tier(endpoints: Vec<ProxyEndpoint>) -> crate::config::ResolvedTier {
+        crate::config::ResolvedTier {
+            endpoints,
+            retry_schedule: RetrySchedule::new(500, 3),
+        }
+    }
+
+    #[test]
+    fn test_selector_excludes_circuit_broken_endpoint_from_its_tier() {
+        let selector = ProxySelector::new();
+        selector.record_failure("http://p1", 1, Duration::from_secs(60));
+
+        let resolved = ResolvedProxyPool {
+            tiers: vec![tier(vec![endpoint("http://p1"), endpoint("http://p2")])],
+        };
+        let ordered: Vec<&str> = selector
+            .ordered_endpoints(&resolved)
+            .into_iter()
+            .map(|(e, _)| e.uri.as_str())
+            .collect();
+        assert_eq!(ordered, vec!["http://p2"]);
+    }
+
+    #[test]
+    fn test_selector_falls_through_tier_only_when_fully_circuit_broken() {
+        let selector = ProxySelector::new();
+        selector.record_failure("http://p1", 1, Duration::from_secs(60));
+        // p2 stays below threshold, so tier 0 still has a live endpoint and
+        // tier 1 should never be tried.
+        selector.record_failure("http://p2", 2, Duration::from_secs(60));
+
+        let resolved = ResolvedProxyPool {
+            tiers: vec![
+                tier(vec![endpoint("http://p1"), endpoint("http://p2")]),
+                tier(vec![endpoint("http://p3")]),
+            ],
+        };
+        let ordered: Vec<&str> = selector
+            .ordered_endpoints(&resolved)
+            .into_iter()
+            .map(|(e, _)| e.uri.as_str())
+            .collect();
+        assert_eq!(ordered, vec!["http://p2"]);
+
+        // Once every endpoint in tier 0 is broken, tier 1 becomes reachable.
+        selector.record_failure("http://p2", 2, Duration::from_secs(60));
+        let ordered: Vec<&str> = selector
+            .ordered_endpoints(&resolved)
+            .into_iter()
+            .map(|(e, _)| e.uri.as_str())
+            .collect();
+        assert_eq!(ordered, vec!["http://p3"]);
+    }
+
+    #[test]
+    fn test_selector_prefers_lower_scored_endpoint() {
+        let selector = ProxySelector::new();
+        selector.record_start("http://slow");
+        selector.record_success("http://slow", Duration::from_millis(500));
+        selector.record_start("http://fast");
+        selector.record_success("http://fast", Duration::from_millis(10));
+
+        let resolved = ResolvedProxyPool {
+            tiers: vec![tier(vec![endpoint("http://slow"), endpoint("http://fast")])],
+        };
+        let ordered: Vec<&str> = selector
+            .ordered_endpoints(&resolved)
+            .into_iter()
+            .map(|(e, _)| e.uri.as_str())
+            .collect();
+        assert_eq!(ordered[0], "http://fast");
+    }
+
+    #[test]
+    fn test_selector_success_clears_circuit_breaker() {
+        let selector = ProxySelector::new();
+        selector.record_failure("http://p1", 1, Duration::from_secs(60));
+        assert!(selector.is_broken("http://p1"));
+
+        selector.record_success("http://p1", Duration::from_millis(20));
+        assert!(!selector.is_broken("http://p1"));
+    }
+
+    #[test]
+    fn test_selector_reaches_direct_tier_once_proxy_tier_is_exhausted() {
+        let selector = ProxySelector::new();
+        selector.record_failure("http://p1", 1, Duration::from_secs(60));
+
+        let resolved = ResolvedProxyPool {
+            tiers: vec![
+                tier(vec![endpoint("http://p1")]),
+                tier(vec![ProxyEndpoint::direct()]),
+            ],
+        };
+        let ordered = selector.ordered_endpoints(&resolved);
+        assert_eq!(ordered.len(), 1);
+        assert_eq!(ordered[0].0.proxy_url(), None);
+    }
+
+    #[test]
+    fn test_retry_schedule_first_attempt_has_no_delay() {
+        let schedule = RetrySchedule::new(500, 3);
+        assert_eq!(schedule.sleeps()[0], Duration::ZERO);
+    }
+
+    #[test]
+    fn test_retry_schedule_grows_and_stays_capped() {
+        let schedule = RetrySchedule::new(1000, 20);
+        let sleeps = schedule.sleeps();
+        assert_eq!(sleeps.len(), 21);
+        for (n, sleep) in sleeps.iter().enumerate().skip(1) {
+            let bound = (1000u64 << n.min(20)).min(30_000);
+            assert!(sleep.as_millis() as u64 <= bound);
+        }
+    }
+
+    #[test]
+    fn test_direct_endpoint_proxy_url_is_none() {
+        let direct = ProxyEndpoint::direct();
+        assert_eq!(direct.proxy_url(), None);
+
+        let proxied = endpoint("http://p1");
+        assert_eq!(proxied.proxy_url(), Some("http://p1"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Time-ordered retry scheduling for failed downloads
+//!
+//! Modeled on cargo's `SleepTracker`: instead of blocking a worker inline
+//! with `tokio::time::sleep` while it waits to retry one task (the old
+//! behavior of [`super::http::HttpClient::download`]), a failed task is
+//! parked in a min-heap keyed by `wake_at` and only re-dispatched once its
+//! backoff window has elapsed. Both [`super::pool::run_pool`] and
+//! [`super::spawn_pool`]'s worker loops keep one [`RetryScheduler`] per
+//! worker, draining ready fresh tasks first and peeking this heap the rest
+//! of the time, sleeping at most until the nearest `wake_at` when idle - so
+//! one stalled host no longer holds up every other in-flight download.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use crate::proto::DownloadTask;
+
+/// A task parked until its backoff window elapses
+struct Parked {
+    wake_at: Instant,
+    seq: u64,
+    task: DownloadTask,
+}
+
+impl PartialEq for Parked {
+    fn eq(&self, other: &Self) -> bool {
+        self.wake_at == other.wake_at
+    }
+}
+
+impl Eq for Parked {}
+
+impl PartialOrd for Parked {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Parked {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.wake_at.cmp(&other.wake_at)
+    }
+}
+
+/// Exponential backoff (1s, 2s, 4s, ... capped at 2^10s) with up to 20%
+/// jitter, so that every task failing in a broad outage doesn't wake and
+/// retry at the exact same instant
+pub fn backoff_for(seq: u64, attempt: u32) -> Duration {
+    let base_secs = 1u64 << attempt.saturating_sub(1).min(10);
+    let base = Duration::from_secs(base_secs);
+
+    // No `rand` dependency in this tree - a cheap splitmix-style hash of
+    // `seq`/`attempt`/the current instant is plenty for jitter, which only
+    // needs to decorrelate wake times, not be cryptographically random.
+    let mut x = seq
+        ^ (u64::from(attempt).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        ^ (Instant::now().elapsed().as_nanos() as u64);
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x ^= x >> 27;
+
+    let max_jitter_ms = (base.as_millis() as u64 / 5).max(1);
+    let jitter_ms = x % max_jitter_ms;
+
+    base + Duration::from_millis(jitter_ms)
+}
+
+/// Min-heap of parked retries, ordered by `wake_at`
+#[derive(Default)]
+pub struct RetryScheduler {
+    heap: BinaryHeap<Reverse<Parked>>,
+}
+
+impl RetryScheduler {
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Park `task` (originally stored at `seq`) to be retried after
+    /// `attempt`'s backoff window
+    pub fn schedule(&mut self, seq: u64, task: DownloadTask, attempt: u32) {
+        let wake_at = Instant::now() + backoff_for(seq, attempt);
+        self.heap.push(Reverse(Parked { wake_at, seq, task }));
+    }
+
+    /// The earliest `wake_at` still parked, if anything is
+    pub fn next_wake(&self) -> Option<Instant> {
+        self.heap.peek().map(|Reverse(p)| p.wake_at)
+    }
+
+    /// Pop every entry whose backoff window has elapsed
+    pub fn drain_ready(&mut self) -> Vec<(u64, DownloadTask)> {
+        let now = Instant::now();
+        let mut ready = Vec::new();
+
+        while matches!(self.heap.peek(), Some(Reverse(p)) if p.wake_at <= now) {
+            let Reverse(p) = self.heap.pop().expect("just peeked Some");
+            ready.push((p.seq, p.task));
+        }
+
+        ready
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> DownloadTask {
+        DownloadTask {
+            job_id: "job-1".to_string(),
+            resource_id: "res-1".to_string(),
+            url: "https://example.com/file".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially() {
+        let b1 = backoff_for(1, 1).as_millis();
+        let b2 = backoff_for(1, 2).as_millis();
+        let b3 = backoff_for(1, 3).as_millis();
+
+        assert!(b1 >= 1000 && b1 < 1300);
+        assert!(b2 >= 2000 && b2 < 2500);
+        assert!(b3 >= 4000 && b3 < 5000);
+    }
+
+    #[test]
+    fn test_drain_ready_returns_only_elapsed_entries() {
+        let mut scheduler = RetryScheduler::new();
+        scheduler.heap.push(Reverse(Parked {
+            wake_at: Instant::now() - Duration::from_millis(1),
+            seq: 1,
+            task: task(),
+        }));
+        scheduler.heap.push(Reverse(Parked {
+            wake_at: Instant::now() + Duration::from_secs(60),
+            seq: 2,
+            task: task(),
+        }));
+
+        let ready = scheduler.drain_ready();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].0, 1);
+        assert!(!scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_next_wake_reflects_earliest_entry() {
+        let mut scheduler = RetryScheduler::new();
+        assert!(scheduler.next_wake().is_none());
+
+        scheduler.schedule(1, task(), 1);
+        scheduler.schedule(2, task(), 5);
+
+        let next = scheduler.next_wake().unwrap();
+        assert!(next <= Instant::now() + Duration::from_secs(2));
+    }
+}
@@ -1,12 +1,25 @@
 //! Task runner - processes individual DownloadTask messages
 
-use super::http::{HttpClient, HttpConfig};
-use crate::proto::{DownloadTask, JobLog, LogLevel};
+use super::host_limit::{self, HostLimiter};
+use super::http::{DownloadError, HttpClient, HttpConfig, ResumeFrom};
+use super::manifest;
+use super::notify::{NotificationDispatcher, NotificationEvent};
+use super::proxy::ProxyRotator;
+use super::status_stream::{StatusBroadcaster, TaskEventPayload};
+use super::task_state::{self, TaskEvent, TaskState};
+use super::validate::{ContentValidator, StreamingChecksum};
+use crate::api::models::{JobError, JobSnapshot};
+use crate::handlers::{HandlerRegistry, JobSummary};
+use crate::ledger::FjallStore;
+use crate::observability::Metrics;
+use crate::proto::DownloadTask;
+use crate::queue::{FjallQueue, PartialDownload};
 use crate::storage::StorageClient;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
-use time::OffsetDateTime;
-use tracing::{error, info};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Error)]
 pub enum TaskError {
@@ -16,89 +29,777 @@ pub enum TaskError {
     #[error("Upload failed: {0}")]
     UploadFailed(String),
 
+    #[error("Content validation failed: {0}")]
+    ValidationFailed(String),
+
     #[error("Invalid task: {0}")]
     InvalidTask(String),
 }
 
 pub type Result<T> = std::result::Result<T, TaskError>;
 
-// TODO: Rewrite process_task to use Fjall ledger instead of Iggy producer (Phase 4)
+/// Terminal (or not-yet-terminal) outcome of one [`process_task`] call
+///
+/// `Retry` is not a job-level terminal state: the caller is expected to
+/// park the returned task (e.g. via [`super::retry::RetryScheduler`]) and
+/// re-dispatch it once its backoff window elapses, rather than treating the
+/// attempt as done.
+#[derive(Debug)]
+pub enum TaskOutcome {
+    Succeeded,
+    DeadLettered,
+    Retry { task: DownloadTask, attempt: u32 },
+}
+
+/// Size-threshold policy for choosing between the buffered and
+/// multipart-streaming upload paths in [`download_and_store`]
+///
+/// A response at or below `stream_threshold_bytes` is buffered fully in
+/// memory, same as before - small enough that the full sha256/MIME/media
+/// validation in [`ContentValidator::validate`] and resumable-download
+/// support (see [`super::http::ResumeFrom`]) both still apply. Above the
+/// threshold the body is streamed straight into
+/// [`crate::storage::StorageClient::upload_multipart`] in
+/// `upload_part_size_bytes` chunks instead, trading those two features
+/// for bounded memory use: a partial transfer still gets a checksum (hashed
+/// incrementally as it streams) and a MIME check (from the `Content-Type`
+/// header alone), but not `probe_media` or a retry-resumable buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingPolicy {
+    pub stream_threshold_bytes: u64,
+    pub upload_part_size_bytes: usize,
+    pub max_content_length_bytes: Option<u64>,
+}
 
-/*
 /// Process a single download task
+///
+/// Downloads the resource, uploads it to storage, and folds the outcome
+/// directly into the job's [`crate::api::models::JobSnapshot`] via the
+/// shared `ledger`. The task is driven through [`super::task_state`]'s
+/// `Queued -> Running -> Succeeded | Failed [-> DeadLettered]` state machine
+/// as it goes, with every transition logged as a structured
+/// `fetchbox::jobs::status` event (see [`transition`]) - the single-process
+/// architecture's stand-in for a separate status stream.
+///
+/// When a task fails and has already reached `max_attempts`, it is moved to
+/// the queue's dead-letter partition (keyed by `seq`, looked up by
+/// job_id+resource_id via [`crate::queue::FjallQueue::find_dlq_entry`])
+/// instead of being retried, so operators can inspect and replay it later.
+/// Otherwise [`TaskOutcome::Retry`] carries a copy of the task with `attempt`
+/// incremented, ready to hand to a retry scheduler - neither the ledger nor
+/// `notifier` are touched for a retryable failure, since the job isn't done
+/// with this resource yet, though [`Metrics::record_retry`] still is.
+///
+/// When `task.proxy_hint` and `proxy_rotator` are both present, the download
+/// is routed through [`super::proxy::ProxyRotator`]; otherwise it goes out
+/// direct. Either way the serving pool (or `"direct"`) is recorded via
+/// [`Metrics::record_proxy_pool`], and the attempt itself via
+/// [`Metrics::record_download_attempt`]/[`Metrics::record_download_success`]/
+/// [`Metrics::record_download_failure`].
+///
+/// Before either of those, `host_limiter` caps how many downloads may be in
+/// flight against `task.url`'s host at once (see
+/// [`super::host_limit::HostLimiter`]), independent of how many worker
+/// slots are free, so one saturated host can't starve every other job.
+///
+/// Before the body is uploaded, `validator` checks it against `task.job_type`'s
+/// configured rules (see [`ContentValidator`]) and computes its sha256
+/// checksum; a rejected body fails the task the same way a download error
+/// does (retried, then dead-lettered past `max_attempts`).
+///
+/// Once the resource is stored, its outcome is merged into the job's run
+/// manifest at `task.manifest_key` (see [`manifest::record_resource`]) -
+/// best-effort, so a manifest write failure is logged but does not fail the
+/// task.
+///
+/// Once the task reaches a terminal state (`Succeeded` or `DeadLettered`),
+/// `notifier` is handed a [`NotificationEvent`] describing it (see
+/// [`NotificationDispatcher::dispatch`]) - delivery happens off the critical
+/// path and never delays the task's completion.
+///
+/// `streaming_policy` decides whether the body is buffered or streamed
+/// straight into storage - see [`StreamingPolicy`].
+///
+/// Every status transition and a handful of key log lines are also fanned
+/// out through `status_broadcaster` (keyed by `seq`) for
+/// `GET /tasks/{seq}/events` subscribers - see [`super::status_stream`].
+///
+/// Once the ledger reports that this resource was the job's last one
+/// outstanding, `registry` is used to look up the job's handler and call
+/// [`crate::handlers::JobHandler::finalize_job`] - see [`finalize_if_done`].
+#[allow(clippy::too_many_arguments)]
 pub async fn process_task(
+    seq: u64,
     task: DownloadTask,
     storage: Arc<StorageClient>,
     ledger: Arc<FjallStore>,
-    proxy_url: Option<&str>,
-) -> Result<()> {
+    queue: Arc<RwLock<FjallQueue>>,
+    max_attempts: u32,
+    proxy_rotator: Option<Arc<ProxyRotator>>,
+    validator: Arc<ContentValidator>,
+    notifier: Arc<NotificationDispatcher>,
+    metrics: Arc<Metrics>,
+    host_limiter: Arc<HostLimiter>,
+    streaming_policy: StreamingPolicy,
+    status_broadcaster: Arc<StatusBroadcaster>,
+    registry: Arc<HandlerRegistry>,
+) -> Result<TaskOutcome> {
     let job_id = task.job_id.clone();
     let resource_id = task.resource_id.clone();
     let url = task.url.clone();
+    let attempt = task.attempt;
+
+    info!(job_id, resource_id, url, attempt, "Processing task");
+
+    let state = transition(
+        &status_broadcaster,
+        seq,
+        &job_id,
+        &resource_id,
+        attempt,
+        TaskState::Queued,
+        TaskEvent::Started,
+    );
+
+    metrics.download_started();
+    let outcome = download_and_store(
+        seq,
+        &task,
+        storage,
+        queue.clone(),
+        proxy_rotator.as_deref(),
+        &validator,
+        &metrics,
+        &host_limiter,
+        streaming_policy,
+        &status_broadcaster,
+    )
+    .await;
+    metrics.download_finished();
+
+    match outcome {
+        Ok(storage_key) => {
+            transition(
+                &status_broadcaster,
+                seq,
+                &job_id,
+                &resource_id,
+                attempt,
+                state,
+                TaskEvent::Completed,
+            );
+            info!(job_id, resource_id, storage_key, "Task completed");
+            status_broadcaster.record(
+                seq,
+                TaskEventPayload::Log {
+                    level: "info".to_string(),
+                    message: format!("Task completed, stored at {storage_key}"),
+                },
+            );
+
+            notifier.dispatch(
+                &task.job_type,
+                NotificationEvent::new(
+                    &job_id,
+                    &resource_id,
+                    &task.tenant,
+                    TaskState::Succeeded,
+                    Some(storage_key),
+                    None,
+                ),
+            );
 
-    info!(job_id, resource_id, url, "Processing task");
+            let folded = ledger
+                .record_resource_outcome(&job_id, None)
+                .map_err(|e| TaskError::InvalidTask(e.to_string()))?;
+            finalize_if_done(&registry, folded).await;
 
-    // Step 1: Download resource
-    let http_config = HttpConfig::default();
-    let client = HttpClient::new(http_config, proxy_url)
-        .map_err(|e| TaskError::DownloadFailed(e.to_string()))?;
+            Ok(TaskOutcome::Succeeded)
+        }
+        Err(e) => {
+            let state = transition(
+                &status_broadcaster,
+                seq,
+                &job_id,
+                &resource_id,
+                attempt,
+                state,
+                TaskEvent::Failed(e.to_string()),
+            );
+            error!(job_id, resource_id, error = %e, "Task failed");
+            status_broadcaster.record(
+                seq,
+                TaskEventPayload::Log {
+                    level: "error".to_string(),
+                    message: format!("Task failed: {e}"),
+                },
+            );
+
+            let code = match &e {
+                TaskError::ValidationFailed(_) => "VALIDATION_FAILED",
+                TaskError::UploadFailed(_) => "UPLOAD_FAILED",
+                _ => "DOWNLOAD_FAILED",
+            };
+
+            if attempt >= max_attempts {
+                warn!(job_id, resource_id, attempt, max_attempts, "Task exhausted retries, moving to DLQ");
+                let queue = queue.write().await;
+                queue
+                    .move_to_dlq(seq, code.to_string(), e.to_string(), attempt)
+                    .map_err(|e| TaskError::InvalidTask(e.to_string()))?;
+                // No further attempt is coming, so a buffered partial (if
+                // any) is no longer useful - replay picks up fresh instead.
+                if let Err(e) = queue.remove_partial(seq) {
+                    warn!(seq, error = %e, "Failed to clear partial download entry for DLQ'd task");
+                }
+                drop(queue);
+                transition(
+                    &status_broadcaster,
+                    seq,
+                    &job_id,
+                    &resource_id,
+                    attempt,
+                    state,
+                    TaskEvent::DeadLettered,
+                );
+
+                let error = JobError {
+                    resource_name: resource_id.clone(),
+                    code: code.to_string(),
+                    message: e.to_string(),
+                    timestamp: chrono::Utc::now(),
+                };
+
+                notifier.dispatch(
+                    &task.job_type,
+                    NotificationEvent::new(
+                        &job_id,
+                        &resource_id,
+                        &task.tenant,
+                        TaskState::DeadLettered,
+                        None,
+                        Some(error.message.clone()),
+                    ),
+                );
+
+                let folded = ledger
+                    .record_resource_outcome(&job_id, Some(error))
+                    .map_err(|e| TaskError::InvalidTask(e.to_string()))?;
+                finalize_if_done(&registry, folded).await;
+
+                Ok(TaskOutcome::DeadLettered)
+            } else {
+                // Retries remaining: the job isn't done with this resource
+                // yet, so neither the ledger nor `notifier` are touched -
+                // the caller is expected to park the returned task (e.g. via
+                // `super::retry::RetryScheduler`) and re-dispatch it once
+                // its backoff window elapses.
+                metrics.record_retry();
+                let mut retry_task = task;
+                retry_task.attempt = attempt + 1;
+                Ok(TaskOutcome::Retry {
+                    task: retry_task,
+                    attempt: attempt + 1,
+                })
+            }
+        }
+    }
+}
+
+/// Call the owning handler's `finalize_job` once a job's last resource has
+/// just been accounted for by [`FjallStore::record_resource_outcome`]
+///
+/// `folded` is its return value verbatim: `None` if the job wasn't found
+/// (nothing to finalize), `Some((_, false))` if the job has resources still
+/// outstanding, and `Some((snapshot, true))` exactly once, on whichever
+/// worker's outcome happened to push `resource_completed + resource_failed`
+/// over `resource_total` - every other caller sees `false` and does
+/// nothing, so `finalize_job` runs at most once per job. A missing or
+/// erroring handler is logged and otherwise ignored: the job's terminal
+/// status is already durably recorded regardless of how its finalize hook
+/// behaves.
+async fn finalize_if_done(registry: &HandlerRegistry, folded: Option<(JobSnapshot, bool)>) {
+    let Some((snapshot, true)) = folded else {
+        return;
+    };
+
+    let summary = JobSummary {
+        job_id: snapshot.job_id.clone(),
+        job_type: snapshot.job_type.clone(),
+        total_resources: snapshot.resource_total,
+        completed_resources: snapshot.resource_completed,
+        failed_resources: snapshot.resource_failed,
+    };
+
+    match registry.get(&snapshot.job_type) {
+        Ok(handler) => {
+            if let Err(e) = handler.finalize_job(summary).await {
+                warn!(
+                    job_id = %snapshot.job_id,
+                    job_type = %snapshot.job_type,
+                    error = %e,
+                    "finalize_job failed"
+                );
+            }
+        }
+        Err(e) => {
+            warn!(
+                job_id = %snapshot.job_id,
+                job_type = %snapshot.job_type,
+                error = %e,
+                "No handler registered for job_type, skipping finalize_job"
+            );
+        }
+    }
+}
+
+/// Validate a [`TaskState`] transition via [`task_state::next`] and emit the
+/// resulting status both as a structured `fetchbox::jobs::status` tracing
+/// event and as a [`TaskEventPayload::Status`] on `status_broadcaster`, for
+/// `GET /tasks/{seq}/events` subscribers (see [`super::status_stream`]). An
+/// illegal transition is a worker bug: it's logged and the state is left
+/// unchanged rather than panicking.
+#[allow(clippy::too_many_arguments)]
+fn transition(
+    status_broadcaster: &StatusBroadcaster,
+    seq: u64,
+    job_id: &str,
+    resource_id: &str,
+    attempt: u32,
+    current: TaskState,
+    event: TaskEvent,
+) -> TaskState {
+    match task_state::next(current, &event) {
+        Ok(next_state) => {
+            info!(
+                target: "fetchbox::jobs::status",
+                job_id,
+                resource_id,
+                attempt,
+                from = ?current,
+                to = ?next_state,
+                "Task status transition"
+            );
+            status_broadcaster.record(
+                seq,
+                TaskEventPayload::Status {
+                    from: format!("{current:?}"),
+                    to: format!("{next_state:?}"),
+                },
+            );
+            next_state
+        }
+        Err(e) => {
+            error!(job_id, resource_id, attempt, error = %e, "Invalid task state transition");
+            current
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn download_and_store(
+    seq: u64,
+    task: &DownloadTask,
+    storage: Arc<StorageClient>,
+    queue: Arc<RwLock<FjallQueue>>,
+    proxy_rotator: Option<&ProxyRotator>,
+    validator: &ContentValidator,
+    metrics: &Metrics,
+    host_limiter: &HostLimiter,
+    streaming_policy: StreamingPolicy,
+    status_broadcaster: &StatusBroadcaster,
+) -> Result<String> {
+    let http_config = HttpConfig {
+        max_content_length: streaming_policy.max_content_length_bytes,
+        ..HttpConfig::default()
+    };
 
-    // Convert headers from proto format
     let headers: Vec<(String, String)> = task
         .headers
         .iter()
         .map(|h| (h.name.clone(), h.value.clone()))
         .collect();
 
-    let bytes = client
-        .download(&url, headers)
-        .await
-        .map_err(|e| TaskError::DownloadFailed(e.to_string()))?;
+    // Only the direct (non-proxied) path resumes a partial download - see
+    // the doc comment on `ProxyRotator::download` for why a pool failover
+    // can't safely reuse a buffered prefix.
+    let direct = task.proxy_hint.is_none() || proxy_rotator.is_none();
+    let existing_partial = if direct {
+        queue
+            .read()
+            .await
+            .get_partial(seq)
+            .map_err(|e| TaskError::DownloadFailed(e.to_string()))?
+    } else {
+        None
+    };
+    let resume = existing_partial.as_ref().map(|partial| ResumeFrom {
+        received_bytes: partial.bytes.len() as u64,
+        validator: partial.validator.clone(),
+    });
+
+    // Held for the download only (not the upload/validation that follows),
+    // so a host at its concurrency cap blocks new requests to it without
+    // tying up the slot any longer than the fetch itself takes.
+    let _host_permit = host_limiter.acquire(&host_limit::host_of(&task.url)).await;
+
+    let started_at = Instant::now();
+    let started_at_utc = chrono::Utc::now();
+
+    // The streaming-to-storage path only applies to fresh, direct attempts:
+    // a resumed download already has a partial buffer in hand (small enough
+    // that holding it was fine), and a proxied attempt always goes through
+    // `ProxyRotator::download`'s buffered `HttpClient::download` call. For
+    // everything else, peek the size via `HEAD` before committing to a path
+    // - a failed or unsupported `HEAD` is treated as "unknown size" and
+    // falls back to the buffered path, same as today.
+    if direct && resume.is_none() {
+        let client = HttpClient::new(http_config.clone(), None)
+            .map_err(|e| TaskError::DownloadFailed(e.to_string()))?;
+        let size = client.peek_content_length(&task.url, &headers).await;
+        if size.is_some_and(|len| len > streaming_policy.stream_threshold_bytes) {
+            return download_and_store_streaming(
+                seq,
+                task,
+                &client,
+                &storage,
+                validator,
+                metrics,
+                streaming_policy,
+                started_at,
+                started_at_utc,
+                headers,
+                _host_permit,
+                status_broadcaster,
+            )
+            .await;
+        }
+    }
+
+    metrics.record_download_attempt();
+    let (response, served_by) = match (&task.proxy_hint, proxy_rotator) {
+        (Some(hint), Some(rotator)) => {
+            let hint: crate::handlers::types::ProxyHint = hint.clone().into();
+            match rotator.download(&http_config, &hint, &task.url, headers).await {
+                Ok(result) => {
+                    metrics.record_download_success();
+                    result
+                }
+                Err(e) => {
+                    metrics.record_download_failure(e.metric_label());
+                    return Err(TaskError::DownloadFailed(e.to_string()));
+                }
+            }
+        }
+        _ => {
+            let client = HttpClient::new(http_config, None)
+                .map_err(|e| TaskError::DownloadFailed(e.to_string()))?;
+            match client.download(&task.url, headers, resume.as_ref()).await {
+                Ok(response) => {
+                    metrics.record_download_success();
+                    (response, "direct".to_string())
+                }
+                Err(DownloadError::PartialBody {
+                    partial,
+                    accept_ranges,
+                    etag,
+                    last_modified,
+                    message,
+                    ..
+                }) => {
+                    metrics.record_download_failure("partial_body");
+                    if let Some(validator) = accept_ranges.then_some(()).and(etag.or(last_modified)) {
+                        let combined = existing_partial
+                            .map(|p| p.bytes)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .chain(partial)
+                            .collect();
+                        let saved = PartialDownload { bytes: combined, validator };
+                        if let Err(e) = queue.write().await.save_partial(seq, &saved) {
+                            warn!(seq, error = %e, "Failed to persist partial download");
+                        }
+                    }
+                    return Err(TaskError::DownloadFailed(message));
+                }
+                Err(e) => {
+                    metrics.record_download_failure(e.metric_label());
+                    return Err(TaskError::DownloadFailed(e.to_string()));
+                }
+            }
+        }
+    };
+    drop(_host_permit);
+    let status = response.status;
+
+    // The body either completed in full (status != 206) or is the tail of a
+    // previously-buffered prefix (status == 206, `resume` was sent) - either
+    // way the task is now past the point a partial buffer would help, so
+    // drop it rather than leave it to grow stale.
+    if direct {
+        let queue = queue.write().await;
+        if let Err(e) = queue.remove_partial(seq) {
+            warn!(seq, error = %e, "Failed to clear partial download entry");
+        }
+    }
+
+    // A `206`'s `Content-Length` covers only the tail, so the full length
+    // to validate against is the buffered prefix plus that tail.
+    let prefix_len = match (&existing_partial, status) {
+        (Some(partial), 206) => partial.bytes.len() as u64,
+        _ => 0,
+    };
+    let expected_len = response.content_length.map(|len| len + prefix_len);
 
+    let bytes = if status == 206 {
+        let mut combined = existing_partial
+            .map(|partial| partial.bytes)
+            .unwrap_or_default();
+        combined.extend_from_slice(&response.bytes);
+        bytes::Bytes::from(combined)
+    } else {
+        response.bytes
+    };
+
+    status_broadcaster.record(
+        seq,
+        TaskEventPayload::Progress {
+            bytes_downloaded: bytes.len() as u64,
+            total_bytes: expected_len,
+        },
+    );
+
+    let validation = validator
+        .validate(
+            &task.job_type,
+            response.content_type.as_deref(),
+            expected_len,
+            &bytes,
+            task.attributes.as_ref().map(|a| a.extra.as_slice()),
+        )
+        .await
+        .map_err(|e| TaskError::ValidationFailed(e.to_string()))?;
     info!(
-        job_id,
-        resource_id,
-        size = bytes.len(),
-        "Download completed"
+        target: "fetchbox::jobs::validation",
+        job_id = %task.job_id,
+        resource_id = %task.resource_id,
+        checksum_sha256 = %validation.checksum_sha256,
+        probe = ?validation.probe,
+        sniffed_mime = ?validation.sniffed_mime,
+        "Content validation passed"
     );
 
-    // Step 2: Upload to storage
-    let storage_key = determine_storage_key(&task);
+    // An explicit `storage_hint` is a handler telling us exactly where to
+    // put this resource (spec §2) - honor it verbatim. Otherwise, content-
+    // address the upload (see [`crate::storage::StorageClient::upload_content_addressed`])
+    // so identical resources fetched by different jobs converge on one blob
+    // instead of each paying for its own copy.
+    let has_explicit_hint = task
+        .storage_hint
+        .as_ref()
+        .is_some_and(|hint| !hint.key_prefix.is_empty());
 
-    storage
-        .upload(&storage_key, bytes.to_vec())
+    let storage_key = if has_explicit_hint {
+        let storage_key = determine_storage_key(task);
+        // Tag the object with its sniffed content-type (spec §2's
+        // `StorageHint.object_metadata`) so a consumer reading it straight
+        // out of the bucket sees accurate metadata. Content-addressed
+        // uploads skip this below: the key is shared by every job that
+        // ever fetched these exact bytes, so there's no single "this
+        // job's" content-type to tag it with.
+        storage
+            .upload_with_content_type(
+                &storage_key,
+                bytes.to_vec(),
+                validation.sniffed_mime.as_deref(),
+            )
+            .await
+            .map_err(|e| TaskError::UploadFailed(e.to_string()))?;
+        storage_key
+    } else {
+        let uploaded = storage
+            .upload_content_addressed("resources", bytes.to_vec())
+            .await
+            .map_err(|e| TaskError::UploadFailed(e.to_string()))?;
+        if uploaded.deduped {
+            debug!(
+                job_id = %task.job_id,
+                resource_id = %task.resource_id,
+                storage_key = %uploaded.key,
+                "Resource deduped via content-addressed storage"
+            );
+        }
+        uploaded.key
+    };
+
+    let manifest_record = manifest::ManifestRecord {
+        resource_id: task.resource_id.clone(),
+        storage_bucket: storage.bucket.clone(),
+        storage_key: storage_key.clone(),
+        size_bytes: bytes.len() as u64,
+        checksum_sha256: validation.checksum_sha256.clone(),
+        http_status: status,
+        content_type: response.content_type.clone(),
+        detected_mime_type: validation.sniffed_mime.clone(),
+        proxy_pool: served_by.clone(),
+        attempt: task.attempt,
+        started_at: started_at_utc,
+        completed_at: chrono::Utc::now(),
+    };
+    if let Err(e) = manifest::record_resource(
+        &storage,
+        &task.manifest_key,
+        &task.job_id,
+        manifest_record,
+    )
+    .await
+    {
+        warn!(
+            job_id = %task.job_id,
+            resource_id = %task.resource_id,
+            error = %e,
+            "Failed to record resource into run manifest"
+        );
+    }
+
+    metrics.observe_download(
+        &task.job_type,
+        &task.tenant,
+        started_at.elapsed(),
+        bytes.len() as u64,
+        status,
+    );
+    metrics.record_proxy_pool(&task.job_type, &served_by);
+
+    Ok(storage_key)
+}
+
+/// Streaming-to-storage branch of [`download_and_store`], for a fresh,
+/// direct attempt whose `HEAD`-reported size exceeds
+/// `streaming_policy.stream_threshold_bytes`
+///
+/// Trades away resumability and [`ContentValidator`]'s full
+/// empty/truncation/`probe_media`/sniffed-mime checks for bounded memory
+/// use: the MIME type is still checked against `task.job_type`'s rules (via
+/// [`ContentValidator::check_mime`]) and the checksum is still computed, just
+/// incrementally as chunks stream through (via [`StreamingChecksum`]) rather
+/// than over a complete in-memory body - there's no single point with the
+/// full body in hand to sniff magic bytes from.
+#[allow(clippy::too_many_arguments)]
+async fn download_and_store_streaming(
+    seq: u64,
+    task: &DownloadTask,
+    client: &HttpClient,
+    storage: &StorageClient,
+    validator: &ContentValidator,
+    metrics: &Metrics,
+    streaming_policy: StreamingPolicy,
+    started_at: Instant,
+    started_at_utc: chrono::DateTime<chrono::Utc>,
+    headers: Vec<(String, String)>,
+    host_permit: tokio::sync::OwnedSemaphorePermit,
+    status_broadcaster: &StatusBroadcaster,
+) -> Result<String> {
+    metrics.record_download_attempt();
+    let (meta, body) = match client.download_stream(&task.url, &headers, None).await {
+        Ok(result) => {
+            metrics.record_download_success();
+            result
+        }
+        Err(e) => {
+            metrics.record_download_failure(e.metric_label());
+            return Err(TaskError::DownloadFailed(e.to_string()));
+        }
+    };
+
+    validator
+        .check_mime(&task.job_type, meta.content_type.as_deref())
+        .map_err(|e| TaskError::ValidationFailed(e.to_string()))?;
+
+    let checksum = Arc::new(std::sync::Mutex::new(StreamingChecksum::new()));
+    let checksum_for_stream = checksum.clone();
+    let bytes_downloaded = Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let bytes_downloaded_for_stream = bytes_downloaded.clone();
+    let total_bytes = meta.content_length;
+    let checked_body = futures::TryStreamExt::inspect_ok(body, move |chunk| {
+        checksum_for_stream.lock().unwrap().update(chunk);
+        let downloaded = bytes_downloaded_for_stream
+            .fetch_add(chunk.len() as u64, std::sync::atomic::Ordering::Relaxed)
+            + chunk.len() as u64;
+        status_broadcaster.record(
+            seq,
+            TaskEventPayload::Progress {
+                bytes_downloaded: downloaded,
+                total_bytes,
+            },
+        );
+    })
+    .map_err(|e| TaskError::DownloadFailed(e.to_string()));
+
+    let storage_key = determine_storage_key(task);
+    let upload_meta = storage
+        .upload_multipart(&storage_key, checked_body, streaming_policy.upload_part_size_bytes)
         .await
         .map_err(|e| TaskError::UploadFailed(e.to_string()))?;
+    drop(host_permit);
 
-    info!(job_id, resource_id, storage_key, "Upload completed");
-
-    // Step 3: Emit success log
-    let log = JobLog {
-        job_id: job_id.clone(),
-        resource_id: resource_id.clone(),
-        level: LogLevel::Info as i32,
-        message: format!("Successfully downloaded and stored: {}", storage_key),
-        fields: vec![
-            (
-                "storage_key".to_string(),
-                storage_key.clone(),
-            ),
-            ("url".to_string(), url.clone()),
-        ]
-        .into_iter()
-        .collect(),
-        timestamp_ms: OffsetDateTime::now_utc().unix_timestamp() as u64 * 1000,
-        trace_id: task.trace_id.clone(),
-    };
+    let checksum_sha256 = Arc::try_unwrap(checksum)
+        .expect("stream fully drained, no other references remain")
+        .into_inner()
+        .unwrap()
+        .finalize_hex();
+    info!(
+        target: "fetchbox::jobs::validation",
+        job_id = %task.job_id,
+        resource_id = %task.resource_id,
+        checksum_sha256 = %checksum_sha256,
+        "Content validation passed (streamed, no media probe)"
+    );
 
-    if let Err(e) = producer.publish_job_log(&log).await {
-        error!(job_id, resource_id, error = %e, "Failed to publish log");
-        // Don't fail the task if logging fails
+    let manifest_record = manifest::ManifestRecord {
+        resource_id: task.resource_id.clone(),
+        storage_bucket: storage.bucket.clone(),
+        storage_key: storage_key.clone(),
+        size_bytes: upload_meta.size as u64,
+        checksum_sha256,
+        http_status: meta.status,
+        content_type: meta.content_type.clone(),
+        // No magic-byte sniff here: the streaming path never holds the full
+        // body at once (see this function's doc comment), only a leading
+        // chunk of it would be available to sniff from at any given moment.
+        detected_mime_type: None,
+        proxy_pool: "direct".to_string(),
+        attempt: task.attempt,
+        started_at: started_at_utc,
+        completed_at: chrono::Utc::now(),
+    };
+    if let Err(e) = manifest::record_resource(
+        storage,
+        &task.manifest_key,
+        &task.job_id,
+        manifest_record,
+    )
+    .await
+    {
+        warn!(
+            job_id = %task.job_id,
+            resource_id = %task.resource_id,
+            error = %e,
+            "Failed to record resource into run manifest"
+        );
     }
 
-    Ok(())
+    metrics.observe_download(
+        &task.job_type,
+        &task.tenant,
+        started_at.elapsed(),
+        upload_meta.size as u64,
+        meta.status,
+    );
+    metrics.record_proxy_pool(&task.job_type, "direct");
+
+    Ok(storage_key)
 }
-*/
 
 /// Determine storage key from task hints or defaults
 pub fn determine_storage_key(task: &DownloadTask) -> String {
@@ -0,0 +1,272 @@
+//! Per-task status/log/progress event stream for live SSE visibility
+//!
+//! [`super::runner::process_task`] used to only fold its
+//! `fetchbox::jobs::status` transitions into a tracing event - useful for
+//! operators tailing logs, but nothing an API client could subscribe to.
+//! [`StatusBroadcaster`] gives each task (keyed by its queue `seq`, the same
+//! identifier already exposed via `GET /operators/deadletters/{seq}/replay`)
+//! its own [`tokio::sync::broadcast`] channel of [`TaskEvent`]s plus a small
+//! ring buffer of the most recent ones, so `GET /tasks/{seq}/events` (see
+//! [`crate::api::services::task_events`]) can both stream live updates and
+//! replay whatever a reconnecting client (via `Last-Event-ID`) missed while
+//! disconnected.
+//!
+//! Implements [`MessageProducer`]/[`MessageConsumer`] so the broadcaster
+//! composes with the rest of [`crate::messaging`]'s pub/sub abstraction
+//! rather than exposing a one-off API; [`Self::record`]/[`Self::subscribe_from`]
+//! are the typed, ergonomic entry points most callers want.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::messaging::{MessageConsumer, MessageProducer, MessagingError, Result};
+
+/// Events older than this are dropped from a task's ring buffer; bounds
+/// per-task memory while still giving a client that reconnects with
+/// `Last-Event-ID` a useful backlog to replay.
+const RING_BUFFER_CAPACITY: usize = 64;
+
+/// Broadcast channel capacity per task. A subscriber that falls this far
+/// behind the live channel just misses the gap - the ring buffer replay on
+/// (re)subscribe is what a client is expected to rely on for continuity,
+/// not channel depth.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// What changed about an in-flight task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskEventPayload {
+    /// A [`super::task_state::TaskState`] transition, named rather than
+    /// typed so this event can outlive a refactor of that enum
+    Status { from: String, to: String },
+    /// A structured log line about the task
+    Log { level: String, message: String },
+    /// Cumulative bytes downloaded so far; `total_bytes` is `None` when the
+    /// origin didn't send a `Content-Length`
+    Progress {
+        bytes_downloaded: u64,
+        total_bytes: Option<u64>,
+    },
+}
+
+/// A [`TaskEventPayload`] tagged with the monotonic id and task identity an
+/// SSE client needs to frame it and resume via `Last-Event-ID`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    /// Monotonically increasing per-task, starting at `1`; never reused
+    pub id: u64,
+    pub seq: u64,
+    pub payload: TaskEventPayload,
+}
+
+struct TaskChannel {
+    sender: broadcast::Sender<TaskEvent>,
+    ring: VecDeque<TaskEvent>,
+    next_id: u64,
+}
+
+impl TaskChannel {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            ring: VecDeque::with_capacity(RING_BUFFER_CAPACITY),
+            next_id: 0,
+        }
+    }
+}
+
+/// Fan-out broadcaster for per-task status/log/progress events, held in
+/// [`crate::api::state::AppState`] and fed by the worker as it processes
+/// each task - see the module docs.
+#[derive(Default)]
+pub struct StatusBroadcaster {
+    channels: Mutex<HashMap<u64, TaskChannel>>,
+}
+
+impl StatusBroadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `payload` against `seq`, assigning it the next id in that
+    /// task's sequence and fanning it out to any live subscribers. Safe to
+    /// call with nobody subscribed yet - the event still lands in the ring
+    /// buffer for whoever subscribes next.
+    pub fn record(&self, seq: u64, payload: TaskEventPayload) {
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.entry(seq).or_insert_with(TaskChannel::new);
+        channel.next_id += 1;
+        let event = TaskEvent {
+            id: channel.next_id,
+            seq,
+            payload,
+        };
+        if channel.ring.len() == RING_BUFFER_CAPACITY {
+            channel.ring.pop_front();
+        }
+        channel.ring.push_back(event.clone());
+        // No subscribers (or all dropped) is not an error - the event is
+        // still retained in the ring buffer above.
+        let _ = channel.sender.send(event);
+    }
+
+    /// Subscribe to `seq`'s live event stream, returning both the
+    /// backlog of retained events with `id > last_event_id` (pass `0` for
+    /// everything still in the ring buffer) and a receiver for events
+    /// published from this point on.
+    pub fn subscribe_from(
+        &self,
+        seq: u64,
+        last_event_id: u64,
+    ) -> (Vec<TaskEvent>, broadcast::Receiver<TaskEvent>) {
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.entry(seq).or_insert_with(TaskChannel::new);
+        let backlog = channel
+            .ring
+            .iter()
+            .filter(|event| event.id > last_event_id)
+            .cloned()
+            .collect();
+        (backlog, channel.sender.subscribe())
+    }
+}
+
+#[async_trait]
+impl MessageProducer for StatusBroadcaster {
+    /// `stream` is `seq` rendered as a string; `message` is `payload`
+    /// JSON-encoded - see [`Self::record`] for the typed equivalent most
+    /// callers want instead.
+    async fn publish(&self, stream: &str, message: Vec<u8>) -> Result<()> {
+        let seq: u64 = stream
+            .parse()
+            .map_err(|e| MessagingError::PublishFailed(format!("invalid task seq: {e}")))?;
+        let payload: TaskEventPayload = serde_json::from_slice(&message)
+            .map_err(|e| MessagingError::PublishFailed(e.to_string()))?;
+        self.record(seq, payload);
+        Ok(())
+    }
+
+    async fn health(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl MessageConsumer for StatusBroadcaster {
+    type Message = TaskEvent;
+
+    async fn subscribe(&self, stream: &str) -> Result<broadcast::Receiver<TaskEvent>> {
+        let seq: u64 = stream
+            .parse()
+            .map_err(|e| MessagingError::ConnectionError(format!("invalid task seq: {e}")))?;
+        Ok(self.subscribe_from(seq, 0).1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_assigns_increasing_ids() {
+        let broadcaster = StatusBroadcaster::new();
+        broadcaster.record(
+            1,
+            TaskEventPayload::Status {
+                from: "Queued".to_string(),
+                to: "Running".to_string(),
+            },
+        );
+        broadcaster.record(
+            1,
+            TaskEventPayload::Status {
+                from: "Running".to_string(),
+                to: "Succeeded".to_string(),
+            },
+        );
+
+        let (backlog, _rx) = broadcaster.subscribe_from(1, 0);
+        assert_eq!(backlog.len(), 2);
+        assert_eq!(backlog[0].id, 1);
+        assert_eq!(backlog[1].id, 2);
+    }
+
+    #[test]
+    fn test_subscribe_from_replays_only_events_after_last_event_id() {
+        let broadcaster = StatusBroadcaster::new();
+        for i in 0..3 {
+            broadcaster.record(
+                1,
+                TaskEventPayload::Log {
+                    level: "info".to_string(),
+                    message: format!("event {i}"),
+                },
+            );
+        }
+
+        let (backlog, _rx) = broadcaster.subscribe_from(1, 1);
+        assert_eq!(backlog.len(), 2);
+        assert_eq!(backlog[0].id, 2);
+        assert_eq!(backlog[1].id, 3);
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest_beyond_capacity() {
+        let broadcaster = StatusBroadcaster::new();
+        for i in 0..(RING_BUFFER_CAPACITY + 5) {
+            broadcaster.record(
+                1,
+                TaskEventPayload::Log {
+                    level: "info".to_string(),
+                    message: format!("event {i}"),
+                },
+            );
+        }
+
+        let (backlog, _rx) = broadcaster.subscribe_from(1, 0);
+        assert_eq!(backlog.len(), RING_BUFFER_CAPACITY);
+        assert_eq!(backlog[0].id, 6);
+    }
+
+    #[tokio::test]
+    async fn test_live_subscriber_receives_events_published_after_subscribe() {
+        let broadcaster = StatusBroadcaster::new();
+        let (backlog, mut rx) = broadcaster.subscribe_from(1, 0);
+        assert!(backlog.is_empty());
+
+        broadcaster.record(
+            1,
+            TaskEventPayload::Progress {
+                bytes_downloaded: 1024,
+                total_bytes: Some(2048),
+            },
+        );
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.id, 1);
+        assert_eq!(event.seq, 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_producer_consumer_round_trip() {
+        let broadcaster = StatusBroadcaster::new();
+        let rx = MessageConsumer::subscribe(&broadcaster, "42").await.unwrap();
+        let mut rx = rx;
+
+        let payload = TaskEventPayload::Log {
+            level: "warn".to_string(),
+            message: "retrying".to_string(),
+        };
+        MessageProducer::publish(&broadcaster, "42", serde_json::to_vec(&payload).unwrap())
+            .await
+            .unwrap();
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.seq, 42);
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-resource task lifecycle state machine
+//!
+//! This is the in-process analogue of the `jobs.status`/`jobs.logs`/`jobs.dlq`
+//! topics the original Iggy-based design stubbed out (see the removed
+//! `setup_iggy_streams` in `tests/e2e.rs`). Since [`crate::worker`] moved to
+//! a single-process architecture (no external broker, see
+//! [`crate::queue::TaskBroker`]'s doc comment), there is no external status
+//! topic to publish to: [`runner::process_task`](super::runner::process_task)
+//! drives tasks through this state machine and emits every transition as a
+//! structured tracing event and as an in-process
+//! [`super::status_stream::StatusBroadcaster`] event instead, while terminal
+//! failures still land in [`crate::queue::FjallQueue`]'s existing
+//! dead-letter partition.
+//!
+//! [`next`] is the single source of truth for legal transitions: callers
+//! validate every move through it rather than mutating state ad hoc.
+
+use thiserror::Error;
+
+/// Lifecycle state of a single resource download task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    DeadLettered,
+}
+
+/// Event driving a [`TaskState`] transition
+#[derive(Debug, Clone)]
+pub enum TaskEvent {
+    /// The worker picked the task up and started the download
+    Started,
+    /// Download + storage upload both succeeded
+    Completed,
+    /// Download or upload failed; carries the failure cause
+    Failed(String),
+    /// The task exhausted its retries and was moved to the dead-letter queue
+    DeadLettered,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("illegal transition: {from:?} -> {event:?}")]
+pub struct InvalidTransition {
+    pub from: TaskState,
+    pub event: TaskEventKind,
+}
+
+/// `TaskEvent` without its payload, just for error messages/equality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskEventKind {
+    Started,
+    Completed,
+    Failed,
+    DeadLettered,
+}
+
+impl From<&TaskEvent> for TaskEventKind {
+    fn from(event: &TaskEvent) -> Self {
+        match event {
+            TaskEvent::Started => TaskEventKind::Started,
+            TaskEvent::Completed => TaskEventKind::Completed,
+            TaskEvent::Failed(_) => TaskEventKind::Failed,
+            TaskEvent::DeadLettered => TaskEventKind::DeadLettered,
+        }
+    }
+}
+
+/// Validate and apply a state transition
+///
+/// `Queued -> Running -> Succeeded | Failed`, with `Failed -> DeadLettered`
+/// once retries are exhausted. Every other combination (e.g.
+/// `Succeeded -> Running`) is rejected.
+pub fn next(current: TaskState, event: &TaskEvent) -> Result<TaskState, InvalidTransition> {
+    use TaskState::*;
+
+    let transitioned = match (current, event) {
+        (Queued, TaskEvent::Started) => Running,
+        (Running, TaskEvent::Completed) => Succeeded,
+        (Running, TaskEvent::Failed(_)) => Failed,
+        (Failed, TaskEvent::DeadLettered) => DeadLettered,
+        _ => {
+            return Err(InvalidTransition {
+                from: current,
+                event: event.into(),
+            });
+        }
+    };
+
+    Ok(transitioned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legal_happy_path() {
+        let state = next(TaskState::Queued, &TaskEvent::Started).unwrap();
+        assert_eq!(state, TaskState::Running);
+
+        let state = next(state, &TaskEvent::Completed).unwrap();
+        assert_eq!(state, TaskState::Succeeded);
+    }
+
+    #[test]
+    fn test_legal_failure_path_to_dead_letter() {
+        let state = next(TaskState::Queued, &TaskEvent::Started).unwrap();
+        let state = next(state, &TaskEvent::Failed("boom".to_string())).unwrap();
+        assert_eq!(state, TaskState::Failed);
+
+        let state = next(state, &TaskEvent::DeadLettered).unwrap();
+        assert_eq!(state, TaskState::DeadLettered);
+    }
+
+    #[test]
+    fn test_illegal_transition_rejected() {
+        let err = next(TaskState::Succeeded, &TaskEvent::Started).unwrap_err();
+        assert_eq!(err.from, TaskState::Succeeded);
+        assert_eq!(err.event, TaskEventKind::Started);
+    }
+
+    #[test]
+    fn test_cannot_skip_running() {
+        let err = next(TaskState::Queued, &TaskEvent::Completed).unwrap_err();
+        assert_eq!(err.from, TaskState::Queued);
+        assert_eq!(err.event, TaskEventKind::Completed);
+    }
+
+    #[test]
+    fn test_cannot_retry_dead_letter() {
+        let err = next(TaskState::DeadLettered, &TaskEvent::Started).unwrap_err();
+        assert_eq!(err.from, TaskState::DeadLettered);
+    }
+}
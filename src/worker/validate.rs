@@ -0,0 +1,545 @@
+//! Post-download content validation and media probing
+//!
+//! Runs after a resource's bytes are in hand but before they're uploaded to
+//! storage: rejects empty/truncated bodies, enforces a per-`job_type`
+//! MIME allow-list, always computes a sha256 content hash so downstream
+//! consumers (e.g. the run manifest written in [`super::runner`]) have
+//! something stable to key on, and sniffs the actual media type from the
+//! body's magic bytes (see [`sniff_mime`]) to catch a resource whose
+//! declared `allowed_mime_types` attribute doesn't match what it actually
+//! downloaded - content-type spoofing or a truncated/corrupt file, not just
+//! an untrusted `Content-Type` header.
+//!
+//! Rules are opt-in per `job_type`, read from that job type's
+//! [`crate::config::HandlerConfig::options`] under a `"validation"` key:
+//!
+//! ```toml
+//! [handlers.gallery.options.validation]
+//! allowed_mime_types = ["image/jpeg", "image/png"]
+//! probe_media = true
+//! ```
+//!
+//! `job_type`s with no `"validation"` block skip MIME/probe checks entirely
+//! (the empty-body and, when a `Content-Length` header is present, the
+//! size-mismatch checks still apply - they're cheap and never a false
+//! positive).
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::HandlerConfig;
+
+#[derive(Debug, Error)]
+pub enum ContentValidationError {
+    #[error("downloaded body is empty")]
+    EmptyBody,
+
+    #[error("body truncated: Content-Length said {expected} bytes, got {actual}")]
+    Truncated { expected: u64, actual: u64 },
+
+    #[error("mime type {0:?} is not in the allowed list for this job type")]
+    DisallowedMimeType(Option<String>),
+
+    /// The manifest's `attributes.allowed_mime_types` declared a set of
+    /// media types for this resource, but the type sniffed from the body's
+    /// magic bytes isn't in it - a mismatch this strict catches that the
+    /// header-based [`ContentValidationError::DisallowedMimeType`] check
+    /// can't: a spoofed `Content-Type` header, or a truncated/corrupt body
+    /// that no longer looks like what it claims to be.
+    #[error("sniffed mime type {sniffed:?} is not in the declared allow-list {declared:?}")]
+    SniffedMimeMismatch {
+        declared: Vec<String>,
+        sniffed: Option<String>,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ContentValidationError>;
+
+/// Per-`job_type` validation rules, parsed from `HandlerConfig.options.validation`
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct ValidationOptions {
+    /// MIME types this job type accepts; empty means "allow any"
+    pub allowed_mime_types: Vec<String>,
+    /// Probe image/video resources for dimensions/duration/codec
+    pub probe_media: bool,
+}
+
+/// Dimensions/duration/codec extracted from an image or video resource
+///
+/// Any field may be `None` - a probe that fails or returns garbage never
+/// fails the task, it just leaves the corresponding fields unset (see
+/// [`probe_media`]).
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct MediaProbe {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_secs: Option<f64>,
+    pub codec: Option<String>,
+}
+
+/// Outcome of a successful [`ContentValidator::validate`] call
+#[derive(Debug, Clone)]
+pub struct ValidationOutcome {
+    pub checksum_sha256: String,
+    pub probe: Option<MediaProbe>,
+    /// The media type sniffed from the body's magic bytes (see
+    /// [`sniff_mime`]), regardless of whether the resource declared an
+    /// allow-list to check it against - `None` if the body didn't match any
+    /// recognized signature
+    pub sniffed_mime: Option<String>,
+}
+
+/// A resource's declared media-type allow-list, parsed from the manifest
+/// `attributes` carried on its `DownloadTask` (see
+/// [`crate::handlers::types::DownloadTask::attributes`])
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct ResourceAttributes {
+    allowed_mime_types: Vec<String>,
+}
+
+/// Parse a resource's declared `allowed_mime_types` out of its manifest
+/// `attributes`, serialized onto the wire as
+/// [`crate::proto::TaskAttributes::extra`]
+///
+/// Absent, non-object, or unparsable `attributes` all quietly mean "no
+/// declared allow-list" rather than a validation failure - this check is
+/// opt-in per resource, same as [`ValidationOptions`] is opt-in per
+/// `job_type`.
+fn declared_mime_types(extra: &[u8]) -> Vec<String> {
+    if extra.is_empty() {
+        return Vec::new();
+    }
+    serde_json::from_slice::<ResourceAttributes>(extra)
+        .map(|attrs| attrs.allowed_mime_types)
+        .unwrap_or_default()
+}
+
+/// Sniff a media type from `bytes`' leading magic number
+///
+/// Deliberately narrow: it covers the handful of binary formats this
+/// service actually fetches (images, video, archives, PDFs) rather than
+/// pulling in a general-purpose signature database - anything else sniffs
+/// as `None`, which [`ContentValidator::validate`] treats as "doesn't match
+/// any declared allow-list" rather than "unknown, so allow it".
+pub fn sniff_mime(bytes: &[u8]) -> Option<String> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\xFF\xD8\xFF", "image/jpeg"),
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+    ];
+
+    for (signature, mime) in SIGNATURES {
+        if bytes.starts_with(signature) {
+            return Some((*mime).to_string());
+        }
+    }
+
+    // WebP/MP4/etc. wrap their signature a few bytes in behind a container
+    // size field, rather than leading the file outright.
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp".to_string());
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return Some("video/mp4".to_string());
+    }
+
+    None
+}
+
+/// Validates downloaded bytes against each `job_type`'s configured rules
+pub struct ContentValidator {
+    rules: HashMap<String, ValidationOptions>,
+}
+
+impl ContentValidator {
+    /// Build from the server's `[handlers.*]` config, picking out whichever
+    /// job types carry a `options.validation` block
+    pub fn new(handlers: &HashMap<String, HandlerConfig>) -> Self {
+        let rules = handlers
+            .iter()
+            .filter_map(|(job_type, config)| {
+                let validation = config.options.get("validation")?;
+                match serde_json::from_value::<ValidationOptions>(validation.clone()) {
+                    Ok(options) => Some((job_type.clone(), options)),
+                    Err(e) => {
+                        warn!(job_type, error = %e, "Ignoring unparsable validation options");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Validate a downloaded body for `job_type` and compute its content hash
+    ///
+    /// `content_type`/`content_length` come from the HTTP response headers
+    /// (see [`super::http::DownloadResponse`]); media probing only runs when
+    /// the body passed every other check. `resource_attributes` is the
+    /// resource's raw `TaskAttributes.extra` bytes, if any - when it
+    /// declares `allowed_mime_types`, the type sniffed from `bytes` itself
+    /// (see [`sniff_mime`]) must be in that list, independent of and in
+    /// addition to `job_type`'s own header-based allow-list.
+    pub async fn validate(
+        &self,
+        job_type: &str,
+        content_type: Option<&str>,
+        content_length: Option<u64>,
+        bytes: &[u8],
+        resource_attributes: Option<&[u8]>,
+    ) -> Result<ValidationOutcome> {
+        if bytes.is_empty() {
+            return Err(ContentValidationError::EmptyBody);
+        }
+
+        if let Some(expected) = content_length {
+            let actual = bytes.len() as u64;
+            if actual != expected {
+                return Err(ContentValidationError::Truncated { expected, actual });
+            }
+        }
+
+        let checksum_sha256 = sha256_hex(bytes);
+        let sniffed_mime = sniff_mime(bytes);
+
+        let declared = resource_attributes.map(declared_mime_types).unwrap_or_default();
+        if !declared.is_empty() {
+            let matches = sniffed_mime
+                .as_deref()
+                .is_some_and(|sniffed| declared.iter().any(|d| sniffed.starts_with(d.as_str())));
+            if !matches {
+                return Err(ContentValidationError::SniffedMimeMismatch {
+                    declared,
+                    sniffed: sniffed_mime,
+                });
+            }
+        }
+
+        let Some(rules) = self.rules.get(job_type) else {
+            return Ok(ValidationOutcome {
+                checksum_sha256,
+                probe: None,
+                sniffed_mime,
+            });
+        };
+
+        self.check_mime(job_type, content_type)?;
+
+        let probe = if rules.probe_media {
+            probe_media(bytes, content_type).await
+        } else {
+            None
+        };
+
+        Ok(ValidationOutcome {
+            checksum_sha256,
+            probe,
+            sniffed_mime,
+        })
+    }
+
+    /// Check `content_type` against `job_type`'s `allowed_mime_types`, if any
+    ///
+    /// Split out of [`validate`](Self::validate) so the streaming upload path
+    /// in [`super::runner::download_and_store`] can apply the same MIME rule
+    /// without the full body in hand - unlike the checksum, a MIME check only
+    /// needs the `Content-Type` header, never the bytes themselves.
+    pub fn check_mime(&self, job_type: &str, content_type: Option<&str>) -> Result<()> {
+        let Some(rules) = self.rules.get(job_type) else {
+            return Ok(());
+        };
+
+        if rules.allowed_mime_types.is_empty() {
+            return Ok(());
+        }
+
+        let allowed = content_type
+            .map(|ct| {
+                rules
+                    .allowed_mime_types
+                    .iter()
+                    .any(|allowed| ct.starts_with(allowed.as_str()))
+            })
+            .unwrap_or(false);
+        if !allowed {
+            return Err(ContentValidationError::DisallowedMimeType(
+                content_type.map(|s| s.to_string()),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Incremental sha256 hasher for the streaming upload path
+///
+/// [`ContentValidator::validate`] hashes a complete in-memory body; a
+/// response large enough to stream straight into storage (see
+/// [`super::runner::download_and_store`]) never holds the full body at
+/// once, so the checksum is folded in as each chunk arrives instead.
+#[derive(Default)]
+pub struct StreamingChecksum(Sha256);
+
+impl StreamingChecksum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.0.update(chunk);
+    }
+
+    pub fn finalize_hex(self) -> String {
+        hex::encode(self.0.finalize())
+    }
+}
+
+/// sha256 of `bytes`, hex-encoded
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Shell out to `ffprobe` for dimensions/duration/codec
+///
+/// Any failure - missing binary, non-zero exit, unparsable JSON - is logged
+/// and treated as "no metadata available" rather than a task failure; the
+/// resource still downloads and stores normally.
+async fn probe_media(bytes: &[u8], content_type: Option<&str>) -> Option<MediaProbe> {
+    let suffix = content_type
+        .and_then(|ct| ct.split('/').nth(1))
+        .unwrap_or("bin");
+    let mut tmp = std::env::temp_dir();
+    tmp.push(format!("fetchbox-probe-{}.{suffix}", uuid::Uuid::new_v4()));
+
+    if let Err(e) = tokio::fs::write(&tmp, bytes).await {
+        warn!(error = %e, "Failed to write temp file for media probe");
+        return None;
+    }
+
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(&tmp)
+        .output()
+        .await;
+
+    let _ = tokio::fs::remove_file(&tmp).await;
+
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(status = ?output.status, "ffprobe exited non-zero, skipping media probe");
+            return None;
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to spawn ffprobe, skipping media probe");
+            return None;
+        }
+    };
+
+    parse_ffprobe_json(&output.stdout)
+}
+
+fn parse_ffprobe_json(stdout: &[u8]) -> Option<MediaProbe> {
+    let value: serde_json::Value = serde_json::from_slice(stdout).ok()?;
+    let stream = value.get("streams")?.as_array()?.first()?;
+
+    Some(MediaProbe {
+        width: stream.get("width").and_then(|v| v.as_u64()).map(|v| v as u32),
+        height: stream.get("height").and_then(|v| v.as_u64()).map(|v| v as u32),
+        duration_secs: value
+            .get("format")
+            .and_then(|f| f.get("duration"))
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<f64>().ok()),
+        codec: stream
+            .get("codec_name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn handler_config(options: serde_json::Value) -> HandlerConfig {
+        HandlerConfig {
+            handler: "fetchbox::handlers::DefaultHandler".to_string(),
+            storage_bucket: None,
+            default_headers: Default::default(),
+            options,
+            proxy_pool: None,
+            notify: None,
+            wasm_module: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_empty_body() {
+        let validator = ContentValidator::new(&HashMap::new());
+        let result = validator.validate("default", None, None, &[], None).await;
+        assert!(matches!(result, Err(ContentValidationError::EmptyBody)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_truncated_body() {
+        let validator = ContentValidator::new(&HashMap::new());
+        let result = validator
+            .validate("default", None, Some(10), b"short", None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ContentValidationError::Truncated { expected: 10, actual: 5 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_computes_checksum_with_no_rules() {
+        let validator = ContentValidator::new(&HashMap::new());
+        let outcome = validator
+            .validate("default", None, None, b"hello world", None)
+            .await
+            .unwrap();
+        assert_eq!(outcome.checksum_sha256, sha256_hex(b"hello world"));
+        assert!(outcome.probe.is_none());
+        assert!(outcome.sniffed_mime.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_validate_enforces_mime_allow_list() {
+        let mut handlers = HashMap::new();
+        handlers.insert(
+            "gallery".to_string(),
+            handler_config(json!({"validation": {"allowed_mime_types": ["image/"]}})),
+        );
+        let validator = ContentValidator::new(&handlers);
+
+        let ok = validator
+            .validate("gallery", Some("image/jpeg"), None, b"fake-jpeg-bytes", None)
+            .await;
+        assert!(ok.is_ok());
+
+        let rejected = validator
+            .validate("gallery", Some("text/html"), None, b"<html>", None)
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(ContentValidationError::DisallowedMimeType(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_untouched_job_types_skip_mime_check() {
+        let mut handlers = HashMap::new();
+        handlers.insert(
+            "gallery".to_string(),
+            handler_config(json!({"validation": {"allowed_mime_types": ["image/"]}})),
+        );
+        let validator = ContentValidator::new(&handlers);
+
+        let result = validator
+            .validate("default", Some("text/html"), None, b"<html>", None)
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_accepts_sniffed_mime_in_declared_allow_list() {
+        let validator = ContentValidator::new(&HashMap::new());
+        let png_bytes = b"\x89PNG\r\n\x1a\nrest-of-file";
+        let attributes = json!({"allowed_mime_types": ["image/png"]}).to_string();
+
+        let outcome = validator
+            .validate(
+                "default",
+                None,
+                None,
+                png_bytes,
+                Some(attributes.as_bytes()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.sniffed_mime.as_deref(), Some("image/png"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_sniffed_mime_outside_declared_allow_list() {
+        let validator = ContentValidator::new(&HashMap::new());
+        let html_bytes = b"<html>pretending to be a jpeg</html>";
+        let attributes = json!({"allowed_mime_types": ["image/jpeg"]}).to_string();
+
+        let result = validator
+            .validate(
+                "default",
+                None,
+                None,
+                html_bytes,
+                Some(attributes.as_bytes()),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(ContentValidationError::SniffedMimeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sniff_mime_recognizes_common_signatures() {
+        assert_eq!(sniff_mime(b"\xFF\xD8\xFFrest"), Some("image/jpeg".to_string()));
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\nrest"), Some("image/png".to_string()));
+        assert_eq!(sniff_mime(b"%PDF-1.4"), Some("application/pdf".to_string()));
+        assert_eq!(sniff_mime(b"not a recognized format"), None);
+    }
+
+    #[test]
+    fn test_ignores_unparsable_validation_options() {
+        let mut handlers = HashMap::new();
+        handlers.insert(
+            "gallery".to_string(),
+            handler_config(json!({"validation": {"allowed_mime_types": "not-a-list"}})),
+        );
+        let validator = ContentValidator::new(&handlers);
+        assert!(!validator.rules.contains_key("gallery"));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_json() {
+        let stdout = json!({
+            "streams": [{"width": 1920, "height": 1080, "codec_name": "h264"}],
+            "format": {"duration": "12.5"}
+        })
+        .to_string();
+
+        let probe = parse_ffprobe_json(stdout.as_bytes()).unwrap();
+        assert_eq!(probe.width, Some(1920));
+        assert_eq!(probe.height, Some(1080));
+        assert_eq!(probe.codec, Some("h264".to_string()));
+        assert_eq!(probe.duration_secs, Some(12.5));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_json_garbage_returns_none() {
+        assert!(parse_ffprobe_json(b"not json").is_none());
+        assert!(parse_ffprobe_json(b"{}").is_none());
+    }
+}
@@ -15,6 +15,7 @@ use fetchbox::handlers::HandlerRegistry;
 use fetchbox::ledger::FjallStore;
 use fetchbox::queue::{FjallQueue, TaskBroker};
 use fetchbox::storage::StorageClient;
+use fetchbox::worker::status_stream::StatusBroadcaster;
 use tokio::sync::RwLock;
 
 /// Creates a minimal config for testing
@@ -70,22 +71,74 @@ async fn build_test_app() -> (Router, TempDir) {
     let registry = HandlerRegistry::with_defaults();
 
     // Create app state
-    let state = AppState::new(config, registry, store, storage, broker);
-
-    // Build router with all routes and middleware
-    let app = Router::new()
+    let metrics = Arc::new(fetchbox::observability::Metrics::new());
+    let config_handle = fetchbox::config::ConfigHandle::new(config.clone());
+    let status_broadcaster = Arc::new(StatusBroadcaster::new());
+    let scheduler = Arc::new(
+        fetchbox::queue::Scheduler::load(store.clone(), broker.clone())
+            .expect("Failed to load test scheduler"),
+    );
+    let state = AppState::new(
+        config,
+        config_handle,
+        registry,
+        store,
+        storage,
+        broker,
+        metrics,
+        status_broadcaster,
+        scheduler,
+    );
+
+    // Build router with all routes and middleware, mirroring `api::server::run`
+    let authenticated = Router::new()
         .route(
             "/jobs",
             axum::routing::post(fetchbox::api::services::ingest_job),
         )
+        .route(
+            "/operators/jobs",
+            axum::routing::get(fetchbox::api::services::list_jobs),
+        )
         .route(
             "/operators/jobs/{job_id}",
             axum::routing::get(fetchbox::api::services::get_job),
         )
+        .route(
+            "/operators/jobs/{job_id}/resources",
+            axum::routing::get(fetchbox::api::services::get_manifest_resources),
+        )
+        .route(
+            "/operators/jobs/{job_id}/events",
+            axum::routing::get(fetchbox::api::services::job_events),
+        )
+        .route(
+            "/operators/jobs/{job_id}/deadletters",
+            axum::routing::get(fetchbox::api::services::list_deadletters),
+        )
+        .route(
+            "/operators/jobs/{job_id}/deadletters/{resource_id}/replay",
+            axum::routing::post(fetchbox::api::services::replay_deadletter),
+        )
+        .route(
+            "/operators/deadletters/replay",
+            axum::routing::post(fetchbox::api::services::replay_dlq_all),
+        )
+        .route(
+            "/operators/deadletters/{seq}/replay",
+            axum::routing::post(fetchbox::api::services::replay_dlq),
+        )
         .route(
             "/operators/health",
             axum::routing::get(fetchbox::api::services::health),
         )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            fetchbox::api::auth::hmac_auth,
+        ));
+
+    let app = Router::new()
+        .merge(authenticated)
         .route(
             "/health",
             axum::routing::get(fetchbox::api::services::health),
@@ -96,6 +149,104 @@ async fn build_test_app() -> (Router, TempDir) {
     (app, temp_dir)
 }
 
+/// Creates a test config with a single tenant's HMAC secret configured, so
+/// the auth middleware enforces signatures instead of passing requests
+/// through unchecked.
+fn create_authenticated_test_config() -> Config {
+    let config_toml = r#"
+[server]
+host = "127.0.0.1"
+port = 8080
+fjall_path = "/tmp/test.fjall"
+
+[storage]
+provider = "s3"
+bucket = "test-bucket"
+region = "us-east-1"
+
+[handlers.default]
+handler = "default"
+
+[auth.tenants.test-tenant]
+secret = "test-secret"
+    "#;
+
+    toml::from_str(config_toml).expect("Failed to parse authenticated test config")
+}
+
+async fn build_authenticated_test_app() -> (Router, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let store_path = temp_dir.path().join("test.fjall");
+    let queue_path = temp_dir.path().join("queue.fjall");
+
+    let store = FjallStore::open(store_path.to_str().unwrap())
+        .expect("Failed to open test Fjall store");
+    let storage = StorageClient::in_memory();
+    let queue = Arc::new(RwLock::new(
+        FjallQueue::open(&queue_path).expect("Failed to open test queue"),
+    ));
+    let (broker, _worker_receivers, _ack_tx) =
+        TaskBroker::new(queue, 4, 100, std::time::Duration::from_secs(30), 3);
+    let broker = Arc::new(broker);
+
+    let config = create_authenticated_test_config();
+    let registry = HandlerRegistry::with_defaults();
+    let metrics = Arc::new(fetchbox::observability::Metrics::new());
+    let config_handle = fetchbox::config::ConfigHandle::new(config.clone());
+    let status_broadcaster = Arc::new(StatusBroadcaster::new());
+    let scheduler = Arc::new(
+        fetchbox::queue::Scheduler::load(store.clone(), broker.clone())
+            .expect("Failed to load test scheduler"),
+    );
+    let state = AppState::new(
+        config,
+        config_handle,
+        registry,
+        store,
+        storage,
+        broker,
+        metrics,
+        status_broadcaster,
+        scheduler,
+    );
+
+    let authenticated = Router::new()
+        .route(
+            "/jobs",
+            axum::routing::post(fetchbox::api::services::ingest_job),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            fetchbox::api::auth::hmac_auth,
+        ));
+
+    let app = Router::new()
+        .merge(authenticated)
+        .with_state(state)
+        .layer(tower_http::decompression::RequestDecompressionLayer::new());
+
+    (app, temp_dir)
+}
+
+/// Signs a request body with the given tenant secret, returning the headers
+/// to attach (timestamp + signature) alongside `X-Fetchbox-Tenant`.
+fn sign_test_request(secret: &str, method: &str, path: &str, body: &str) -> (String, String) {
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256};
+
+    type HmacSha256 = Hmac<Sha256>;
+
+    let timestamp = chrono::Utc::now().timestamp().to_string();
+    let body_hash = hex::encode(Sha256::digest(body.as_bytes()));
+    let canonical = format!("{}\n{}\n{}\n{}", method, path, body_hash, timestamp);
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(canonical.as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    (timestamp, signature)
+}
+
 /// Creates a valid test manifest
 fn valid_manifest() -> serde_json::Value {
     json!({
@@ -139,6 +290,7 @@ fn post_job_request(manifest: serde_json::Value) -> Request<Body> {
         .method("POST")
         .header(header::CONTENT_TYPE, "application/json")
         .header("X-Fetchbox-Tenant", "test-tenant")
+        .header("X-Fetchbox-Job-Type", "default")
         .body(Body::from(serde_json::to_string(&manifest).unwrap()))
         .unwrap()
 }
@@ -177,6 +329,7 @@ async fn test_ingest_job_idempotency() {
         .method("POST")
         .header(header::CONTENT_TYPE, "application/json")
         .header("X-Fetchbox-Tenant", "test-tenant")
+        .header("X-Fetchbox-Job-Type", "default")
         .header("X-Fetchbox-Idempotency-Key", "test-key-123")
         .body(Body::from(serde_json::to_string(&manifest).unwrap()))
         .unwrap();
@@ -195,6 +348,7 @@ async fn test_ingest_job_idempotency() {
         .method("POST")
         .header(header::CONTENT_TYPE, "application/json")
         .header("X-Fetchbox-Tenant", "test-tenant")
+        .header("X-Fetchbox-Job-Type", "default")
         .header("X-Fetchbox-Idempotency-Key", "test-key-123")
         .body(Body::from(serde_json::to_string(&manifest).unwrap()))
         .unwrap();
@@ -327,6 +481,180 @@ async fn test_get_job_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_get_manifest_resources_success() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let ingest_request = post_job_request(valid_manifest());
+    let ingest_response = ServiceExt::<Request<Body>>::oneshot(app.clone(), ingest_request).await.unwrap();
+    let ingest_body = axum::body::to_bytes(ingest_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let job: JobAcceptedResponse = serde_json::from_slice(&ingest_body).unwrap();
+
+    let request = Request::builder()
+        .uri(format!("/operators/jobs/{}/resources", job.job_id))
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let resources: Vec<fetchbox::api::models::Resource> = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(resources.len(), 2);
+    assert_eq!(resources[0].name, "resource1.txt");
+}
+
+#[tokio::test]
+async fn test_get_manifest_resources_not_found_for_unknown_job() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/jobs/nonexistent-job-id/resources")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_jobs_paginates_and_filters_by_tenant() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    for _ in 0..3 {
+        let request = post_job_request(valid_manifest());
+        let response = ServiceExt::<Request<Body>>::oneshot(app.clone(), request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    let list_request = Request::builder()
+        .uri("/operators/jobs?limit=2")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+    let list_response = ServiceExt::<Request<Body>>::oneshot(app.clone(), list_request).await.unwrap();
+    assert_eq!(list_response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(list_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let page: fetchbox::api::models::JobListResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.jobs.len(), 2);
+    assert!(page.next_cursor.is_some());
+
+    let no_match_request = Request::builder()
+        .uri("/operators/jobs?tenant=no-such-tenant")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+    let no_match_response = app.oneshot(no_match_request).await.unwrap();
+    assert_eq!(no_match_response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(no_match_response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let page: fetchbox::api::models::JobListResponse = serde_json::from_slice(&body).unwrap();
+    assert!(page.jobs.is_empty());
+    assert!(page.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn test_job_events_not_found_for_unknown_job() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/jobs/nonexistent-job-id/events")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_deadletters_empty_for_unknown_job() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/jobs/nonexistent-job-id/deadletters")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let entries: Vec<fetchbox::api::models::DeadLetterEntry> =
+        serde_json::from_slice(&body).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[tokio::test]
+async fn test_replay_deadletter_not_found() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/jobs/job-1/deadletters/res-1/replay")
+        .method("POST")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_replay_dlq_not_found() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/deadletters/42/replay")
+        .method("POST")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_replay_dlq_all_empty_when_nothing_matches() {
+    let (app, _temp_dir) = build_test_app().await;
+
+    let request = Request::builder()
+        .uri("/operators/deadletters/replay?failure_code=NETWORK_ERROR")
+        .method("POST")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let parsed: fetchbox::api::models::ReplayDlqAllResponse =
+        serde_json::from_slice(&body).unwrap();
+    assert!(parsed.seqs.is_empty());
+}
+
 #[tokio::test]
 async fn test_health_endpoint() {
     let (app, _temp_dir) = build_test_app().await;
@@ -360,3 +688,89 @@ async fn test_health_endpoint() {
     assert!(components.contains_key("task_broker"));
     assert!(components.contains_key("storage"));
 }
+
+#[tokio::test]
+async fn test_ingest_job_with_valid_signature_succeeds() {
+    let (app, _temp_dir) = build_authenticated_test_app().await;
+
+    let body = serde_json::to_string(&valid_manifest()).unwrap();
+    let (timestamp, signature) = sign_test_request("test-secret", "POST", "/jobs", &body);
+
+    let request = Request::builder()
+        .uri("/jobs")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Fetchbox-Tenant", "test-tenant")
+        .header("X-Fetchbox-Job-Type", "default")
+        .header("X-Fetchbox-Timestamp", timestamp)
+        .header("X-Fetchbox-Signature", signature)
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_ingest_job_missing_signature_is_unauthorized() {
+    let (app, _temp_dir) = build_authenticated_test_app().await;
+
+    let body = serde_json::to_string(&valid_manifest()).unwrap();
+
+    let request = Request::builder()
+        .uri("/jobs")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Fetchbox-Tenant", "test-tenant")
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_ingest_job_invalid_signature_is_unauthorized() {
+    let (app, _temp_dir) = build_authenticated_test_app().await;
+
+    let body = serde_json::to_string(&valid_manifest()).unwrap();
+    let (timestamp, _) = sign_test_request("test-secret", "POST", "/jobs", &body);
+
+    let request = Request::builder()
+        .uri("/jobs")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Fetchbox-Tenant", "test-tenant")
+        .header("X-Fetchbox-Timestamp", timestamp)
+        .header("X-Fetchbox-Signature", "0000000000000000000000000000000000000000000000000000000000000000")
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_ingest_job_unknown_tenant_is_unauthorized() {
+    let (app, _temp_dir) = build_authenticated_test_app().await;
+
+    let body = serde_json::to_string(&valid_manifest()).unwrap();
+    let (timestamp, signature) = sign_test_request("test-secret", "POST", "/jobs", &body);
+
+    let request = Request::builder()
+        .uri("/jobs")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Fetchbox-Tenant", "other-tenant")
+        .header("X-Fetchbox-Timestamp", timestamp)
+        .header("X-Fetchbox-Signature", signature)
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}